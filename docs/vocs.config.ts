@@ -65,6 +65,14 @@ export default defineConfig({
         { text: 'Browser', link: '/tools/browser' },
         { text: 'View', link: '/tools/view' },
         { text: 'Cue points', link: '/tools/cue-points' },
+        { text: 'Modulation', link: '/tools/modulation' },
+        { text: 'Macros', link: '/tools/macros' },
+        { text: 'Presets', link: '/tools/presets' },
+        { text: 'Sound Design', link: '/tools/sound-design' },
+        { text: 'Mixer', link: '/tools/mixer' },
+        { text: 'Track Templates', link: '/tools/templates' },
+        { text: 'Recording', link: '/tools/recording' },
+        { text: 'Scheduler', link: '/tools/scheduler' },
       ],
     },
     {
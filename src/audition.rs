@@ -0,0 +1,272 @@
+//! Sample audition: preview a list of browser items one after another.
+//!
+//! Each candidate is previewed for a fixed number of beats at the song tempo.
+//! When the song is playing, playback starts on the next multiple of that
+//! length in song beats, so previews line up with the arrangement.
+//!
+//! Previews use `/live/browser/preview_sample [name]` with the item's name,
+//! and playback ends with `/live/browser/stop_preview`, also when an
+//! audition is stopped early.
+
+use std::collections::BTreeSet;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use rosc::OscType;
+use serde::Serialize;
+use tokio::task::JoinHandle;
+use tracing::debug;
+
+use crate::browser_index::BrowserItem;
+use crate::error::Error;
+use crate::osc::OscHandle;
+
+/// Beats to wait before the first preview so playback starts on a boundary
+/// of `length` song beats. Starts immediately when the song is stopped.
+pub fn lead_in_beats(song_beat: f64, length: f64, playing: bool) -> f64 {
+    if !playing || length <= 0.0 {
+        return 0.0;
+    }
+    let into = song_beat.rem_euclid(length);
+    if into < 1e-6 { 0.0 } else { length - into }
+}
+
+/// Index of the candidate playing `elapsed` beats after the first preview
+/// started, or `None` before the start or after the last one.
+pub fn playing_index(elapsed: f64, length: f64, count: usize) -> Option<usize> {
+    if elapsed < 0.0 || length <= 0.0 {
+        return None;
+    }
+    let index = (elapsed / length).floor() as usize;
+    (index < count).then_some(index)
+}
+
+/// A candidate in the audition list.
+#[derive(Debug, Clone, Serialize)]
+pub struct Candidate {
+    /// Position in the list, used by `mark_sample`.
+    pub index: usize,
+    pub name: String,
+    pub path: String,
+    /// Whether the user marked it as a keeper.
+    pub marked: bool,
+}
+
+/// State of the audition, returned by `get_audition`.
+#[derive(Debug, Clone, Serialize)]
+pub struct AuditionStatus {
+    /// Whether previews are still running.
+    pub playing: bool,
+    /// Candidate being previewed now.
+    pub current: Option<usize>,
+    /// Beats each candidate plays for.
+    pub beats: f32,
+    /// Tempo used to convert beats to time.
+    pub tempo: f32,
+    pub candidates: Vec<Candidate>,
+}
+
+/// An audition list and its playback task.
+struct Session {
+    items: Vec<BrowserItem>,
+    marked: BTreeSet<usize>,
+    beats: f32,
+    tempo: f32,
+    /// When the first preview starts.
+    start: Instant,
+    task: JoinHandle<()>,
+    /// Connection the previews are sent on, used to stop playback.
+    osc: OscHandle,
+}
+
+impl Session {
+    /// Candidate being previewed now.
+    fn current(&self) -> Option<usize> {
+        if self.task.is_finished() {
+            return None;
+        }
+        let now = Instant::now();
+        if now < self.start {
+            return None;
+        }
+        let elapsed = (now - self.start).as_secs_f64() * f64::from(self.tempo) / 60.0;
+        playing_index(elapsed, f64::from(self.beats), self.items.len())
+    }
+
+    fn candidate(&self, index: usize) -> Candidate {
+        let item = &self.items[index];
+        Candidate {
+            index,
+            name: item.name.clone(),
+            path: item.path.clone(),
+            marked: self.marked.contains(&index),
+        }
+    }
+
+    fn status(&self) -> AuditionStatus {
+        AuditionStatus {
+            playing: !self.task.is_finished(),
+            current: self.current(),
+            beats: self.beats,
+            tempo: self.tempo,
+            candidates: (0..self.items.len()).map(|i| self.candidate(i)).collect(),
+        }
+    }
+}
+
+/// Owner of the current audition.
+///
+/// Only one audition runs at a time; starting a new one replaces the old
+/// list and its marks. Cloning shares the same audition.
+#[derive(Clone, Default)]
+pub struct Auditioner {
+    session: Arc<Mutex<Option<Session>>>,
+}
+
+impl Auditioner {
+    /// Create an auditioner with no audition.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start previewing `items`, `beats` each at `tempo` BPM, after
+    /// `lead_in` beats.
+    pub fn start(
+        &self,
+        osc: OscHandle,
+        items: Vec<BrowserItem>,
+        beats: f32,
+        tempo: f32,
+        lead_in: f64,
+    ) -> AuditionStatus {
+        let mut session = self.session.lock().unwrap();
+        if let Some(old) = session.take() {
+            old.task.abort();
+        }
+
+        let start = Instant::now() + Duration::from_secs_f64(lead_in * 60.0 / f64::from(tempo));
+        let period = Duration::from_secs_f64(f64::from(beats) * 60.0 / f64::from(tempo));
+        let task = tokio::spawn(Self::run(osc.clone(), items.clone(), start, period));
+        let new = Session {
+            items,
+            marked: BTreeSet::new(),
+            beats,
+            tempo,
+            start,
+            task,
+            osc,
+        };
+        let status = new.status();
+        *session = Some(new);
+        status
+    }
+
+    /// Preview each item in turn, then stop the preview.
+    async fn run(osc: OscHandle, items: Vec<BrowserItem>, start: Instant, period: Duration) {
+        for (i, item) in items.iter().enumerate() {
+            tokio::time::sleep_until((start + period * i as u32).into()).await;
+            let args = vec![OscType::String(item.name.clone())];
+            if let Err(e) = osc.send("/live/browser/preview_sample", args).await {
+                debug!(path = %item.path, error = %e, "Audition preview failed");
+            }
+        }
+        tokio::time::sleep_until((start + period * items.len() as u32).into()).await;
+        if let Err(e) = osc.send("/live/browser/stop_preview", vec![]).await {
+            debug!(error = %e, "Could not stop audition preview");
+        }
+        debug!(count = items.len(), "Audition finished");
+    }
+
+    /// Current audition, if one was started.
+    pub fn status(&self) -> Option<AuditionStatus> {
+        self.session.lock().unwrap().as_ref().map(Session::status)
+    }
+
+    /// Stop playback, keeping the list and marks.
+    ///
+    /// The task is aborted before `stop_preview` is sent, so no later
+    /// candidate can start after it. Returns `false` if nothing was playing.
+    pub async fn stop(&self) -> bool {
+        let osc = {
+            let session = self.session.lock().unwrap();
+            match session.as_ref() {
+                Some(session) if !session.task.is_finished() => {
+                    session.task.abort();
+                    session.osc.clone()
+                }
+                _ => return false,
+            }
+        };
+        if let Err(e) = osc.send("/live/browser/stop_preview", vec![]).await {
+            debug!(error = %e, "Could not stop audition preview");
+        }
+        true
+    }
+
+    /// Mark or unmark a candidate; `None` means the one playing now.
+    pub fn mark(&self, index: Option<usize>, keep: bool) -> Result<Candidate, Error> {
+        let mut session = self.session.lock().unwrap();
+        let session = session
+            .as_mut()
+            .ok_or_else(|| Error::InvalidParameter("No audition has been started".to_string()))?;
+        let index = match index {
+            Some(index) if index < session.items.len() => index,
+            Some(index) => {
+                return Err(Error::InvalidParameter(format!(
+                    "Candidate {index} is out of range (0-{})",
+                    session.items.len() - 1
+                )));
+            }
+            None => session.current().ok_or_else(|| {
+                Error::InvalidParameter(
+                    "Nothing is playing; give the candidate index to mark".to_string(),
+                )
+            })?,
+        };
+        if keep {
+            session.marked.insert(index);
+        } else {
+            session.marked.remove(&index);
+        }
+        Ok(session.candidate(index))
+    }
+
+    /// Marked items, in list order.
+    pub fn marked(&self) -> Vec<BrowserItem> {
+        self.session
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map_or_else(Vec::new, |session| {
+                session
+                    .marked
+                    .iter()
+                    .map(|&i| session.items[i].clone())
+                    .collect()
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Playback waits for the next boundary only while the song plays.
+    #[test]
+    fn lead_in_aligns_to_length() {
+        assert!(lead_in_beats(5.0, 4.0, false).abs() < 1e-9);
+        assert!((lead_in_beats(5.0, 4.0, true) - 3.0).abs() < 1e-9);
+        assert!(lead_in_beats(8.0, 4.0, true).abs() < 1e-9);
+        assert!((lead_in_beats(0.5, 2.0, true) - 1.5).abs() < 1e-9);
+    }
+
+    /// Each candidate owns one length of beats.
+    #[test]
+    fn playing_index_follows_elapsed_beats() {
+        assert_eq!(playing_index(-0.1, 4.0, 3), None);
+        assert_eq!(playing_index(0.0, 4.0, 3), Some(0));
+        assert_eq!(playing_index(7.9, 4.0, 3), Some(1));
+        assert_eq!(playing_index(11.9, 4.0, 3), Some(2));
+        assert_eq!(playing_index(12.0, 4.0, 3), None);
+    }
+}
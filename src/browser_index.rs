@@ -0,0 +1,756 @@
+//! Browser index: a local, searchable copy of Live's browser tree.
+//!
+//! The index is built by crawling the browser in a background task and is
+//! cached on disk, so searches are answered locally without round-trips to
+//! Live. A cache older than [`MAX_AGE`] is still served while a fresh crawl
+//! runs.
+//!
+//! The crawl walks each category with `/live/browser/browse [category]` for
+//! the root and `/live/browser/browse_path [category, path]` below it, both
+//! of which reply with the names of a folder's children. Names with a file
+//! extension are loadable files. Other names are browsed in turn: those with
+//! children are folders, those without are loadable items such as devices
+//! and plugins. The top level of the device categories lists the devices
+//! themselves, which are loadable and hold their presets.
+//!
+//! If Live does not answer, the failure is kept and no automatic crawl is
+//! started again until a backoff (doubling with each failure) has passed.
+//!
+//! The crawler uses its own [`OscHandle`], so its queries arrive on a separate
+//! socket and never steal responses from tool calls.
+
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use rosc::OscType;
+use serde::{Deserialize, Serialize};
+use tokio::task::JoinHandle;
+use tracing::{debug, info};
+
+use crate::config::cache_dir;
+use crate::error::Error;
+use crate::osc::OscHandle;
+
+/// Top-level browser categories that are crawled.
+pub const CATEGORIES: &[&str] = &[
+    "sounds",
+    "drums",
+    "instruments",
+    "audio_effects",
+    "midi_effects",
+    "max_for_live",
+    "plugins",
+    "clips",
+    "samples",
+    "packs",
+    "user_library",
+];
+
+/// Age after which the cached index is refreshed.
+pub const MAX_AGE: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Deepest folder level crawled below a category.
+const MAX_DEPTH: usize = 8;
+
+/// Upper bound on indexed items, to keep huge sample libraries in check.
+const MAX_ITEMS: usize = 200_000;
+
+/// Cache file format version; older caches are ignored.
+const CACHE_VERSION: u32 = 2;
+
+/// Categories whose top-level items are devices.
+const DEVICE_CATEGORIES: &[&str] = &["instruments", "audio_effects", "midi_effects"];
+
+/// Categories whose items without children load as devices.
+const DEVICE_LEAF_CATEGORIES: &[&str] =
+    &["instruments", "audio_effects", "midi_effects", "plugins"];
+
+/// File extensions of presets, racks and Max devices, which load as devices.
+const DEVICE_EXTENSIONS: &[&str] = &["adv", "adg", "amxd"];
+
+/// Wait before retrying a crawl after the first failure.
+const RETRY_AFTER: Duration = Duration::from_secs(30);
+
+/// Longest wait between crawl retries.
+const MAX_RETRY_AFTER: Duration = Duration::from_secs(15 * 60);
+
+/// An item in Live's browser.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BrowserItem {
+    /// Display name.
+    pub name: String,
+    /// Full path including the category, e.g. `sounds/Pad/Warm Analog Pad.adv`.
+    pub path: String,
+    /// Top-level category.
+    pub category: String,
+    /// Whether the item can be loaded.
+    pub loadable: bool,
+    /// Whether loading the item creates a device.
+    pub is_device: bool,
+    /// Whether the item is a folder.
+    pub is_folder: bool,
+}
+
+/// Cache file contents.
+#[derive(Serialize, Deserialize)]
+struct CacheFile {
+    version: u32,
+    /// Crawl time in seconds since the Unix epoch.
+    crawled_at: u64,
+    items: Vec<BrowserItem>,
+}
+
+/// Optional restrictions on search results.
+#[derive(Debug, Clone, Default)]
+pub struct SearchFilter {
+    /// Only items in this category.
+    pub category: Option<String>,
+    /// Only loadable items.
+    pub loadable_only: bool,
+    /// Only items that create a device.
+    pub devices_only: bool,
+}
+
+impl SearchFilter {
+    fn allows(&self, item: &BrowserItem) -> bool {
+        self.category
+            .as_ref()
+            .is_none_or(|c| item.category.eq_ignore_ascii_case(c))
+            && (!self.loadable_only || item.loadable)
+            && (!self.devices_only || item.is_device)
+    }
+}
+
+/// Lowercase words of a name, split on anything that is not a letter or digit.
+fn words(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Relevance of an item for the query words, or `None` if a word is missing.
+///
+/// Every query word must match the item's name or one of its folders. Name
+/// matches count more than folder matches, whole words more than prefixes,
+/// and a name containing the whole query gets a bonus.
+fn score(item: &BrowserItem, query: &[String]) -> Option<u32> {
+    let name_words = words(&item.name);
+    let name = name_words.concat();
+    let folder_words = words(&item.path);
+
+    let mut total = 0;
+    for word in query {
+        total += if name_words.iter().any(|w| w == word) {
+            30
+        } else if name_words.iter().any(|w| w.starts_with(word.as_str())) {
+            20
+        } else if name.contains(word.as_str()) {
+            10
+        } else if folder_words.iter().any(|w| w == word) {
+            8
+        } else if folder_words.iter().any(|w| w.starts_with(word.as_str())) {
+            5
+        } else {
+            return None;
+        };
+    }
+    if name.contains(&query.concat()) {
+        total += 25;
+    }
+    if item.loadable {
+        total += 2;
+    }
+    Some(total)
+}
+
+/// Rank items against a query and return the best `limit` matches.
+///
+/// Ties go to shorter names, then alphabetical order.
+pub fn search<'a>(
+    items: &'a [BrowserItem],
+    query: &str,
+    filter: &SearchFilter,
+    limit: usize,
+) -> Vec<&'a BrowserItem> {
+    let query = words(query);
+    if query.is_empty() {
+        return Vec::new();
+    }
+    let mut hits: Vec<(u32, &BrowserItem)> = items
+        .iter()
+        .filter(|item| filter.allows(item))
+        .filter_map(|item| score(item, &query).map(|s| (s, item)))
+        .collect();
+    hits.sort_by(|(sa, a), (sb, b)| {
+        sb.cmp(sa)
+            .then(a.name.len().cmp(&b.name.len()))
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    hits.into_iter().take(limit).map(|(_, item)| item).collect()
+}
+
+/// Items below a folder (at any depth), in browser order.
+///
+/// `folder` is a full path such as `samples/Drums/Kicks`; case is ignored.
+pub fn within<'a>(
+    items: &'a [BrowserItem],
+    folder: &str,
+    filter: &SearchFilter,
+    limit: usize,
+) -> Vec<&'a BrowserItem> {
+    let prefix = format!("{}/", folder.trim_end_matches('/').to_lowercase());
+    items
+        .iter()
+        .filter(|item| item.path.to_lowercase().starts_with(&prefix) && filter.allows(item))
+        .take(limit)
+        .collect()
+}
+
+/// Loadable items in a category with exactly this name (case-insensitive),
+/// in browser order.
+pub fn named<'a>(items: &'a [BrowserItem], category: &str, name: &str) -> Vec<&'a BrowserItem> {
+    items
+        .iter()
+        .filter(|item| {
+            item.loadable
+                && item.category.eq_ignore_ascii_case(category)
+                && item.name.eq_ignore_ascii_case(name)
+        })
+        .collect()
+}
+
+/// Result of `search_browser_index`.
+#[derive(Debug, Clone, Serialize)]
+pub struct IndexSearch {
+    /// Whether the results come from a finished crawl; `false` while the
+    /// first crawl is still running.
+    pub complete: bool,
+    /// Matches, best first.
+    pub results: Vec<BrowserItem>,
+}
+
+/// Seconds since the Unix epoch.
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+/// Location of the index cache file.
+fn cache_path() -> Result<PathBuf, Error> {
+    Ok(cache_dir()?.join("browser-index.json"))
+}
+
+/// State of the index, returned by `browser_index_status`.
+#[derive(Debug, Clone, Serialize)]
+pub struct IndexStatus {
+    /// Whether a crawl is running.
+    pub crawling: bool,
+    /// Items available for search.
+    pub items: usize,
+    /// Items found so far by the running crawl.
+    pub crawled_items: usize,
+    /// Age of the index in seconds, if it has been built.
+    pub age_seconds: Option<u64>,
+    /// Whether the index is older than the refresh interval.
+    pub stale: bool,
+    /// Cache file location.
+    pub cache_path: Option<PathBuf>,
+    /// Error from the last crawl, if it failed.
+    pub error: Option<String>,
+    /// Seconds until a failed crawl is retried automatically.
+    pub retry_in_seconds: Option<u64>,
+}
+
+#[derive(Default)]
+struct State {
+    /// Whether the disk cache has been read.
+    loaded: bool,
+    /// Searchable items from the last completed crawl.
+    items: Vec<BrowserItem>,
+    crawled_at: Option<u64>,
+    /// Items found by the running crawl.
+    pending: Vec<BrowserItem>,
+    task: Option<JoinHandle<()>>,
+    error: Option<String>,
+    /// Crawls that failed in a row.
+    failures: u32,
+    /// No automatic crawl is started before this time.
+    retry_at: Option<Instant>,
+}
+
+impl State {
+    fn crawling(&self) -> bool {
+        self.task.as_ref().is_some_and(|task| !task.is_finished())
+    }
+
+    fn stale(&self) -> bool {
+        self.crawled_at
+            .is_none_or(|at| now().saturating_sub(at) > MAX_AGE.as_secs())
+    }
+
+    fn status(&self) -> IndexStatus {
+        IndexStatus {
+            crawling: self.crawling(),
+            items: self.items.len(),
+            crawled_items: self.pending.len(),
+            age_seconds: self.crawled_at.map(|at| now().saturating_sub(at)),
+            stale: self.stale(),
+            cache_path: cache_path().ok(),
+            error: self.error.clone(),
+            retry_in_seconds: self
+                .retry_at
+                .map(|at| at.saturating_duration_since(Instant::now()).as_secs()),
+        }
+    }
+
+    /// Whether an automatic crawl may start, i.e. no failure backoff is pending.
+    fn may_retry(&self) -> bool {
+        self.retry_at.is_none_or(|at| Instant::now() >= at)
+    }
+}
+
+/// Shared browser index.
+///
+/// Cloning the index shares the same state.
+#[derive(Clone, Default)]
+pub struct BrowserIndex {
+    state: Arc<Mutex<State>>,
+}
+
+impl BrowserIndex {
+    /// Create an empty index. Nothing is read or crawled until first use.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Load the disk cache on first use and start a crawl if the index is
+    /// missing or stale.
+    pub fn ensure_fresh(&self) -> IndexStatus {
+        let mut state = self.state.lock().unwrap();
+        if !state.loaded {
+            state.loaded = true;
+            match read_cache() {
+                Ok(Some(cache)) => {
+                    debug!(items = cache.items.len(), "Loaded browser index cache");
+                    state.items = cache.items;
+                    state.crawled_at = Some(cache.crawled_at);
+                }
+                Ok(None) => {}
+                Err(e) => debug!(error = %e, "Could not read browser index cache"),
+            }
+        }
+        if state.stale() && !state.crawling() && state.may_retry() {
+            self.start_crawl(&mut state);
+        }
+        state.status()
+    }
+
+    /// Start a crawl now, unless one is already running. Ignores the backoff
+    /// after a failed crawl.
+    ///
+    /// Returns `false` if a crawl was already running.
+    pub fn refresh(&self) -> bool {
+        let mut state = self.state.lock().unwrap();
+        state.loaded = true;
+        if state.crawling() {
+            return false;
+        }
+        self.start_crawl(&mut state);
+        true
+    }
+
+    /// Current index state.
+    pub fn status(&self) -> IndexStatus {
+        self.state.lock().unwrap().status()
+    }
+
+    /// Search the index; while the first crawl runs, partial results are searched.
+    pub fn search(&self, query: &str, filter: &SearchFilter, limit: usize) -> Vec<BrowserItem> {
+        let state = self.state.lock().unwrap();
+        let items = if state.items.is_empty() {
+            &state.pending
+        } else {
+            &state.items
+        };
+        search(items, query, filter, limit)
+            .into_iter()
+            .cloned()
+            .collect()
+    }
+
+    /// List items below a folder; while the first crawl runs, partial results are listed.
+    pub fn within(&self, folder: &str, filter: &SearchFilter, limit: usize) -> Vec<BrowserItem> {
+        let state = self.state.lock().unwrap();
+        let items = if state.items.is_empty() {
+            &state.pending
+        } else {
+            &state.items
+        };
+        within(items, folder, filter, limit)
+            .into_iter()
+            .cloned()
+            .collect()
+    }
+
+    /// Paths of the loadable items in a category with the given name.
+    pub fn named(&self, category: &str, name: &str) -> Vec<String> {
+        let state = self.state.lock().unwrap();
+        let items = if state.items.is_empty() {
+            &state.pending
+        } else {
+            &state.items
+        };
+        named(items, category, name)
+            .into_iter()
+            .map(|item| item.path.clone())
+            .collect()
+    }
+
+    /// Look up an item by its full path (case-insensitive).
+    pub fn get(&self, path: &str) -> Option<BrowserItem> {
+        let state = self.state.lock().unwrap();
+        state
+            .items
+            .iter()
+            .chain(&state.pending)
+            .find(|item| item.path.eq_ignore_ascii_case(path))
+            .cloned()
+    }
+
+    /// Stop a running crawl.
+    pub fn stop(&self) {
+        let task = self.state.lock().unwrap().task.take();
+        if let Some(task) = task {
+            task.abort();
+        }
+    }
+
+    fn start_crawl(&self, state: &mut State) {
+        state.pending.clear();
+        state.error = None;
+        state.task = Some(tokio::spawn(Self::crawl(self.clone())));
+    }
+
+    /// Crawl every category, then publish the result and write the cache.
+    async fn crawl(self) {
+        info!("Crawling Live's browser");
+        let osc = OscHandle::new();
+        let mut reachable = false;
+
+        'categories: for category in CATEGORIES {
+            // Folders to browse, with their depth and their own item's index
+            let mut folders = vec![(String::new(), 0, None)];
+            while let Some((folder, depth, own)) = folders.pop() {
+                let children = match list_children(&osc, category, &folder).await {
+                    Ok(children) => children,
+                    Err(e) => {
+                        debug!(category, folder, error = %e, "Browser folder not crawled");
+                        continue;
+                    }
+                };
+                reachable = true;
+
+                let mut state = self.state.lock().unwrap();
+                if children.is_empty() {
+                    // Not a folder after all, but an item such as a plugin
+                    if let Some(item) = own.and_then(|i: usize| state.pending.get_mut(i)) {
+                        item.is_folder = false;
+                        item.loadable = true;
+                        item.is_device |= DEVICE_LEAF_CATEGORIES.contains(category);
+                    }
+                    continue;
+                }
+                for name in children {
+                    let relative = if folder.is_empty() {
+                        name.clone()
+                    } else {
+                        format!("{folder}/{name}")
+                    };
+                    let item = classify(category, depth, name, &relative);
+                    if item.is_folder && depth < MAX_DEPTH {
+                        folders.push((relative, depth + 1, Some(state.pending.len())));
+                    }
+                    state.pending.push(item);
+                }
+                if state.pending.len() >= MAX_ITEMS {
+                    break 'categories;
+                }
+            }
+        }
+
+        let mut state = self.state.lock().unwrap();
+        if !reachable {
+            state.failures += 1;
+            let delay = retry_delay(state.failures);
+            state.retry_at = Some(Instant::now() + delay);
+            state.error = Some(format!(
+                "Live did not answer /live/browser/browse; is AbletonOSC running? Retrying in {} s",
+                delay.as_secs()
+            ));
+            return;
+        }
+        state.failures = 0;
+        state.retry_at = None;
+
+        let crawled_at = now();
+        state.items = std::mem::take(&mut state.pending);
+        state.crawled_at = Some(crawled_at);
+        info!(items = state.items.len(), "Browser index built");
+
+        let cache = CacheFile {
+            version: CACHE_VERSION,
+            crawled_at,
+            items: state.items.clone(),
+        };
+        drop(state);
+        if let Err(e) = write_cache(&cache) {
+            debug!(error = %e, "Could not write browser index cache");
+        }
+    }
+}
+
+/// Names of the children of a browser folder (`""` is the category root).
+async fn list_children(
+    osc: &OscHandle,
+    category: &str,
+    folder: &str,
+) -> Result<Vec<String>, Error> {
+    let mut args = vec![OscType::String(category.to_string())];
+    let addr = if folder.is_empty() {
+        "/live/browser/browse"
+    } else {
+        args.push(OscType::String(folder.to_string()));
+        "/live/browser/browse_path"
+    };
+    let reply: Vec<OscType> = osc.query(addr, args.clone()).await?;
+    Ok(child_names(reply, &args))
+}
+
+/// String values of a browse reply, without the echoed request arguments.
+fn child_names(reply: Vec<OscType>, request: &[OscType]) -> Vec<String> {
+    let echoed = if reply.starts_with(request) {
+        request.len()
+    } else {
+        0
+    };
+    reply
+        .into_iter()
+        .skip(echoed)
+        .filter_map(|value| match value {
+            OscType::String(name) => Some(name),
+            _ => None,
+        })
+        .collect()
+}
+
+/// File extension of a browser item name, e.g. `wav` or `adv`.
+///
+/// Only short alphanumeric suffixes count, so names like "Vol. 2" or
+/// "Pads v1.5" are not taken for files.
+fn extension(name: &str) -> Option<&str> {
+    let (_, extension) = name.rsplit_once('.')?;
+    ((2..=5).contains(&extension.len())
+        && extension.chars().all(|c| c.is_ascii_alphanumeric())
+        && extension.chars().any(|c| c.is_ascii_alphabetic()))
+    .then_some(extension)
+}
+
+/// Browser item for a child `name` found `depth` levels below a category.
+///
+/// Files are loadable leaves; everything else is taken for a folder until
+/// browsing it shows no children. Top-level items of the device categories
+/// are devices.
+fn classify(category: &str, depth: usize, name: String, relative: &str) -> BrowserItem {
+    let extension = extension(&name).map(str::to_ascii_lowercase);
+    let top_device = depth == 0 && DEVICE_CATEGORIES.contains(&category) && extension.is_none();
+    BrowserItem {
+        path: format!("{category}/{relative}"),
+        category: category.to_string(),
+        loadable: extension.is_some() || top_device,
+        is_device: top_device
+            || extension
+                .as_deref()
+                .is_some_and(|e| DEVICE_EXTENSIONS.contains(&e)),
+        is_folder: extension.is_none(),
+        name,
+    }
+}
+
+/// Backoff before retrying after `failures` failed crawls in a row.
+fn retry_delay(failures: u32) -> Duration {
+    RETRY_AFTER
+        .saturating_mul(1 << failures.saturating_sub(1).min(16))
+        .min(MAX_RETRY_AFTER)
+}
+
+/// Read the cache file, ignoring caches from other versions.
+fn read_cache() -> Result<Option<CacheFile>, Error> {
+    let path = cache_path()?;
+    let Ok(json) = fs::read_to_string(&path) else {
+        return Ok(None);
+    };
+    let cache: CacheFile = serde_json::from_str(&json)
+        .map_err(|e| Error::Storage(format!("Invalid cache {}: {e}", path.display())))?;
+    Ok((cache.version == CACHE_VERSION).then_some(cache))
+}
+
+fn write_cache(cache: &CacheFile) -> Result<(), Error> {
+    let path = cache_path()?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| Error::Storage(format!("{}: {e}", parent.display())))?;
+    }
+    let json = serde_json::to_string(cache)
+        .map_err(|e| Error::Storage(format!("Could not encode browser index: {e}")))?;
+    fs::write(&path, json).map_err(|e| Error::Storage(format!("{}: {e}", path.display())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(path: &str, loadable: bool, is_device: bool) -> BrowserItem {
+        let (category, _) = path.split_once('/').unwrap();
+        BrowserItem {
+            name: path.rsplit('/').next().unwrap().to_string(),
+            path: path.to_string(),
+            category: category.to_string(),
+            loadable,
+            is_device,
+            is_folder: !loadable,
+        }
+    }
+
+    fn library() -> Vec<BrowserItem> {
+        vec![
+            item("sounds/Pad", false, false),
+            item("sounds/Pad/Warm Analog Pad.adv", true, true),
+            item("sounds/Pad/Analog Warmth.adv", true, true),
+            item("sounds/Pad/Glass Pad.adv", true, true),
+            item("instruments/Analog", true, true),
+            item("samples/Pads/warm-analog-pad-C3.wav", true, false),
+        ]
+    }
+
+    fn names(hits: &[&BrowserItem]) -> Vec<String> {
+        hits.iter().map(|item| item.name.clone()).collect()
+    }
+
+    /// Whole-word name matches outrank prefixes and folder matches.
+    #[test]
+    fn ranks_name_matches_first() {
+        let items = library();
+        let hits = search(&items, "warm analog pad", &SearchFilter::default(), 10);
+        assert_eq!(
+            names(&hits),
+            vec![
+                "Warm Analog Pad.adv",
+                "warm-analog-pad-C3.wav",
+                "Analog Warmth.adv",
+            ]
+        );
+    }
+
+    /// Every query word must match somewhere; folders count too.
+    #[test]
+    fn requires_every_word() {
+        let items = library();
+        let hits = search(&items, "glass pad", &SearchFilter::default(), 10);
+        assert_eq!(names(&hits), vec!["Glass Pad.adv"]);
+        assert!(search(&items, "glass bass", &SearchFilter::default(), 10).is_empty());
+    }
+
+    /// Filters restrict results by category and kind.
+    #[test]
+    fn filters_results() {
+        let items = library();
+        let devices = SearchFilter {
+            devices_only: true,
+            ..SearchFilter::default()
+        };
+        assert!(
+            search(&items, "analog", &devices, 10)
+                .iter()
+                .all(|item| item.is_device)
+        );
+        let samples = SearchFilter {
+            category: Some("Samples".to_string()),
+            ..SearchFilter::default()
+        };
+        assert_eq!(
+            names(&search(&items, "analog", &samples, 10)),
+            vec!["warm-analog-pad-C3.wav"]
+        );
+    }
+
+    /// Folder listings include nested items but not the folder itself.
+    #[test]
+    fn lists_items_within_folder() {
+        let items = library();
+        let loadable = SearchFilter {
+            loadable_only: true,
+            ..SearchFilter::default()
+        };
+        assert_eq!(
+            names(&within(&items, "Sounds/pad/", &loadable, 10)),
+            vec!["Warm Analog Pad.adv", "Analog Warmth.adv", "Glass Pad.adv"]
+        );
+        assert_eq!(within(&items, "sounds/Pad", &loadable, 1).len(), 1);
+        assert!(within(&items, "sounds/Pa", &loadable, 10).is_empty());
+    }
+
+    /// Same-name lookups stay within the category and skip folders.
+    #[test]
+    fn finds_items_with_the_same_name() {
+        let mut items = library();
+        items.push(item("sounds/Keys/Glass Pad.adv", true, true));
+        items.push(item("instruments/Glass Pad.adv", true, true));
+        let paths: Vec<&str> = named(&items, "Sounds", "glass pad.adv")
+            .iter()
+            .map(|item| item.path.as_str())
+            .collect();
+        assert_eq!(
+            paths,
+            vec!["sounds/Pad/Glass Pad.adv", "sounds/Keys/Glass Pad.adv"]
+        );
+        assert!(named(&items, "sounds", "Pad").is_empty());
+    }
+
+    /// Browse replies drop the echoed request; names are classified by
+    /// extension and position.
+    #[test]
+    fn reads_and_classifies_browse_replies() {
+        let request = vec![OscType::String("sounds".to_string())];
+        let reply = vec![
+            OscType::String("sounds".to_string()),
+            OscType::String("Pad".to_string()),
+        ];
+        assert_eq!(child_names(reply, &request), vec!["Pad"]);
+        assert_eq!(
+            child_names(vec![OscType::String("Bass".to_string())], &request),
+            vec!["Bass"]
+        );
+
+        let preset = classify("sounds", 1, "Warm Pad.adv".to_string(), "Pad/Warm Pad.adv");
+        assert!(preset.loadable && preset.is_device && !preset.is_folder);
+        assert_eq!(preset.path, "sounds/Pad/Warm Pad.adv");
+        let sample = classify("samples", 1, "Kick.WAV".to_string(), "Drums/Kick.WAV");
+        assert!(sample.loadable && !sample.is_device);
+        let folder = classify("samples", 0, "Vol. 2".to_string(), "Vol. 2");
+        assert!(folder.is_folder && !folder.loadable);
+        let device = classify("audio_effects", 0, "Reverb".to_string(), "Reverb");
+        assert!(device.is_folder && device.loadable && device.is_device);
+    }
+
+    /// Retries back off exponentially up to a limit.
+    #[test]
+    fn backs_off_failed_crawls() {
+        assert_eq!(retry_delay(1), RETRY_AFTER);
+        assert_eq!(retry_delay(2), RETRY_AFTER * 2);
+        assert_eq!(retry_delay(40), MAX_RETRY_AFTER);
+    }
+}
@@ -0,0 +1,61 @@
+//! Locations of remix-mcp's own files (presets, templates, caches).
+
+use std::path::PathBuf;
+
+use crate::error::Error;
+
+/// Directory holding remix-mcp's configuration and user data.
+///
+/// This is `remix-mcp` inside the platform config directory, e.g.
+/// `~/Library/Application Support/remix-mcp` on macOS or
+/// `~/.config/remix-mcp` on Linux.
+pub fn config_dir() -> Result<PathBuf, Error> {
+    dirs::config_dir()
+        .map(|dir| dir.join("remix-mcp"))
+        .ok_or_else(|| Error::Storage("Could not determine the config directory".to_string()))
+}
+
+/// Directory for remix-mcp's caches, which can be deleted at any time.
+///
+/// This is `remix-mcp` inside the platform cache directory, e.g.
+/// `~/Library/Caches/remix-mcp` on macOS or `~/.cache/remix-mcp` on Linux.
+pub fn cache_dir() -> Result<PathBuf, Error> {
+    dirs::cache_dir()
+        .map(|dir| dir.join("remix-mcp"))
+        .ok_or_else(|| Error::Storage("Could not determine the cache directory".to_string()))
+}
+
+/// Replace characters that are unsafe in file names, keeping names readable.
+pub fn file_stem(name: &str) -> String {
+    let stem: String = name
+        .trim()
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || matches!(c, ' ' | '-' | '_' | '.') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    let stem = stem.trim_matches('.').to_string();
+    if stem.is_empty() {
+        "untitled".to_string()
+    } else {
+        stem
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Path separators and other unsafe characters are replaced.
+    #[test]
+    fn file_stem_sanitizes_names() {
+        assert_eq!(file_stem("Warm Pad"), "Warm Pad");
+        assert_eq!(file_stem("Bass/Lead: v2"), "Bass_Lead_ v2");
+        assert_eq!(file_stem("../secret"), "_secret");
+        assert_eq!(file_stem("  "), "untitled");
+    }
+}
@@ -26,6 +26,26 @@ pub enum Error {
     #[error("Invalid parameter: {0}")]
     InvalidParameter(String),
 
+    /// No item matched a name lookup.
+    #[error("No {kind} matches \"{query}\" (available: {})", .available.join(", "))]
+    NotFound {
+        kind: &'static str,
+        query: String,
+        available: Vec<String>,
+    },
+
+    /// A name lookup matched more than one item.
+    #[error("Ambiguous {kind} \"{query}\" matches: {}", .candidates.join(", "))]
+    Ambiguous {
+        kind: &'static str,
+        query: String,
+        candidates: Vec<String>,
+    },
+
+    /// Reading or writing a local file (presets, templates, caches) failed.
+    #[error("Storage error: {0}")]
+    Storage(String),
+
     /// Ableton Live not connected.
     #[error("Ableton Live is not connected or `AbletonOSC` is not running")]
     NotConnected,
@@ -0,0 +1,213 @@
+//! Conversion between Live's mixer values and the units Live displays.
+//!
+//! Track volume is a fader position from 0.0 to 1.0, where 0.85 is 0 dB and
+//! 1.0 is +6 dB. Above -18 dB (0.4) the fader is linear in dB, 40 dB per unit
+//! of travel; below that the curve steepens towards -inf at 0.0, and is
+//! interpolated from points along Live's fader, so very low levels are
+//! approximate. Pan runs from -1.0 to 1.0 and is shown as `50L` to `50R`, with
+//! `C` in the middle.
+
+use crate::error::Error;
+use crate::types::{Pan, Volume};
+
+/// Fader position of 0 dB.
+pub const UNITY: f32 = 0.85;
+
+/// Loudest fader position, in dB.
+pub const MAX_DB: f32 = 6.0;
+
+/// Fader position and dB value where the linear range ends.
+const KNEE: (f32, f32) = (0.4, -18.0);
+
+/// Points along the fader below the knee, from the knee down.
+const CURVE: &[(f32, f32)] = &[
+    KNEE,
+    (0.3, -23.5),
+    (0.2, -30.0),
+    (0.15, -34.5),
+    (0.1, -40.5),
+    (0.05, -50.0),
+    (0.02, -60.0),
+    (0.0, -70.0),
+];
+
+/// dB per unit of fader travel in the linear range.
+const DB_PER_UNIT: f32 = 40.0;
+
+/// Pan shown as fully left or right.
+const PAN_SCALE: f32 = 50.0;
+
+/// Fader position in dB; `-inf` at 0.0.
+pub fn volume_to_db(volume: f32) -> f32 {
+    if volume <= 0.0 {
+        return f32::NEG_INFINITY;
+    }
+    if volume >= KNEE.0 {
+        return (volume - UNITY) * DB_PER_UNIT;
+    }
+    let segment = CURVE
+        .windows(2)
+        .find(|w| volume >= w[1].0)
+        .unwrap_or_else(|| &CURVE[CURVE.len() - 2..]);
+    let ((v0, db0), (v1, db1)) = (segment[0], segment[1]);
+    db1 + (db0 - db1) * (volume - v1) / (v0 - v1)
+}
+
+/// Fader position for a level in dB, clamped to the fader's range.
+pub fn db_to_volume(db: f32) -> f32 {
+    if db >= KNEE.1 {
+        return (UNITY + db / DB_PER_UNIT).min(1.0);
+    }
+    let segment = CURVE
+        .windows(2)
+        .find(|w| db >= w[1].1)
+        .unwrap_or_else(|| &CURVE[CURVE.len() - 2..]);
+    let ((v0, db0), (v1, db1)) = (segment[0], segment[1]);
+    (v1 + (v0 - v1) * (db - db1) / (db0 - db1)).max(0.0)
+}
+
+/// Fader position in dB for JSON output: rounded to 0.1 dB, `None` for -inf.
+pub fn volume_db(volume: f32) -> Option<f32> {
+    let db = volume_to_db(volume);
+    db.is_finite().then(|| (db * 10.0).round() / 10.0)
+}
+
+/// A dB value as Live shows it, e.g. `-6.0 dB` or `-inf dB`.
+pub fn format_db(db: f32) -> String {
+    if db.is_finite() {
+        format!("{db:.1} dB")
+    } else {
+        "-inf dB".to_string()
+    }
+}
+
+/// A pan value as Live shows it: `25L`, `C` or `50R`.
+pub fn format_pan(pan: f32) -> String {
+    let amount = (pan.abs() * PAN_SCALE).round();
+    if amount == 0.0 {
+        "C".to_string()
+    } else if pan < 0.0 {
+        format!("{amount}L")
+    } else {
+        format!("{amount}R")
+    }
+}
+
+/// Parse a level in dB: `-6 dB`, `-6dB`, `+3 db` or `-inf`.
+pub fn parse_db(text: &str) -> Option<f32> {
+    let text = text.trim().to_lowercase();
+    let number = text.strip_suffix("db").unwrap_or(&text).trim();
+    match number {
+        "-inf" | "-∞" => Some(f32::NEG_INFINITY),
+        _ => number.parse().ok(),
+    }
+}
+
+/// Parse a pan position as Live shows it: `25L`, `L25`, `C`, `center` or `50R`.
+pub fn parse_pan(text: &str) -> Option<f32> {
+    let text = text.trim().to_uppercase();
+    if text == "C" || text == "CENTER" || text == "CENTRE" {
+        return Some(0.0);
+    }
+    let (amount, sign) =
+        if let Some(amount) = text.strip_suffix('L').or_else(|| text.strip_prefix('L')) {
+            (amount, -1.0)
+        } else if let Some(amount) = text.strip_suffix('R').or_else(|| text.strip_prefix('R')) {
+            (amount, 1.0)
+        } else {
+            return None;
+        };
+    let amount: f32 = amount.trim().parse().ok()?;
+    (0.0..=PAN_SCALE)
+        .contains(&amount)
+        .then_some(sign * amount / PAN_SCALE)
+}
+
+impl Volume {
+    /// Fader position, checking the range.
+    pub fn to_fader(&self) -> Result<f32, Error> {
+        match self {
+            Self::Fader(volume) if (0.0..=1.0).contains(volume) => Ok(*volume),
+            Self::Fader(_) => Err(Error::InvalidParameter(
+                "Volume must be between 0.0 and 1.0".to_string(),
+            )),
+            Self::Db(text) => match parse_db(text) {
+                Some(db) if db <= MAX_DB => Ok(db_to_volume(db)),
+                Some(_) => Err(Error::InvalidParameter(format!(
+                    "Volume must be at most +{MAX_DB} dB"
+                ))),
+                None => Err(Error::InvalidParameter(format!(
+                    "Cannot read \"{text}\" as a volume; use 0.0-1.0 or a level like \"-6 dB\""
+                ))),
+            },
+        }
+    }
+}
+
+impl Pan {
+    /// Pan value, checking the range.
+    pub fn to_value(&self) -> Result<f32, Error> {
+        match self {
+            Self::Value(pan) if (-1.0..=1.0).contains(pan) => Ok(*pan),
+            Self::Value(_) => Err(Error::InvalidParameter(
+                "Pan must be between -1.0 and 1.0".to_string(),
+            )),
+            Self::Label(text) => parse_pan(text).ok_or_else(|| {
+                Error::InvalidParameter(format!(
+                    "Cannot read \"{text}\" as a pan position; use -1.0 to 1.0 or \"50L\" to \"50R\""
+                ))
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The linear range hits Live's landmarks.
+    #[test]
+    fn fader_landmarks() {
+        assert!(volume_to_db(UNITY).abs() < 1e-4);
+        assert!((volume_to_db(1.0) - 6.0).abs() < 1e-4);
+        assert!((volume_to_db(0.7) + 6.0).abs() < 1e-4);
+        assert!((volume_to_db(0.4) + 18.0).abs() < 1e-4);
+        assert!(volume_to_db(0.0).is_infinite());
+        assert!((db_to_volume(-6.0) - 0.7).abs() < 1e-4);
+        assert!(db_to_volume(f32::NEG_INFINITY).abs() < 1e-6);
+        assert!((db_to_volume(12.0) - 1.0).abs() < 1e-6);
+    }
+
+    /// Converting to dB and back lands on the same fader position.
+    #[test]
+    fn fader_round_trip() {
+        for i in 1..=100 {
+            let volume = i as f32 / 100.0;
+            let back = db_to_volume(volume_to_db(volume));
+            assert!((back - volume).abs() < 1e-4, "{volume} came back as {back}");
+        }
+    }
+
+    /// Levels parse with or without a unit, and stay below +6 dB.
+    #[test]
+    fn parses_db() {
+        assert_eq!(parse_db("-6 dB"), Some(-6.0));
+        assert_eq!(parse_db("+3dB"), Some(3.0));
+        assert_eq!(parse_db("-inf"), Some(f32::NEG_INFINITY));
+        assert_eq!(parse_db("loud"), None);
+        assert!(Volume::Db("+7 dB".to_string()).to_fader().is_err());
+        assert!(Volume::Fader(1.2).to_fader().is_err());
+    }
+
+    /// Pan labels read and print the way Live shows them.
+    #[test]
+    fn pan_labels() {
+        assert_eq!(parse_pan("25L"), Some(-0.5));
+        assert_eq!(parse_pan("r50"), Some(1.0));
+        assert_eq!(parse_pan("C"), Some(0.0));
+        assert_eq!(parse_pan("60L"), None);
+        assert_eq!(format_pan(-0.5), "25L");
+        assert_eq!(format_pan(0.004), "C");
+        assert_eq!(format_pan(1.0), "50R");
+    }
+}
@@ -3,12 +3,29 @@
 //! This library provides an MCP (Model Context Protocol) server that allows
 //! AI assistants to control Ableton Live through the `AbletonOSC` Remote Script.
 
+pub mod audition;
+pub mod browser_index;
+pub mod config;
 pub mod error;
+pub mod fader;
 pub mod installer;
+pub mod macros;
+pub mod metering;
+pub mod mix_state;
+pub mod modulation;
 pub mod osc;
+pub mod presets;
+pub mod recording;
+pub mod resolve;
+pub mod rng;
+pub mod routing;
+pub mod scheduler;
 pub mod server;
+pub mod sound_design;
+pub mod templates;
 pub mod tools;
 pub mod types;
+pub mod units;
 
 pub use error::Error;
 pub use installer::InstallStatus;
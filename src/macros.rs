@@ -0,0 +1,104 @@
+//! In-memory rack macro variations.
+//!
+//! Variations are kept for the lifetime of the server, keyed by the rack's
+//! track and device index. The rack name is stored with them, so variations
+//! left behind by a moved or deleted rack are not applied to whatever takes
+//! its place.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use serde::Serialize;
+
+/// A macro value stored in a variation.
+#[derive(Debug, Clone, Serialize)]
+pub struct StoredMacro {
+    pub number: u32,
+    pub name: String,
+    pub value: f32,
+}
+
+/// A named set of macro values.
+#[derive(Debug, Clone, Serialize)]
+pub struct MacroVariation {
+    pub name: String,
+    pub macros: Vec<StoredMacro>,
+}
+
+/// Variations stored for one rack.
+#[derive(Debug, Clone, Default)]
+struct RackVariations {
+    /// Rack name when the variations were stored, to detect a moved device.
+    rack_name: String,
+    variations: Vec<MacroVariation>,
+}
+
+/// In-memory store of macro variations, shared by all server clones.
+#[derive(Clone, Default)]
+pub struct MacroVariationStore {
+    racks: Arc<Mutex<HashMap<(u32, u32), RackVariations>>>,
+}
+
+impl MacroVariationStore {
+    /// Create an empty store.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Store a variation, replacing any variation with the same name.
+    pub fn store(&self, rack: (u32, u32), rack_name: &str, variation: MacroVariation) {
+        let mut racks = self.racks.lock().unwrap();
+        let entry = racks.entry(rack).or_default();
+        if entry.rack_name != rack_name {
+            // A different rack now sits at this position; its variations are stale
+            *entry = RackVariations {
+                rack_name: rack_name.to_string(),
+                variations: Vec::new(),
+            };
+        }
+        entry.variations.retain(|v| v.name != variation.name);
+        entry.variations.push(variation);
+    }
+
+    /// Variations stored for a rack, if the rack name still matches.
+    pub fn list(&self, rack: (u32, u32), rack_name: &str) -> Vec<MacroVariation> {
+        self.racks
+            .lock()
+            .unwrap()
+            .get(&rack)
+            .filter(|entry| entry.rack_name == rack_name)
+            .map(|entry| entry.variations.clone())
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Variations are replaced by name and dropped when a different rack takes the slot.
+    #[test]
+    fn variation_store_tracks_rack_identity() {
+        let store = MacroVariationStore::new();
+        let variation = |name: &str, value: f32| MacroVariation {
+            name: name.to_string(),
+            macros: vec![StoredMacro {
+                number: 1,
+                name: "Cutoff".to_string(),
+                value,
+            }],
+        };
+
+        store.store((0, 1), "Bass Rack", variation("verse", 10.0));
+        store.store((0, 1), "Bass Rack", variation("verse", 20.0));
+        store.store((0, 1), "Bass Rack", variation("drop", 30.0));
+        let stored = store.list((0, 1), "Bass Rack");
+        assert_eq!(stored.len(), 2);
+        assert!((stored[0].macros[0].value - 20.0).abs() < f32::EPSILON);
+
+        assert!(store.list((0, 1), "Keys Rack").is_empty());
+        store.store((0, 1), "Keys Rack", variation("intro", 0.0));
+        assert_eq!(store.list((0, 1), "Keys Rack").len(), 1);
+        assert!(store.list((0, 1), "Bass Rack").is_empty());
+    }
+}
@@ -160,6 +160,7 @@ async fn cmd_serve(skip_install_check: bool) -> Result<()> {
 
     // Create the server (infallible — OSC connects lazily on first tool call)
     let server = AbletonServer::new();
+    let handle = server.clone();
 
     // Run the server with stdio transport
     let service = server.serve(rmcp::transport::stdio()).await?;
@@ -167,8 +168,10 @@ async fn cmd_serve(skip_install_check: bool) -> Result<()> {
     info!("Server running, waiting for requests...");
 
     // Wait for the service to complete
-    service.waiting().await?;
+    let result = service.waiting().await;
 
     info!("Server shutting down");
+    handle.shutdown().await;
+    result?;
     Ok(())
 }
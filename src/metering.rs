@@ -0,0 +1,161 @@
+//! Level statistics from sampled output meters.
+//!
+//! Live's output meters report one instantaneous value between 0.0 and 1.0.
+//! Sampling them over a window gives a usable picture of a track's level:
+//! the peak, an RMS-style average, their ratio (crest factor) and how often
+//! the meter sat near full scale. Decibel values treat the meter as linear
+//! amplitude relative to full scale, so they are approximate.
+//!
+//! Gain staging builds on this: it moves faders towards a target average and
+//! measures again until the level is close enough.
+
+use serde::Serialize;
+
+use crate::fader::{db_to_volume, volume_to_db};
+
+/// Lowest level reported in dB; quieter samples count as silence.
+pub const FLOOR_DB: f32 = -70.0;
+
+/// Meter level in dB relative to full scale, or `None` below [`FLOOR_DB`].
+pub fn to_db(level: f32) -> Option<f32> {
+    let db = 20.0 * level.log10();
+    (db >= FLOOR_DB).then_some(db)
+}
+
+/// Statistics over a window of meter samples.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LevelStats {
+    /// Number of samples taken.
+    pub samples: usize,
+    /// Highest sample.
+    pub peak: f32,
+    /// Root mean square of the samples.
+    pub average: f32,
+    pub peak_db: Option<f32>,
+    pub average_db: Option<f32>,
+    /// Peak over average in dB; `None` when the track is silent.
+    pub crest_db: Option<f32>,
+    /// Share of samples at or above the clip threshold (0.0-1.0).
+    pub clipping: f32,
+}
+
+impl LevelStats {
+    /// Summarise `samples`, counting those at or above `clip_threshold` as
+    /// near clipping.
+    pub fn from_samples(samples: &[f32], clip_threshold: f32) -> Self {
+        let count = samples.len();
+        let peak = samples.iter().copied().fold(0.0_f32, f32::max);
+        let (average, clipping) = if count == 0 {
+            (0.0, 0.0)
+        } else {
+            let mean_square = samples.iter().map(|s| s * s).sum::<f32>() / count as f32;
+            let clipped = samples.iter().filter(|&&s| s >= clip_threshold).count();
+            (mean_square.sqrt(), clipped as f32 / count as f32)
+        };
+        let peak_db = to_db(peak);
+        let average_db = to_db(average);
+        Self {
+            samples: count,
+            peak,
+            average,
+            peak_db,
+            average_db,
+            crest_db: peak_db.zip(average_db).map(|(p, a)| p - a),
+            clipping,
+        }
+    }
+}
+
+/// Measured levels of one track, returned by `measure_levels`.
+#[derive(Debug, Clone, Serialize)]
+pub struct TrackLevels {
+    pub track: u32,
+    pub name: String,
+    #[serde(flatten)]
+    pub stats: LevelStats,
+}
+
+/// Outcome of gain staging one track.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GainStatus {
+    /// Average level is within the tolerance of the target.
+    Converged,
+    /// Reaching the target would mean raising the fader above the ceiling.
+    AtCeiling,
+    /// The track was silent, so there was nothing to adjust.
+    Silent,
+    /// The passes ran out before the level got within the tolerance.
+    NotConverged,
+}
+
+/// Before/after report for one track, returned by `gain_stage`.
+#[derive(Debug, Clone, Serialize)]
+pub struct GainReport {
+    pub track: u32,
+    pub name: String,
+    pub target_db: f32,
+    pub before_db: Option<f32>,
+    pub after_db: Option<f32>,
+    pub volume_before: f32,
+    pub volume_after: f32,
+    pub status: GainStatus,
+}
+
+/// Fader value that moves a track from `measured_db` towards `target_db`,
+/// following Live's fader curve.
+///
+/// Lowering is always allowed; raising stops at `ceiling`, and a fader that
+/// already sits above the ceiling is never raised further.
+pub fn next_volume(volume: f32, measured_db: f32, target_db: f32, ceiling: f32) -> f32 {
+    let desired = db_to_volume(volume_to_db(volume) + target_db - measured_db);
+    if desired > volume {
+        desired.min(ceiling).max(volume)
+    } else {
+        desired.max(0.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A steady level has a crest factor of zero and no clipping.
+    #[test]
+    fn steady_level() {
+        let stats = LevelStats::from_samples(&[0.5; 10], 0.98);
+        assert!((stats.peak - 0.5).abs() < 1e-6);
+        assert!((stats.average - 0.5).abs() < 1e-6);
+        assert!(stats.crest_db.unwrap().abs() < 1e-4);
+        assert!(stats.clipping.abs() < f32::EPSILON);
+    }
+
+    /// Peaks raise the crest factor and count towards clipping.
+    #[test]
+    fn peaks_and_clipping() {
+        let stats = LevelStats::from_samples(&[0.1, 0.1, 0.1, 1.0], 0.98);
+        assert!((stats.peak - 1.0).abs() < 1e-6);
+        assert!((stats.peak_db.unwrap()).abs() < 1e-4);
+        assert!(stats.crest_db.unwrap() > 5.0);
+        assert!((stats.clipping - 0.25).abs() < 1e-6);
+    }
+
+    /// Silence has no dB values.
+    #[test]
+    fn silence() {
+        let stats = LevelStats::from_samples(&[0.0; 4], 0.98);
+        assert_eq!(stats.peak_db, None);
+        assert_eq!(stats.crest_db, None);
+        assert_eq!(LevelStats::from_samples(&[], 0.98).samples, 0);
+    }
+
+    /// Faders move towards the target but never above the ceiling.
+    #[test]
+    fn next_volume_respects_ceiling() {
+        assert!((next_volume(0.7, -20.0, -24.0, 0.85) - 0.6).abs() < 1e-4);
+        assert!((next_volume(0.7, -24.0, -20.0, 0.85) - 0.8).abs() < 1e-4);
+        assert!((next_volume(0.8, -30.0, -20.0, 0.85) - 0.85).abs() < 1e-6);
+        assert!((next_volume(0.9, -30.0, -20.0, 0.85) - 0.9).abs() < 1e-6);
+        assert!(next_volume(0.1, 0.0, -60.0, 0.85).abs() < 1e-6);
+    }
+}
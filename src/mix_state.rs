@@ -0,0 +1,202 @@
+//! Saved mute and solo states, so solo and mute helpers can be undone.
+//!
+//! Every helper that changes mute or solo on many tracks pushes the state it
+//! found onto a stack first; restoring pops the most recent state. Tracks are
+//! remembered by index and name, so a state still restores correctly after
+//! tracks were inserted or moved, as long as the names are unique.
+
+use std::sync::{Arc, Mutex};
+
+use serde::Serialize;
+
+/// States kept before the oldest is dropped.
+const MAX_SAVED_STATES: usize = 32;
+
+/// Mute and solo state of one track.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TrackMix {
+    pub index: u32,
+    pub name: String,
+    pub mute: bool,
+    pub solo: bool,
+}
+
+/// A saved state of all tracks.
+#[derive(Debug, Clone, Serialize)]
+pub struct MixSnapshot {
+    /// What the state was saved for, e.g. `solo_exclusive Kick, Bass`.
+    pub label: String,
+    pub tracks: Vec<TrackMix>,
+}
+
+/// Summary of a saved state, returned by `list_mix_states`.
+#[derive(Debug, Clone, Serialize)]
+pub struct MixSnapshotInfo {
+    /// Position on the stack; 0 is restored next.
+    pub depth: usize,
+    pub label: String,
+    pub muted: Vec<String>,
+    pub soloed: Vec<String>,
+}
+
+/// Stack of saved mute/solo states, shared by all server clones.
+#[derive(Clone, Default)]
+pub struct MixStateStack {
+    states: Arc<Mutex<Vec<MixSnapshot>>>,
+}
+
+impl MixStateStack {
+    /// Create an empty stack.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Save a state; returns the number of saved states.
+    pub fn push(&self, snapshot: MixSnapshot) -> usize {
+        let mut states = self.states.lock().unwrap();
+        if states.len() == MAX_SAVED_STATES {
+            states.remove(0);
+        }
+        states.push(snapshot);
+        states.len()
+    }
+
+    /// Take the most recent state.
+    pub fn pop(&self) -> Option<MixSnapshot> {
+        self.states.lock().unwrap().pop()
+    }
+
+    /// Saved states, most recent first.
+    pub fn list(&self) -> Vec<MixSnapshotInfo> {
+        let names = |tracks: &[TrackMix], pick: fn(&TrackMix) -> bool| {
+            tracks
+                .iter()
+                .filter(|t| pick(t))
+                .map(|t| t.name.clone())
+                .collect()
+        };
+        self.states
+            .lock()
+            .unwrap()
+            .iter()
+            .rev()
+            .enumerate()
+            .map(|(depth, state)| MixSnapshotInfo {
+                depth,
+                label: state.label.clone(),
+                muted: names(&state.tracks, |t| t.mute),
+                soloed: names(&state.tracks, |t| t.solo),
+            })
+            .collect()
+    }
+}
+
+/// Where each saved track is now: its old index if the name still matches,
+/// otherwise the only track with that name. Tracks that cannot be found are
+/// returned separately.
+pub fn locate<'a>(
+    saved: &'a [TrackMix],
+    names: &[String],
+) -> (Vec<(u32, &'a TrackMix)>, Vec<&'a TrackMix>) {
+    let mut found = Vec::new();
+    let mut missing = Vec::new();
+    for track in saved {
+        if names.get(track.index as usize) == Some(&track.name) {
+            found.push((track.index, track));
+            continue;
+        }
+        let mut same = names.iter().enumerate().filter(|(_, n)| **n == track.name);
+        match (same.next(), same.next()) {
+            (Some((index, _)), None) => found.push((index as u32, track)),
+            _ => missing.push(track),
+        }
+    }
+    (found, missing)
+}
+
+/// Case-insensitive glob match: `*` matches any run of characters and `?`
+/// one character. A pattern without wildcards matches names containing it.
+pub fn matches_pattern(pattern: &str, name: &str) -> bool {
+    let (pattern, name) = (pattern.to_lowercase(), name.to_lowercase());
+    if !pattern.contains(['*', '?']) {
+        return name.contains(&pattern);
+    }
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+
+    // Backtrack to the last `*` on a mismatch
+    let (mut p, mut n) = (0, 0);
+    let mut star: Option<(usize, usize)> = None;
+    while n < name.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == name[n]) {
+            p += 1;
+            n += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            star = Some((p, n));
+            p += 1;
+        } else if let Some((star_p, star_n)) = star {
+            p = star_p + 1;
+            n = star_n + 1;
+            star = Some((star_p, star_n + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn track(index: u32, name: &str) -> TrackMix {
+        TrackMix {
+            index,
+            name: name.to_string(),
+            mute: false,
+            solo: false,
+        }
+    }
+
+    /// Wildcards match whole names; plain text matches part of a name.
+    #[test]
+    fn pattern_matching() {
+        assert!(matches_pattern("vox*", "Vox Lead"));
+        assert!(matches_pattern("*pad*", "Big Pad 2"));
+        assert!(matches_pattern("hat?", "Hat1"));
+        assert!(!matches_pattern("hat?", "Hats 2"));
+        assert!(matches_pattern("bass", "Sub Bass"));
+        assert!(!matches_pattern("kick", "Snare"));
+    }
+
+    /// Saved tracks follow their names when tracks move.
+    #[test]
+    fn locate_follows_names() {
+        let saved = [track(0, "Kick"), track(1, "Bass"), track(2, "Pad")];
+        let names: Vec<String> = ["New", "Kick", "Bass", "Keys"]
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        let (found, missing) = locate(&saved, &names);
+        let found: Vec<(u32, &str)> = found.iter().map(|(i, t)| (*i, t.name.as_str())).collect();
+        assert_eq!(found, [(1, "Kick"), (2, "Bass")]);
+        assert_eq!(missing.len(), 1);
+        assert_eq!(missing[0].name, "Pad");
+    }
+
+    /// The stack restores the most recent state first.
+    #[test]
+    fn stack_is_last_in_first_out() {
+        let stack = MixStateStack::new();
+        let snapshot = |label: &str| MixSnapshot {
+            label: label.to_string(),
+            tracks: vec![track(0, "Kick")],
+        };
+        stack.push(snapshot("first"));
+        assert_eq!(stack.push(snapshot("second")), 2);
+        assert_eq!(stack.list()[0].label, "second");
+        assert_eq!(stack.pop().unwrap().label, "second");
+        assert_eq!(stack.pop().unwrap().label, "first");
+        assert!(stack.pop().is_none());
+    }
+}
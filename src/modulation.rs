@@ -0,0 +1,488 @@
+//! Server-side parameter modulation: timed ramps and LFOs.
+//!
+//! Each active modulation runs as a tokio task that writes its target
+//! parameter at a fixed tick rate. Time is measured in beats, using the song
+//! tempo read when the modulation starts. LFO phase is locked to the song
+//! position, so a 1-beat LFO repeats on every beat of the song.
+//!
+//! Modulation tasks only send OSC messages and never wait for replies, so they
+//! do not compete with tool queries for responses on the shared socket.
+
+use std::collections::HashMap;
+use std::f64::consts::TAU;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use rosc::OscType;
+use serde::Serialize;
+use tokio::task::JoinHandle;
+use tokio::time::MissedTickBehavior;
+use tracing::debug;
+
+use crate::osc::OscHandle;
+use crate::rng::Rng;
+use crate::sound_design::MorphStates;
+use crate::types::Shape;
+
+/// Interval between parameter updates (50 Hz).
+const TICK: Duration = Duration::from_millis(20);
+
+/// Steepness of the exponential ramp curve.
+const EXP_CURVE: f64 = 4.0;
+
+/// Resolved parameter a modulation writes to.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Target {
+    /// A device parameter.
+    Device {
+        track: u32,
+        device: u32,
+        parameter: u32,
+        name: String,
+    },
+    /// Track volume.
+    Volume { track: u32 },
+    /// Track panning.
+    Pan { track: u32 },
+    /// Track send level.
+    Send { track: u32, send: u32 },
+    /// Every parameter of a device, morphing between two states. The
+    /// modulation value is the morph position (0.0 to 1.0).
+    Morph {
+        track: u32,
+        device: u32,
+        #[serde(skip)]
+        states: Arc<MorphStates>,
+    },
+}
+
+impl Target {
+    /// Whether two targets write any of the same parameters.
+    fn overlaps(&self, other: &Self) -> bool {
+        match (self, other) {
+            (
+                Self::Morph { track, device, .. },
+                Self::Morph {
+                    track: t,
+                    device: d,
+                    ..
+                }
+                | Self::Device {
+                    track: t,
+                    device: d,
+                    ..
+                },
+            )
+            | (
+                Self::Device { track, device, .. },
+                Self::Morph {
+                    track: t,
+                    device: d,
+                    ..
+                },
+            ) => track == t && device == d,
+            _ => self == other,
+        }
+    }
+
+    /// Build the OSC message that sets this target to `value`.
+    fn message(&self, value: f32) -> (&'static str, Vec<OscType>) {
+        match *self {
+            Self::Device {
+                track,
+                device,
+                parameter,
+                ..
+            } => (
+                "/live/device/set/parameter/value",
+                vec![
+                    OscType::Int(track as i32),
+                    OscType::Int(device as i32),
+                    OscType::Int(parameter as i32),
+                    OscType::Float(value),
+                ],
+            ),
+            Self::Volume { track } => (
+                "/live/track/set/volume",
+                vec![OscType::Int(track as i32), OscType::Float(value)],
+            ),
+            Self::Pan { track } => (
+                "/live/track/set/panning",
+                vec![OscType::Int(track as i32), OscType::Float(value)],
+            ),
+            Self::Send { track, send } => (
+                "/live/track/set/send",
+                vec![
+                    OscType::Int(track as i32),
+                    OscType::Int(send as i32),
+                    OscType::Float(value),
+                ],
+            ),
+            Self::Morph {
+                track,
+                device,
+                ref states,
+            } => {
+                let mut args = vec![OscType::Int(track as i32), OscType::Int(device as i32)];
+                args.extend(states.at(value).into_iter().map(OscType::Float));
+                ("/live/device/set/parameters/value", args)
+            }
+        }
+    }
+}
+
+/// Description of a modulation: what it drives and how.
+#[derive(Debug, Clone, Serialize)]
+pub struct Modulation {
+    /// Parameter being modulated.
+    pub target: Target,
+    /// Ramp or LFO shape.
+    pub shape: Shape,
+    /// Start value (ramps) or lower bound (LFOs).
+    pub from: f32,
+    /// End value (ramps) or upper bound (LFOs).
+    pub to: f32,
+    /// LFO period in beats (unused by ramps).
+    pub rate_beats: f32,
+    /// Total length in beats; `None` runs an LFO until stopped.
+    pub duration_beats: Option<f32>,
+}
+
+impl Modulation {
+    /// Compute the target value.
+    ///
+    /// `elapsed` is the number of beats since the modulation started and
+    /// `song_beat` the song position in beats. `seed` varies the
+    /// sample-and-hold sequence.
+    pub fn value_at(&self, elapsed: f64, song_beat: f64, seed: u64) -> f32 {
+        let progress = self
+            .duration_beats
+            .map_or(0.0, |d| (elapsed / f64::from(d)).clamp(0.0, 1.0));
+        let phase = (song_beat / f64::from(self.rate_beats)).rem_euclid(1.0);
+
+        // Position between `from` (0.0) and `to` (1.0); every shape starts at `from`
+        let position = match self.shape {
+            Shape::Linear => progress,
+            Shape::Exponential => (EXP_CURVE * progress).exp_m1() / EXP_CURVE.exp_m1(),
+            Shape::Sine => 0.5f64.mul_add(-(TAU * phase).cos(), 0.5),
+            Shape::Triangle => 1.0 - 2.0f64.mul_add(-phase, 1.0).abs(),
+            Shape::Square => {
+                if phase < 0.5 {
+                    0.0
+                } else {
+                    1.0
+                }
+            }
+            Shape::SampleAndHold => noise(
+                seed,
+                (song_beat / f64::from(self.rate_beats)).floor() as u64,
+            ),
+        };
+
+        let (from, to) = (f64::from(self.from), f64::from(self.to));
+        (to - from).mul_add(position, from) as f32
+    }
+
+    /// Whether the modulation has run its full duration.
+    pub fn is_finished(&self, elapsed: f64) -> bool {
+        self.duration_beats.is_some_and(|d| elapsed >= f64::from(d))
+    }
+}
+
+/// Deterministic pseudo-random value in `0.0..1.0` for a seed and step.
+fn noise(seed: u64, step: u64) -> f64 {
+    Rng::new(seed ^ step.wrapping_mul(0x9E37_79B9_7F4A_7C15)).next_f64()
+}
+
+/// Snapshot of an active modulation, returned by `list_modulations`.
+#[derive(Debug, Clone, Serialize)]
+pub struct ModulationStatus {
+    /// Modulation id, used to stop it.
+    pub id: u32,
+    /// What the modulation drives and how.
+    #[serde(flatten)]
+    pub modulation: Modulation,
+    /// Tempo used to convert beats to time.
+    pub tempo: f32,
+    /// Beats elapsed since the modulation started.
+    pub elapsed_beats: f64,
+}
+
+/// A running modulation task.
+struct Active {
+    modulation: Modulation,
+    tempo: f32,
+    started: Instant,
+    task: JoinHandle<()>,
+}
+
+impl Active {
+    fn status(&self, id: u32) -> ModulationStatus {
+        ModulationStatus {
+            id,
+            modulation: self.modulation.clone(),
+            tempo: self.tempo,
+            elapsed_beats: beats_since(self.started, self.tempo),
+        }
+    }
+}
+
+/// Registry of running modulations.
+#[derive(Default)]
+struct Registry {
+    next_id: u32,
+    active: HashMap<u32, Active>,
+}
+
+/// Beats elapsed since `started` at the given tempo.
+fn beats_since(started: Instant, tempo: f32) -> f64 {
+    started.elapsed().as_secs_f64() * f64::from(tempo) / 60.0
+}
+
+/// Engine that owns all running modulations.
+///
+/// Cloning the engine shares the same registry.
+#[derive(Clone, Default)]
+pub struct ModulationEngine {
+    registry: Arc<Mutex<Registry>>,
+}
+
+impl ModulationEngine {
+    /// Create an engine with no active modulations.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start a modulation and return its id.
+    ///
+    /// `tempo` is the song tempo in BPM and `song_beat` the current song
+    /// position, which anchors LFO phase. Any modulation already driving the
+    /// same parameters is stopped first.
+    pub fn start(&self, osc: OscHandle, modulation: Modulation, tempo: f32, song_beat: f64) -> u32 {
+        let mut registry = self.registry.lock().unwrap();
+
+        let replaced: Vec<u32> = registry
+            .active
+            .iter()
+            .filter(|(_, active)| active.modulation.target.overlaps(&modulation.target))
+            .map(|(&id, _)| id)
+            .collect();
+        for id in replaced {
+            if let Some(active) = registry.active.remove(&id) {
+                active.task.abort();
+            }
+        }
+
+        let id = registry.next_id;
+        registry.next_id += 1;
+
+        let started = Instant::now();
+        let task = tokio::spawn(Self::run(
+            self.clone(),
+            id,
+            osc,
+            modulation.clone(),
+            tempo,
+            song_beat,
+            started,
+        ));
+        registry.active.insert(
+            id,
+            Active {
+                modulation,
+                tempo,
+                started,
+                task,
+            },
+        );
+        id
+    }
+
+    /// Drive a modulation until it finishes, then remove it from the registry.
+    async fn run(
+        self,
+        id: u32,
+        osc: OscHandle,
+        modulation: Modulation,
+        tempo: f32,
+        song_beat: f64,
+        started: Instant,
+    ) {
+        let mut ticker = tokio::time::interval(TICK);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Skip);
+
+        loop {
+            ticker.tick().await;
+            let elapsed = beats_since(started, tempo);
+            let value = modulation.value_at(elapsed, song_beat + elapsed, u64::from(id));
+            let (addr, args) = modulation.target.message(value);
+            if let Err(e) = osc.send(addr, args).await {
+                debug!(id, error = %e, "Modulation send failed");
+            }
+            if modulation.is_finished(elapsed) {
+                break;
+            }
+        }
+
+        debug!(id, "Modulation finished");
+        self.registry.lock().unwrap().active.remove(&id);
+    }
+
+    /// List active modulations, ordered by id.
+    pub fn list(&self) -> Vec<ModulationStatus> {
+        let registry = self.registry.lock().unwrap();
+        let mut statuses: Vec<_> = registry
+            .active
+            .iter()
+            .map(|(&id, active)| active.status(id))
+            .collect();
+        statuses.sort_by_key(|status| status.id);
+        statuses
+    }
+
+    /// Stop a modulation, leaving its parameter at the current value.
+    ///
+    /// Returns the stopped modulation, or `None` if no such id is active.
+    pub fn stop(&self, id: u32) -> Option<ModulationStatus> {
+        let active = self.registry.lock().unwrap().active.remove(&id)?;
+        active.task.abort();
+        Some(active.status(id))
+    }
+
+    /// Stop every active modulation and return how many were stopped.
+    pub fn stop_all(&self) -> usize {
+        let mut registry = self.registry.lock().unwrap();
+        let count = registry.active.len();
+        for (_, active) in registry.active.drain() {
+            active.task.abort();
+        }
+        count
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn modulation(shape: Shape, duration_beats: Option<f32>) -> Modulation {
+        Modulation {
+            target: Target::Volume { track: 0 },
+            shape,
+            from: 0.0,
+            to: 1.0,
+            rate_beats: 1.0,
+            duration_beats,
+        }
+    }
+
+    fn approx(a: f32, b: f32) -> bool {
+        (a - b).abs() < 1e-4
+    }
+
+    /// Ramps run from `from` to `to` over the duration and hold the end value.
+    #[test]
+    fn ramps_reach_target() {
+        let linear = modulation(Shape::Linear, Some(4.0));
+        assert!(approx(linear.value_at(0.0, 0.0, 0), 0.0));
+        assert!(approx(linear.value_at(2.0, 2.0, 0), 0.5));
+        assert!(approx(linear.value_at(8.0, 8.0, 0), 1.0));
+
+        let exponential = modulation(Shape::Exponential, Some(4.0));
+        assert!(approx(exponential.value_at(0.0, 0.0, 0), 0.0));
+        assert!(exponential.value_at(2.0, 2.0, 0) < 0.5);
+        assert!(approx(exponential.value_at(4.0, 4.0, 0), 1.0));
+    }
+
+    /// LFO shapes start at `from` and follow the song position.
+    #[test]
+    fn lfo_shapes_follow_song_phase() {
+        let sine = modulation(Shape::Sine, None);
+        assert!(approx(sine.value_at(0.0, 0.0, 0), 0.0));
+        assert!(approx(sine.value_at(0.0, 0.5, 0), 1.0));
+        assert!(approx(sine.value_at(0.0, 3.25, 0), 0.5));
+
+        let triangle = modulation(Shape::Triangle, None);
+        assert!(approx(triangle.value_at(0.0, 0.25, 0), 0.5));
+        assert!(approx(triangle.value_at(0.0, 1.5, 0), 1.0));
+
+        let square = modulation(Shape::Square, None);
+        assert!(approx(square.value_at(0.0, 0.25, 0), 0.0));
+        assert!(approx(square.value_at(0.0, 0.75, 0), 1.0));
+    }
+
+    /// Sample-and-hold stays within bounds and holds for a full period.
+    #[test]
+    fn sample_and_hold_holds_per_period() {
+        let sh = modulation(Shape::SampleAndHold, None);
+        for step in 0..32 {
+            let beat = f64::from(step);
+            let value = sh.value_at(0.0, beat, 7);
+            assert!((0.0..1.0).contains(&value));
+            assert!(approx(value, sh.value_at(0.0, beat + 0.9, 7)));
+        }
+    }
+
+    /// Only modulations with a duration finish.
+    #[test]
+    fn finishes_after_duration() {
+        assert!(!modulation(Shape::Linear, Some(2.0)).is_finished(1.9));
+        assert!(modulation(Shape::Linear, Some(2.0)).is_finished(2.0));
+        assert!(!modulation(Shape::Sine, None).is_finished(1e6));
+    }
+
+    /// A morph overlaps every parameter modulation on the same device.
+    #[test]
+    fn morph_overlaps_device_targets() {
+        let states = Arc::new(MorphStates::new(&[], &[], &[]).unwrap());
+        let morph = |device| Target::Morph {
+            track: 1,
+            device,
+            states: Arc::clone(&states),
+        };
+        let parameter = Target::Device {
+            track: 1,
+            device: 0,
+            parameter: 3,
+            name: "Cutoff".to_string(),
+        };
+        assert!(morph(0).overlaps(&parameter));
+        assert!(parameter.overlaps(&morph(0)));
+        assert!(!morph(1).overlaps(&parameter));
+        assert!(!morph(0).overlaps(&Target::Volume { track: 1 }));
+    }
+
+    /// Starting on a busy target replaces the old modulation; `stop_all` clears everything.
+    #[tokio::test]
+    async fn engine_replaces_and_stops_modulations() {
+        let engine = ModulationEngine::new();
+        let osc = OscHandle::new();
+
+        let first = engine.start(osc.clone(), modulation(Shape::Sine, None), 120.0, 0.0);
+        let second = engine.start(osc.clone(), modulation(Shape::Square, None), 120.0, 0.0);
+        let ids: Vec<u32> = engine.list().iter().map(|s| s.id).collect();
+        assert_eq!(ids, vec![second]);
+        assert!(engine.stop(first).is_none());
+
+        let mut other = modulation(Shape::Triangle, None);
+        other.target = Target::Pan { track: 1 };
+        engine.start(osc, other, 120.0, 0.0);
+        assert_eq!(engine.stop_all(), 2);
+        assert!(engine.list().is_empty());
+    }
+
+    /// Finished ramps remove themselves from the registry.
+    #[tokio::test]
+    async fn finished_ramp_is_removed() {
+        let engine = ModulationEngine::new();
+        // 0.1 beats at 600 BPM is 10 ms
+        engine.start(
+            OscHandle::new(),
+            modulation(Shape::Linear, Some(0.1)),
+            600.0,
+            0.0,
+        );
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert!(engine.list().is_empty());
+    }
+}
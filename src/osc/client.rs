@@ -1,12 +1,14 @@
 //! Async OSC client for communicating with Ableton Live via `AbletonOSC`.
 
+use std::collections::HashMap;
 use std::net::SocketAddr;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
-use rosc::{OscMessage, OscPacket, OscType, decoder, encoder};
+use rosc::{OscBundle, OscMessage, OscPacket, OscTime, OscType, decoder, encoder};
 use tokio::net::UdpSocket;
-use tokio::sync::OnceCell;
+use tokio::sync::{OnceCell, mpsc};
+use tokio::task::JoinHandle;
 use tracing::{debug, trace};
 
 use crate::error::Error;
@@ -18,25 +20,49 @@ const ABLETON_OSC_PORT: u16 = 11000;
 /// Default timeout for waiting for responses.
 const DEFAULT_TIMEOUT: Duration = Duration::from_millis(500);
 
+/// Address `AbletonOSC` reports handler errors on.
+const ERROR_ADDR: &str = "/live/error";
+
+/// Reply channels of in-flight queries, keyed by OSC address.
+type Waiters = Arc<Mutex<HashMap<String, mpsc::UnboundedSender<OscPacket>>>>;
+
 /// Async OSC client for communicating with Ableton Live.
 ///
 /// Uses a single UDP socket for both sending and receiving. `AbletonOSC` replies
 /// to the sender's address, so each client instance automatically receives its
 /// own responses on its ephemeral port — no fixed port contention.
+///
+/// `AbletonOSC` replies on the address it was queried on, so a background task
+/// reads the socket and hands each reply to the query waiting on that address.
+/// Queries on different addresses run concurrently; queries on the same
+/// address take turns.
 pub struct OscClient {
     /// Single socket used for both sending and receiving OSC messages.
-    socket: UdpSocket,
+    socket: Arc<UdpSocket>,
     /// Address of `AbletonOSC` server.
     ableton_addr: SocketAddr,
     /// Timeout for waiting for responses.
     response_timeout: Duration,
+    /// Queries waiting for a reply.
+    waiters: Waiters,
+    /// One lock per queried address, held for the length of a query.
+    address_locks: Mutex<HashMap<String, Arc<tokio::sync::Mutex<()>>>>,
+    /// Task reading replies from the socket.
+    receiver: JoinHandle<()>,
 }
 
 impl OscClient {
     /// Create a new OSC client bound to an ephemeral port.
     pub async fn new() -> Result<Self, Error> {
-        let socket = UdpSocket::bind("127.0.0.1:0").await?;
         let ableton_addr: SocketAddr = format!("127.0.0.1:{ABLETON_OSC_PORT}").parse().unwrap();
+        Self::bind(ableton_addr).await
+    }
+
+    /// Create a client bound to an ephemeral port that talks to `ableton_addr`.
+    async fn bind(ableton_addr: SocketAddr) -> Result<Self, Error> {
+        let socket = Arc::new(UdpSocket::bind("127.0.0.1:0").await?);
+        let waiters = Waiters::default();
+        let receiver = tokio::spawn(receive(Arc::clone(&socket), Arc::clone(&waiters)));
 
         debug!(port = socket.local_addr()?.port(), "OSC client initialized");
 
@@ -44,6 +70,9 @@ impl OscClient {
             socket,
             ableton_addr,
             response_timeout: DEFAULT_TIMEOUT,
+            waiters,
+            address_locks: Mutex::default(),
+            receiver,
         })
     }
 
@@ -69,19 +98,17 @@ impl OscClient {
 
     /// Send an OSC message and wait for a response.
     pub async fn query<T: FromOsc>(&self, addr: &str, args: Vec<OscType>) -> Result<T, Error> {
-        // Clear any pending messages in the receive buffer
-        self.clear_recv_buffer().await;
+        let lock = self.address_lock(addr);
+        let _query = lock.lock().await;
+        let mut waiter = self.wait_for(addr);
 
         // Send the query
         self.send(addr, args).await?;
 
-        // Wait for response on the same socket we sent from
-        let mut buf = [0u8; 65536];
-        let (len, _src) =
-            tokio::time::timeout(self.response_timeout, self.socket.recv_from(&mut buf)).await??;
-
-        let (_, packet) = decoder::decode_udp(&buf[..len])?;
-        trace!(?packet, "Received OSC response");
+        // Wait for the receiver task to hand over the reply
+        let packet = tokio::time::timeout(self.response_timeout, waiter.replies.recv())
+            .await?
+            .ok_or(Error::Timeout)?;
 
         T::from_osc(packet)
     }
@@ -89,34 +116,84 @@ impl OscClient {
     /// Send an OSC message and collect multiple responses until timeout.
     #[allow(dead_code)]
     pub async fn query_all(&self, addr: &str, args: Vec<OscType>) -> Result<Vec<OscPacket>, Error> {
-        // Clear any pending messages
-        self.clear_recv_buffer().await;
+        let lock = self.address_lock(addr);
+        let _query = lock.lock().await;
+        let mut waiter = self.wait_for(addr);
 
         // Send the query
         self.send(addr, args).await?;
 
         // Collect responses until timeout
         let mut responses = Vec::new();
-        let mut buf = [0u8; 65536];
-
-        while let Ok(Ok((len, _src))) =
-            tokio::time::timeout(self.response_timeout, self.socket.recv_from(&mut buf)).await
+        while let Ok(Some(packet)) =
+            tokio::time::timeout(self.response_timeout, waiter.replies.recv()).await
         {
-            if let Ok((_, packet)) = decoder::decode_udp(&buf[..len]) {
-                responses.push(packet);
+            responses.push(packet);
+        }
+
+        Ok(responses)
+    }
+
+    /// Send messages as one OSC bundle and collect the replies on `reply_addr`.
+    ///
+    /// `AbletonOSC` handles every message of a bundle in one pass. Collection
+    /// stops once each message sent to `reply_addr` has been answered, or at
+    /// the timeout.
+    pub async fn query_bundle(
+        &self,
+        messages: Vec<OscMessage>,
+        reply_addr: &str,
+    ) -> Result<Vec<OscPacket>, Error> {
+        let expected = messages.iter().filter(|m| m.addr == reply_addr).count();
+        let bundle = OscPacket::Bundle(OscBundle {
+            // The OSC "immediately" time tag
+            timetag: OscTime {
+                seconds: 0,
+                fractional: 1,
+            },
+            content: messages.into_iter().map(OscPacket::Message).collect(),
+        });
+        let bytes = encoder::encode(&bundle)?;
+
+        let lock = self.address_lock(reply_addr);
+        let _query = lock.lock().await;
+        let mut waiter = self.wait_for(reply_addr);
+
+        trace!(address = reply_addr, expected, "Sending OSC bundle");
+        self.socket.send_to(&bytes, self.ableton_addr).await?;
+
+        let mut responses = Vec::with_capacity(expected);
+        while responses.len() < expected {
+            match tokio::time::timeout(self.response_timeout, waiter.replies.recv()).await {
+                Ok(Some(packet)) => responses.push(packet),
+                _ => break,
             }
         }
 
         Ok(responses)
     }
 
-    /// Clear any pending messages in the receive buffer.
-    async fn clear_recv_buffer(&self) {
-        let mut buf = [0u8; 1024];
-        while tokio::time::timeout(Duration::from_millis(1), self.socket.recv_from(&mut buf))
-            .await
-            .is_ok()
-        {}
+    /// Lock serializing queries on `addr`.
+    fn address_lock(&self, addr: &str) -> Arc<tokio::sync::Mutex<()>> {
+        let mut locks = self.address_locks.lock().unwrap();
+        Arc::clone(locks.entry(addr.to_string()).or_default())
+    }
+
+    /// Register for replies on `addr` until the returned waiter is dropped.
+    ///
+    /// Callers hold the address lock, so there is at most one waiter per
+    /// address.
+    fn wait_for(&self, addr: &str) -> Waiter<'_> {
+        let (sender, replies) = mpsc::unbounded_channel();
+        self.waiters
+            .lock()
+            .unwrap()
+            .insert(addr.to_string(), sender);
+        Waiter {
+            waiters: &self.waiters,
+            addr: addr.to_string(),
+            replies,
+        }
     }
 
     /// Test connection to Ableton Live.
@@ -133,6 +210,72 @@ impl OscClient {
     }
 }
 
+impl Drop for OscClient {
+    fn drop(&mut self) {
+        self.receiver.abort();
+    }
+}
+
+/// A query's registration for replies on one address.
+struct Waiter<'a> {
+    waiters: &'a Waiters,
+    addr: String,
+    replies: mpsc::UnboundedReceiver<OscPacket>,
+}
+
+impl Drop for Waiter<'_> {
+    fn drop(&mut self) {
+        self.waiters.lock().unwrap().remove(&self.addr);
+    }
+}
+
+/// Read replies from `socket` and hand each to the query waiting on its
+/// address.
+///
+/// Errors reported on `/live/error` go to the only waiting query, if there is
+/// exactly one. Replies nobody is waiting for (late replies to timed-out
+/// queries, or errors that cannot be attributed) are dropped.
+async fn receive(socket: Arc<UdpSocket>, waiters: Waiters) {
+    let mut buf = vec![0u8; 65536];
+    loop {
+        let len = match socket.recv_from(&mut buf).await {
+            Ok((len, _src)) => len,
+            Err(e) => {
+                debug!(error = %e, "OSC receive failed");
+                continue;
+            }
+        };
+        let Ok((_, packet)) = decoder::decode_udp(&buf[..len]) else {
+            continue;
+        };
+        trace!(?packet, "Received OSC response");
+
+        let Some(addr) = packet_addr(&packet).map(str::to_string) else {
+            continue;
+        };
+        let waiters = waiters.lock().unwrap();
+        let waiter = match waiters.get(&addr) {
+            Some(waiter) => Some(waiter),
+            None if addr == ERROR_ADDR && waiters.len() == 1 => waiters.values().next(),
+            None => None,
+        };
+        match waiter {
+            Some(waiter) => {
+                let _ = waiter.send(packet);
+            }
+            None => debug!(address = %addr, "Dropping OSC reply nobody is waiting for"),
+        }
+    }
+}
+
+/// Address of a packet: the message's, or the first message's in a bundle.
+fn packet_addr(packet: &OscPacket) -> Option<&str> {
+    match packet {
+        OscPacket::Message(msg) => Some(&msg.addr),
+        OscPacket::Bundle(bundle) => bundle.content.iter().find_map(packet_addr),
+    }
+}
+
 /// Lazy wrapper around [`OscClient`] that defers socket binding until first use.
 ///
 /// This allows the MCP server to start and complete the handshake even when
@@ -187,6 +330,18 @@ impl OscHandle {
         self.client().await?.query_all(addr, args).await
     }
 
+    /// Send messages as one OSC bundle and collect the replies on `reply_addr`.
+    pub async fn query_bundle(
+        &self,
+        messages: Vec<OscMessage>,
+        reply_addr: &str,
+    ) -> Result<Vec<OscPacket>, Error> {
+        self.client()
+            .await?
+            .query_bundle(messages, reply_addr)
+            .await
+    }
+
     /// Test connection to Ableton Live.
     pub async fn test_connection(&self) -> Result<bool, Error> {
         self.client().await?.test_connection().await
@@ -358,4 +513,57 @@ mod tests {
 
         mock_handle.await.unwrap();
     }
+
+    /// A slow reply on one address does not hold up a query on another, and
+    /// each query gets the reply for its own address.
+    #[tokio::test]
+    async fn queries_on_different_addresses_run_concurrently() {
+        let mock = Arc::new(UdpSocket::bind("127.0.0.1:0").await.unwrap());
+        let client = OscClient::bind(mock.local_addr().unwrap()).await.unwrap();
+
+        let server = Arc::clone(&mock);
+        let mock_handle = tokio::spawn(async move {
+            let mut buf = [0u8; 65536];
+            for _ in 0..2 {
+                let (len, sender) = server.recv_from(&mut buf).await.unwrap();
+                let (_, packet) = decoder::decode_udp(&buf[..len]).unwrap();
+                let OscPacket::Message(msg) = packet else {
+                    panic!("expected message");
+                };
+                let (value, delay) = if msg.addr == "/slow" {
+                    (1, Duration::from_millis(300))
+                } else {
+                    (2, Duration::ZERO)
+                };
+                let reply = encoder::encode(&OscPacket::Message(OscMessage {
+                    addr: msg.addr,
+                    args: vec![OscType::Int(value)],
+                }))
+                .unwrap();
+                let server = Arc::clone(&server);
+                tokio::spawn(async move {
+                    tokio::time::sleep(delay).await;
+                    server.send_to(&reply, sender).await.unwrap();
+                });
+            }
+        });
+
+        let started = std::time::Instant::now();
+        let slow = client.query::<i32>("/slow", vec![]);
+        let fast = async {
+            // Let the slow query go out first
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            let value = client.query::<i32>("/fast", vec![]).await;
+            (value, started.elapsed())
+        };
+        let (slow, (fast, fast_elapsed)) = tokio::join!(slow, fast);
+
+        assert_eq!(slow.unwrap(), 1);
+        assert_eq!(fast.unwrap(), 2);
+        assert!(
+            fast_elapsed < Duration::from_millis(200),
+            "fast query waited for the slow one ({fast_elapsed:?})"
+        );
+        mock_handle.await.unwrap();
+    }
 }
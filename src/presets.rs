@@ -0,0 +1,343 @@
+//! Device preset library: JSON snapshots of device parameters.
+//!
+//! Presets live in `<config dir>/presets/<class name>/<preset name>.json`.
+//! Parameters are stored by name, so a preset still loads after a device
+//! update adds, removes or reorders parameters.
+
+use std::collections::{HashMap, VecDeque};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::{config_dir, file_stem};
+use crate::error::Error;
+use crate::resolve::match_name;
+use crate::types::ParameterStructure;
+
+/// A named parameter value in a preset.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PresetParameter {
+    pub name: String,
+    pub value: f32,
+}
+
+/// A saved device state.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DevicePreset {
+    /// Preset name.
+    pub name: String,
+    /// Live class name of the device (e.g. `"Reverb"`, `"InstrumentVector"`).
+    pub class_name: String,
+    /// Device name when the preset was saved.
+    pub device_name: String,
+    /// Parameter values, in device order.
+    pub parameters: Vec<PresetParameter>,
+}
+
+/// Summary of a preset, returned by `list_device_presets`.
+#[derive(Debug, Clone, Serialize)]
+pub struct PresetSummary {
+    pub name: String,
+    pub class_name: String,
+    pub device_name: String,
+    pub parameter_count: usize,
+    pub path: PathBuf,
+}
+
+/// Result of mapping a preset onto a device's current parameters.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PresetMapping {
+    /// Full parameter vector for the device, with preset values applied.
+    pub values: Vec<f32>,
+    /// Number of parameters taken from the preset.
+    pub applied: usize,
+    /// Preset parameters with no counterpart on the device.
+    pub missing: Vec<String>,
+}
+
+/// Map a preset onto a device's parameters by name.
+///
+/// Values are clamped to each parameter's range. When a device has several
+/// parameters with the same name, they are matched in order.
+pub fn map_parameters(preset: &DevicePreset, current: &[ParameterStructure]) -> PresetMapping {
+    let mut by_name: HashMap<&str, VecDeque<f32>> = HashMap::new();
+    for parameter in &preset.parameters {
+        by_name
+            .entry(parameter.name.as_str())
+            .or_default()
+            .push_back(parameter.value);
+    }
+
+    let mut applied = 0;
+    let values = current
+        .iter()
+        .map(|parameter| {
+            match by_name
+                .get_mut(parameter.name.as_str())
+                .and_then(VecDeque::pop_front)
+            {
+                Some(value) => {
+                    applied += 1;
+                    value.clamp(parameter.min, parameter.max)
+                }
+                None => parameter.value,
+            }
+        })
+        .collect();
+
+    // Whatever was not consumed has no counterpart on the device
+    let mut missing = Vec::new();
+    for parameter in &preset.parameters {
+        let unused = by_name
+            .get_mut(parameter.name.as_str())
+            .and_then(VecDeque::pop_front);
+        if unused.is_some() {
+            missing.push(parameter.name.clone());
+        }
+    }
+
+    PresetMapping {
+        values,
+        applied,
+        missing,
+    }
+}
+
+/// Preset files on disk.
+pub struct PresetLibrary {
+    root: PathBuf,
+}
+
+impl PresetLibrary {
+    /// Open a library rooted at `root`.
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    /// Open the library in the remix-mcp config directory.
+    pub fn open_default() -> Result<Self, Error> {
+        Ok(Self::new(config_dir()?.join("presets")))
+    }
+
+    /// Path a preset is stored at.
+    pub fn path_for(&self, class_name: &str, name: &str) -> PathBuf {
+        self.root
+            .join(file_stem(class_name))
+            .join(format!("{}.json", file_stem(name)))
+    }
+
+    /// Save a preset and return its path.
+    ///
+    /// Fails if the preset exists, unless `overwrite` is set. Names that only
+    /// differ in characters replaced for the file name (e.g. `Bass/Lead` and
+    /// `Bass_Lead`) map to the same file; saving one never replaces the other.
+    pub fn save(&self, preset: &DevicePreset, overwrite: bool) -> Result<PathBuf, Error> {
+        let path = self.path_for(&preset.class_name, &preset.name);
+        if path.exists() {
+            match self.load(&path) {
+                Ok(existing)
+                    if existing.name != preset.name || existing.class_name != preset.class_name =>
+                {
+                    return Err(Error::InvalidParameter(format!(
+                        "Preset \"{}\" for {} would be stored in the same file as preset \"{}\" for {}; choose a different name",
+                        preset.name, preset.class_name, existing.name, existing.class_name
+                    )));
+                }
+                _ if !overwrite => {
+                    return Err(Error::InvalidParameter(format!(
+                        "Preset \"{}\" already exists for {} (set overwrite to replace it)",
+                        preset.name, preset.class_name
+                    )));
+                }
+                _ => {}
+            }
+        }
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| storage_error(parent, &e))?;
+        }
+        let json = serde_json::to_string_pretty(preset)
+            .map_err(|e| Error::Storage(format!("Could not encode preset: {e}")))?;
+        fs::write(&path, json).map_err(|e| storage_error(&path, &e))?;
+        Ok(path)
+    }
+
+    /// Load a preset file.
+    pub fn load(&self, path: &Path) -> Result<DevicePreset, Error> {
+        let json = fs::read_to_string(path).map_err(|e| storage_error(path, &e))?;
+        serde_json::from_str(&json)
+            .map_err(|e| Error::Storage(format!("Invalid preset {}: {e}", path.display())))
+    }
+
+    /// List all presets, optionally only those for one device class.
+    ///
+    /// Unreadable files are skipped.
+    pub fn list(&self, class_name: Option<&str>) -> Result<Vec<PresetSummary>, Error> {
+        let mut presets = Vec::new();
+        let Ok(classes) = fs::read_dir(&self.root) else {
+            return Ok(presets);
+        };
+
+        for class_dir in classes.flatten() {
+            let Ok(files) = fs::read_dir(class_dir.path()) else {
+                continue;
+            };
+            for file in files.flatten() {
+                let path = file.path();
+                if path.extension().is_none_or(|ext| ext != "json") {
+                    continue;
+                }
+                let Ok(preset) = self.load(&path) else {
+                    continue;
+                };
+                if class_name.is_some_and(|class| class != preset.class_name) {
+                    continue;
+                }
+                presets.push(PresetSummary {
+                    name: preset.name,
+                    class_name: preset.class_name,
+                    device_name: preset.device_name,
+                    parameter_count: preset.parameters.len(),
+                    path,
+                });
+            }
+        }
+
+        presets.sort_by(|a, b| (&a.class_name, &a.name).cmp(&(&b.class_name, &b.name)));
+        Ok(presets)
+    }
+
+    /// Find a preset by name, preferring presets saved from `class_name`.
+    ///
+    /// Presets of other classes are only searched when no preset of the
+    /// given class matches.
+    pub fn find(&self, class_name: &str, query: &str) -> Result<PresetSummary, Error> {
+        let same_class = self.list(Some(class_name))?;
+        let names: Vec<String> = same_class.iter().map(|p| p.name.clone()).collect();
+        let (presets, index) = match match_name("preset", query, &names) {
+            Ok(index) => (same_class, index),
+            Err(Error::NotFound { .. }) => {
+                let all = self.list(None)?;
+                let names: Vec<String> = all
+                    .iter()
+                    .map(|p| format!("{} ({})", p.name, p.class_name))
+                    .collect();
+                let index = match_name("preset", query, &names)?;
+                (all, index)
+            }
+            Err(e) => return Err(e),
+        };
+        presets
+            .into_iter()
+            .nth(index as usize)
+            .ok_or_else(|| Error::InvalidParameter(format!("Preset \"{query}\" not found")))
+    }
+}
+
+/// Wrap a file system error with the path involved.
+pub(crate) fn storage_error(path: &Path, err: &std::io::Error) -> Error {
+    Error::Storage(format!("{}: {err}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parameter(name: &str, value: f32) -> ParameterStructure {
+        ParameterStructure {
+            name: name.to_string(),
+            value,
+            min: 0.0,
+            max: 1.0,
+            is_quantized: false,
+        }
+    }
+
+    fn preset(parameters: &[(&str, f32)]) -> DevicePreset {
+        DevicePreset {
+            name: "Big Room".to_string(),
+            class_name: "Reverb".to_string(),
+            device_name: "Reverb".to_string(),
+            parameters: parameters
+                .iter()
+                .map(|(name, value)| PresetParameter {
+                    name: (*name).to_string(),
+                    value: *value,
+                })
+                .collect(),
+        }
+    }
+
+    /// A unique scratch directory for a test.
+    fn scratch_dir(test: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("remix-mcp-{test}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    /// Parameters map by name regardless of order; unknown names are reported.
+    #[test]
+    fn maps_parameters_by_name() {
+        let current = [
+            parameter("Device On", 1.0),
+            parameter("Decay", 0.2),
+            parameter("Size", 0.5),
+        ];
+        let mapping = map_parameters(
+            &preset(&[("Size", 0.9), ("Decay", 2.0), ("Shimmer", 0.3)]),
+            &current,
+        );
+        assert_eq!(mapping.values, vec![1.0, 1.0, 0.9]);
+        assert_eq!(mapping.applied, 2);
+        assert_eq!(mapping.missing, vec!["Shimmer".to_string()]);
+    }
+
+    /// Duplicate parameter names are matched in order.
+    #[test]
+    fn maps_duplicate_names_in_order() {
+        let current = [parameter("Gain", 0.0), parameter("Gain", 0.0)];
+        let mapping = map_parameters(
+            &preset(&[("Gain", 0.1), ("Gain", 0.2), ("Gain", 0.3)]),
+            &current,
+        );
+        assert_eq!(mapping.values, vec![0.1, 0.2]);
+        assert_eq!(mapping.missing, vec!["Gain".to_string()]);
+    }
+
+    /// Presets round-trip through the library and refuse accidental overwrites.
+    #[test]
+    fn saves_lists_and_loads_presets() {
+        let dir = scratch_dir("presets");
+        let library = PresetLibrary::new(&dir);
+        let saved = preset(&[("Decay", 0.7)]);
+
+        let path = library.save(&saved, false).unwrap();
+        assert!(library.save(&saved, false).is_err());
+        assert!(library.save(&saved, true).is_ok());
+        assert_eq!(library.load(&path).unwrap(), saved);
+
+        let listed = library.list(Some("Reverb")).unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].parameter_count, 1);
+        assert!(library.list(Some("Delay")).unwrap().is_empty());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    /// Names sharing a file name do not replace each other, even with overwrite.
+    #[test]
+    fn refuses_colliding_file_names() {
+        let dir = scratch_dir("preset-collisions");
+        let library = PresetLibrary::new(&dir);
+        let mut first = preset(&[("Decay", 0.7)]);
+        first.name = "Bass/Lead".to_string();
+        let mut second = first.clone();
+        second.name = "Bass_Lead".to_string();
+
+        let path = library.save(&first, false).unwrap();
+        assert!(library.save(&second, true).is_err());
+        assert_eq!(library.load(&path).unwrap(), first);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
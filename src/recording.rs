@@ -0,0 +1,129 @@
+//! Timing and naming for recording workflows.
+//!
+//! Recordings are launched into an empty clip slot with clip trigger
+//! quantization set to one bar, so they start on a bar line. Launches and
+//! stops are fired half a bar before the bar line they should land on.
+
+use serde::Serialize;
+
+/// Clip trigger quantization value for one bar.
+pub const QUANTIZE_ONE_BAR: i32 = 4;
+
+/// A clip produced by a recording workflow.
+#[derive(Debug, Clone, Serialize)]
+pub struct RecordedClip {
+    pub track: u32,
+    pub slot: u32,
+    pub name: String,
+    /// Clip length in beats.
+    pub length: f32,
+    pub is_audio_clip: bool,
+    /// Recorded audio file, for audio clips.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file_path: Option<String>,
+}
+
+/// Song beat a running recording started at.
+///
+/// Uses the clip's playing position when it is plausible, otherwise the bar
+/// line at or before `song_beat`.
+pub fn recording_start(song_beat: f64, playing_position: f64, beats_per_bar: f64) -> f64 {
+    if (0.0..=song_beat).contains(&playing_position) {
+        song_beat - playing_position
+    } else {
+        (song_beat / beats_per_bar).floor() * beats_per_bar
+    }
+}
+
+/// Bar line at or after `song_beat`.
+pub fn next_bar(song_beat: f64, beats_per_bar: f64) -> f64 {
+    (song_beat / beats_per_bar - 1e-6).ceil() * beats_per_bar
+}
+
+/// Song beat at which to fire a launch quantized to one bar so that it takes
+/// effect on the bar line at `boundary`.
+pub fn fire_beat(boundary: f64, beats_per_bar: f64) -> f64 {
+    boundary - beats_per_bar / 2.0
+}
+
+/// Name of take `number`, e.g. `"Vox Take 3"`.
+pub fn take_name(base: &str, number: u32) -> String {
+    format!("{base} Take {number}")
+}
+
+/// Number for the next take: one more than the highest `"<base> Take N"`
+/// among `names`, or 1.
+pub fn next_take_number(base: &str, names: &[String]) -> u32 {
+    let prefix = format!("{base} Take ");
+    names
+        .iter()
+        .filter_map(|name| name.strip_prefix(&prefix)?.parse::<u32>().ok())
+        .max()
+        .map_or(1, |n| n + 1)
+}
+
+/// Input routing type on another track that carries `source`'s output.
+///
+/// Live lists tracks as input sources by number and name, e.g. `"2-Bass"`;
+/// a plain name is accepted too. Returns the index into `types`.
+pub fn find_track_input(types: &[String], source: u32, name: &str) -> Option<usize> {
+    let numbered = format!("{}-{name}", source + 1);
+    if let Some(index) = types.iter().position(|t| *t == numbered || *t == name) {
+        return Some(index);
+    }
+    let suffix = format!("-{name}");
+    let mut matches = types
+        .iter()
+        .enumerate()
+        .filter(|(_, t)| t.ends_with(&suffix));
+    match (matches.next(), matches.next()) {
+        (Some((index, _)), None) => Some(index),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(names: &[&str]) -> Vec<String> {
+        names.iter().map(ToString::to_string).collect()
+    }
+
+    /// The start comes from the playing position, or the bar line before.
+    #[test]
+    fn recording_start_from_position() {
+        assert!((recording_start(17.25, 1.25, 4.0) - 16.0).abs() < 1e-9);
+        assert!((recording_start(17.25, -1.0, 4.0) - 16.0).abs() < 1e-9);
+        assert!((recording_start(2.0, 5.0, 4.0)).abs() < 1e-9);
+    }
+
+    /// Launches are fired half a bar before the bar line they land on.
+    #[test]
+    fn bar_lines() {
+        assert!((next_bar(17.25, 4.0) - 20.0).abs() < 1e-9);
+        assert!((next_bar(16.0, 4.0) - 16.0).abs() < 1e-9);
+        assert!((next_bar(0.0, 3.0)).abs() < 1e-9);
+        assert!((fire_beat(32.0, 4.0) - 30.0).abs() < 1e-9);
+    }
+
+    /// Takes continue counting from the highest existing take.
+    #[test]
+    fn take_numbers() {
+        let clips = names(&["Vox Take 1", "Vox Take 4", "Vox Take x", "Gtr Take 9"]);
+        assert_eq!(next_take_number("Vox", &clips), 5);
+        assert_eq!(next_take_number("Keys", &clips), 1);
+        assert_eq!(take_name("Vox", 5), "Vox Take 5");
+    }
+
+    /// Sources are found by number and name, or by a unique name suffix.
+    #[test]
+    fn finds_source_track_input() {
+        let types = names(&["Ext. In", "Resampling", "1-Drums", "2-Bass", "3-Bass Sub"]);
+        assert_eq!(find_track_input(&types, 1, "Bass"), Some(3));
+        assert_eq!(find_track_input(&types, 5, "Drums"), Some(2));
+        assert_eq!(find_track_input(&types, 0, "Keys"), None);
+        let twice = names(&["1-Pad", "4-Pad"]);
+        assert_eq!(find_track_input(&twice, 7, "Pad"), None);
+    }
+}
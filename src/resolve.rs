@@ -0,0 +1,301 @@
+//! Resolution of name-or-index references to tracks, scenes and devices.
+//!
+//! Tool parameters accept either a 0-based index or a name. Indices pass
+//! through untouched (no round-trip to Ableton); names are looked up against
+//! the current set and matched in tiers, from strictest to loosest:
+//!
+//! 1. Exact name
+//! 2. A numeric string, treated as an index (which must be in range)
+//! 3. Case-insensitive name
+//! 4. Normalized name (lowercase, alphanumerics only), so `"bass1"` finds `"Bass 1"`
+//! 5. Normalized substring, so `"bass"` finds `"Sub Bass"`
+//! 6. Normalized subsequence, so `"sbss"` finds `"Sub Bass"`
+//!
+//! The first tier with any match wins. If that tier matches more than one
+//! item the lookup fails with [`Error::Ambiguous`] listing the candidates.
+
+use rosc::OscType;
+
+use crate::error::Error;
+use crate::server::AbletonServer;
+use crate::types::{
+    DeviceId, DeviceRef, SceneId, SceneRef, TrackAddr, TrackId, TrackKind, TrackRef,
+};
+
+/// Lowercase a name and strip everything but letters and digits.
+fn normalize(name: &str) -> String {
+    name.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Check whether all characters of `needle` appear in `haystack` in order.
+fn is_subsequence(needle: &str, haystack: &str) -> bool {
+    let mut chars = haystack.chars();
+    needle.chars().all(|n| chars.any(|h| h == n))
+}
+
+/// Format a list of matching items as `index: "name"` for error messages.
+fn describe(names: &[String], indices: &[usize]) -> Vec<String> {
+    indices
+        .iter()
+        .map(|&i| format!("{i}: \"{}\"", names[i]))
+        .collect()
+}
+
+/// Find the index of the item in `names` that best matches `query`.
+///
+/// `kind` names the item type ("track", "scene", ...) for error messages.
+pub fn match_name(kind: &'static str, query: &str, names: &[String]) -> Result<u32, Error> {
+    let exact: Vec<usize> = (0..names.len()).filter(|&i| names[i] == query).collect();
+    if let Some(index) = pick(kind, query, names, &exact)? {
+        return Ok(index);
+    }
+
+    if let Ok(index) = query.trim().parse::<u32>() {
+        if (index as usize) < names.len() {
+            return Ok(index);
+        }
+        return Err(Error::NotFound {
+            kind,
+            query: query.to_string(),
+            available: describe(names, &(0..names.len()).collect::<Vec<_>>()),
+        });
+    }
+
+    let lower = query.to_lowercase();
+    let normalized = normalize(query);
+    let tiers: [&dyn Fn(&str) -> bool; 4] = [
+        &|name| name.to_lowercase() == lower,
+        &|name| !normalized.is_empty() && normalize(name) == normalized,
+        &|name| !normalized.is_empty() && normalize(name).contains(&normalized),
+        &|name| !normalized.is_empty() && is_subsequence(&normalized, &normalize(name)),
+    ];
+
+    for tier in tiers {
+        let matches: Vec<usize> = (0..names.len()).filter(|&i| tier(&names[i])).collect();
+        if let Some(index) = pick(kind, query, names, &matches)? {
+            return Ok(index);
+        }
+    }
+
+    Err(Error::NotFound {
+        kind,
+        query: query.to_string(),
+        available: describe(names, &(0..names.len()).collect::<Vec<_>>()),
+    })
+}
+
+/// Pick the single match of a tier, failing if the tier is ambiguous.
+fn pick(
+    kind: &'static str,
+    query: &str,
+    names: &[String],
+    matches: &[usize],
+) -> Result<Option<u32>, Error> {
+    match matches {
+        [] => Ok(None),
+        [index] => Ok(Some(*index as u32)),
+        _ => Err(Error::Ambiguous {
+            kind,
+            query: query.to_string(),
+            candidates: describe(names, matches),
+        }),
+    }
+}
+
+impl AbletonServer {
+    /// Resolve a track reference of the given kind.
+    ///
+    /// Return tracks are matched against the return track names.
+    pub(crate) async fn resolve_track_of(
+        &self,
+        kind: TrackKind,
+        track: &TrackRef,
+    ) -> Result<TrackAddr, Error> {
+        match (kind, track) {
+            (TrackKind::Regular, _) => Ok(TrackAddr::regular(self.resolve_track(track).await?.0)),
+            (TrackKind::Return, TrackRef::Index(index)) => Ok(TrackAddr::return_track(*index)),
+            (TrackKind::Return, TrackRef::Name(name)) => {
+                let names = self.return_track_names().await?;
+                match_name("return track", name, &names).map(TrackAddr::return_track)
+            }
+        }
+    }
+
+    /// Resolve a device reference on a track of any kind.
+    pub(crate) async fn resolve_device_on(
+        &self,
+        track: TrackAddr,
+        device: &DeviceRef,
+    ) -> Result<DeviceId, Error> {
+        match device {
+            DeviceRef::Index(index) => Ok(DeviceId(*index)),
+            DeviceRef::Name(name) => {
+                let (addr, args) = track.track_message("get/devices/name");
+                let names = self.query_names(&addr, args).await?;
+                match_name("device", name, &names).map(DeviceId)
+            }
+        }
+    }
+
+    /// Resolve a track reference to a track index.
+    pub(crate) async fn resolve_track(&self, track: &TrackRef) -> Result<TrackId, Error> {
+        match track {
+            TrackRef::Index(index) => Ok(TrackId(*index)),
+            TrackRef::Name(name) => {
+                let names = self
+                    .query_names("/live/song/get/track_names", vec![])
+                    .await?;
+                match_name("track", name, &names).map(TrackId)
+            }
+        }
+    }
+
+    /// Resolve a scene reference to a scene index.
+    pub(crate) async fn resolve_scene(&self, scene: &SceneRef) -> Result<SceneId, Error> {
+        match scene {
+            SceneRef::Index(index) => Ok(SceneId(*index)),
+            SceneRef::Name(name) => {
+                let names = self
+                    .query_names("/live/song/get/scenes/name", vec![])
+                    .await?;
+                match_name("scene", name, &names).map(SceneId)
+            }
+        }
+    }
+
+    /// Resolve a device reference to a device index on the given track.
+    pub(crate) async fn resolve_device(
+        &self,
+        track: TrackId,
+        device: &DeviceRef,
+    ) -> Result<DeviceId, Error> {
+        match device {
+            DeviceRef::Index(index) => Ok(DeviceId(*index)),
+            DeviceRef::Name(name) => {
+                let names = self
+                    .query_names(
+                        "/live/track/get/devices/name",
+                        vec![OscType::Int(track.into())],
+                    )
+                    .await?;
+                match_name("device", name, &names).map(DeviceId)
+            }
+        }
+    }
+
+    /// Names of the devices on a track.
+    pub(crate) async fn track_device_names(&self, track: u32) -> Result<Vec<String>, Error> {
+        self.query_names(
+            "/live/track/get/devices/name",
+            vec![OscType::Int(track as i32)],
+        )
+        .await
+    }
+
+    /// Names of the return tracks, in order (A, B, ...).
+    pub(crate) async fn return_track_names(&self) -> Result<Vec<String>, Error> {
+        self.query_names("/live/song/get/return_tracks", vec![])
+            .await
+    }
+
+    /// Query a bulk name list, keeping only the string arguments.
+    ///
+    /// The request arguments echoed at the start of the reply are skipped.
+    pub(crate) async fn query_names(
+        &self,
+        addr: &str,
+        args: Vec<OscType>,
+    ) -> Result<Vec<String>, Error> {
+        let echoed = args.len();
+        let response: Vec<OscType> = self.osc.query(addr, args).await?;
+        Ok(response
+            .into_iter()
+            .skip(echoed)
+            .filter_map(|arg| match arg {
+                OscType::String(s) => Some(s),
+                _ => None,
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(list: &[&str]) -> Vec<String> {
+        list.iter().map(ToString::to_string).collect()
+    }
+
+    /// Exact names win over every looser tier.
+    #[test]
+    fn exact_match_wins() {
+        let tracks = names(&["bass", "Bass", "Sub Bass"]);
+        assert_eq!(match_name("track", "Bass", &tracks).unwrap(), 1);
+    }
+
+    /// Numeric strings are treated as indices when no name matches exactly.
+    #[test]
+    fn numeric_string_is_index() {
+        let tracks = names(&["Drums", "Track 2", "Bass"]);
+        assert_eq!(match_name("track", "2", &tracks).unwrap(), 2);
+        assert_eq!(match_name("track", "Track 2", &tracks).unwrap(), 1);
+    }
+
+    /// Numeric strings past the end of the list are not found.
+    #[test]
+    fn numeric_string_out_of_range() {
+        let tracks = names(&["Drums", "Track 2"]);
+        let err = match_name("track", "2", &tracks).unwrap_err();
+        assert!(matches!(err, Error::NotFound { .. }));
+    }
+
+    /// Case-insensitive and normalized matches.
+    #[test]
+    fn case_and_punctuation_are_ignored() {
+        let tracks = names(&["Drums", "Bass 1", "Keys"]);
+        assert_eq!(match_name("track", "keys", &tracks).unwrap(), 2);
+        assert_eq!(match_name("track", "bass1", &tracks).unwrap(), 1);
+    }
+
+    /// Substring and subsequence matches resolve when unique.
+    #[test]
+    fn fuzzy_match_resolves_unique_candidate() {
+        let tracks = names(&["Drums", "Sub Bass", "Lead Vox"]);
+        assert_eq!(match_name("track", "bass", &tracks).unwrap(), 1);
+        assert_eq!(match_name("track", "ldvx", &tracks).unwrap(), 2);
+    }
+
+    /// Several matches in the winning tier produce an error listing them.
+    #[test]
+    fn ambiguous_match_lists_candidates() {
+        let tracks = names(&["Sub Bass", "Bass Lead", "Drums"]);
+        let err = match_name("track", "bass", &tracks).unwrap_err();
+        let message = err.to_string();
+        assert!(matches!(err, Error::Ambiguous { .. }));
+        assert!(message.contains("0: \"Sub Bass\""));
+        assert!(message.contains("1: \"Bass Lead\""));
+        assert!(!message.contains("Drums"));
+    }
+
+    /// Unknown names report what is available.
+    #[test]
+    fn missing_name_lists_available() {
+        let tracks = names(&["Drums", "Bass"]);
+        let err = match_name("track", "piano", &tracks).unwrap_err();
+        assert!(matches!(err, Error::NotFound { .. }));
+        assert!(err.to_string().contains("1: \"Bass\""));
+    }
+
+    /// References deserialize from either a JSON number or a string.
+    #[test]
+    fn references_deserialize_from_index_or_name() {
+        let index: TrackRef = serde_json::from_str("3").unwrap();
+        let name: TrackRef = serde_json::from_str("\"Bass\"").unwrap();
+        assert_eq!(index, TrackRef::Index(3));
+        assert_eq!(name, TrackRef::Name("Bass".to_string()));
+    }
+}
@@ -0,0 +1,80 @@
+//! Small seedable pseudo-random number generator (splitmix64).
+//!
+//! Used wherever tools need reproducible randomness: a seed returned to the
+//! caller recreates exactly the same result.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Seedable splitmix64 generator.
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    /// Create a generator from a seed.
+    pub const fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    /// Pick a seed from the system clock, for callers that did not give one.
+    pub fn seed_from_time() -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_nanos() as u64)
+    }
+
+    /// Next 64 random bits.
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut x = self.state;
+        x = (x ^ (x >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        x = (x ^ (x >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        x ^ (x >> 31)
+    }
+
+    /// Uniform value in `0.0..1.0`.
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Uniform value in `low..=high`.
+    pub fn range(&mut self, low: f32, high: f32) -> f32 {
+        // 24 random bits fill an f32 mantissa; dividing by their largest
+        // value makes both ends reachable.
+        let fraction = (self.next_u64() >> 40) as f32 / ((1u32 << 24) - 1) as f32;
+        (high - low).mul_add(fraction, low)
+    }
+
+    /// Uniform integer in `low..=high`.
+    pub fn range_int(&mut self, low: i64, high: i64) -> i64 {
+        let span = high.abs_diff(low) + 1;
+        low.min(high) + (self.next_u64() % span) as i64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The same seed always produces the same sequence.
+    #[test]
+    fn same_seed_same_sequence() {
+        let mut a = Rng::new(42);
+        let mut b = Rng::new(42);
+        for _ in 0..16 {
+            assert_eq!(a.next_u64(), b.next_u64());
+        }
+    }
+
+    /// Ranged values stay within their bounds.
+    #[test]
+    fn ranges_stay_in_bounds() {
+        let mut rng = Rng::new(7);
+        for _ in 0..1000 {
+            assert!((0.0..1.0).contains(&rng.next_f64()));
+            assert!((-2.0..=3.0).contains(&rng.range(-2.0, 3.0)));
+            assert!((0..=4).contains(&rng.range_int(0, 4)));
+        }
+    }
+}
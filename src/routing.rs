@@ -0,0 +1,436 @@
+//! Routing graph: how audio flows between tracks, returns and the master.
+//!
+//! Nodes are regular and return tracks plus the master, which `AbletonOSC`
+//! cannot read, so it only appears as a target. Edges come from output routing, input
+//! routing that takes audio from another track, and sends. Routing targets are
+//! matched against track names the way Live displays them, so `"Master"`, a
+//! group track's name or a return's name all resolve to nodes; anything else
+//! (e.g. `"Ext. Out"`) becomes an external node.
+
+use serde::Serialize;
+
+use crate::types::{TrackAddr, TrackKind};
+
+/// Output routing that sends audio nowhere.
+pub const NO_OUTPUT: &str = "No Output";
+
+/// Output routing used by tracks that only feed their sends.
+const SENDS_ONLY: &str = "Sends Only";
+
+/// Output routing of a track inside a group, as some Live versions report it.
+const GROUP: &str = "Group";
+
+/// Input routing that records the master output.
+const RESAMPLING: &str = "Resampling";
+
+/// Node id of the master track.
+pub const MASTER: &str = "master";
+
+/// Input routing types that do not come from another track.
+const EXTERNAL_INPUTS: &[&str] = &["Ext. In", "No Input", "Computer Keyboard", "All Ins"];
+
+/// Routing and sends of one track, as read from Live.
+#[derive(Debug, Clone)]
+pub struct TrackRouting {
+    pub track: TrackAddr,
+    pub name: String,
+    pub is_group: bool,
+    /// Enclosing group track (regular tracks only).
+    pub group: Option<u32>,
+    pub output_type: String,
+    pub output_channel: String,
+    pub input_type: Option<String>,
+    pub input_channel: Option<String>,
+    /// Send levels, one per return track.
+    pub sends: Vec<f32>,
+}
+
+/// A node in the routing graph.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RouteNode {
+    /// Stable id: `track:2`, `return:0`, `master` or `external:<name>`.
+    pub id: String,
+    pub name: String,
+    /// Track kind; `None` for the master and external inputs and outputs.
+    pub kind: Option<TrackKind>,
+    /// Index within the kind's list.
+    pub index: Option<u32>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub is_group: bool,
+}
+
+/// How audio gets from one node to another.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EdgeKind {
+    Output,
+    Input,
+    Send,
+}
+
+/// A directed audio connection.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RouteEdge {
+    pub from: String,
+    pub to: String,
+    pub kind: EdgeKind,
+    /// Routing type (output and input edges).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub routing_type: Option<String>,
+    /// Routing channel (output and input edges).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub channel: Option<String>,
+    /// Send index and level (send edges).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub send: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub level: Option<f32>,
+}
+
+/// The routing graph with detected problems.
+#[derive(Debug, Clone, Serialize)]
+pub struct RoutingGraph {
+    pub nodes: Vec<RouteNode>,
+    pub edges: Vec<RouteEdge>,
+    /// Tracks whose output is "No Output".
+    pub no_output: Vec<String>,
+    /// Cycles through outputs and sends, each as a list of node ids ending
+    /// where it started.
+    pub feedback_loops: Vec<Vec<String>>,
+}
+
+/// Node id of a track.
+pub fn node_id(track: TrackAddr) -> String {
+    match track.kind {
+        TrackKind::Regular => format!("track:{}", track.index),
+        TrackKind::Return => format!("return:{}", track.index),
+    }
+}
+
+impl RoutingGraph {
+    /// Build the graph from the routing of every track.
+    ///
+    /// `tracks` should include regular tracks and returns, with return sends
+    /// in return order. The master is added as a node of its own.
+    pub fn build(tracks: &[TrackRouting]) -> Self {
+        let mut nodes: Vec<RouteNode> = tracks
+            .iter()
+            .map(|t| RouteNode {
+                id: node_id(t.track),
+                name: t.name.clone(),
+                kind: Some(t.track.kind),
+                index: Some(t.track.index),
+                is_group: t.is_group,
+            })
+            .collect();
+        nodes.push(RouteNode {
+            id: MASTER.to_string(),
+            name: "Master".to_string(),
+            kind: None,
+            index: None,
+            is_group: false,
+        });
+
+        // Routing names resolve to the first track with that name
+        let by_name = |name: &str| {
+            tracks
+                .iter()
+                .find(|t| t.name.eq_ignore_ascii_case(name))
+                .map(|t| node_id(t.track))
+        };
+        let external = |name: &str, nodes: &mut Vec<RouteNode>| {
+            let id = format!("external:{name}");
+            if !nodes.iter().any(|n| n.id == id) {
+                nodes.push(RouteNode {
+                    id: id.clone(),
+                    name: name.to_string(),
+                    kind: None,
+                    index: None,
+                    is_group: false,
+                });
+            }
+            id
+        };
+
+        let mut edges = Vec::new();
+        let mut no_output = Vec::new();
+        for t in tracks {
+            let id = node_id(t.track);
+
+            // Output
+            let output = t.output_type.as_str();
+            let target = match output {
+                NO_OUTPUT => {
+                    no_output.push(id.clone());
+                    None
+                }
+                SENDS_ONLY | "" => None,
+                "Master" => Some(MASTER.to_string()),
+                GROUP => t.group.map(|g| node_id(TrackAddr::regular(g))),
+                _ => Some(by_name(output).unwrap_or_else(|| external(output, &mut nodes))),
+            };
+            if let Some(to) = target {
+                edges.push(RouteEdge {
+                    from: id.clone(),
+                    to,
+                    kind: EdgeKind::Output,
+                    routing_type: Some(t.output_type.clone()),
+                    channel: Some(t.output_channel.clone()),
+                    send: None,
+                    level: None,
+                });
+            }
+
+            // Input taken from another track
+            if let Some(input) = t.input_type.as_deref() {
+                let source = match input {
+                    "" => None,
+                    RESAMPLING => Some(MASTER.to_string()),
+                    _ if EXTERNAL_INPUTS.contains(&input) => None,
+                    _ => by_name(input),
+                };
+                if let Some(from) = source.filter(|from| *from != id) {
+                    edges.push(RouteEdge {
+                        from,
+                        to: id.clone(),
+                        kind: EdgeKind::Input,
+                        routing_type: Some(input.to_string()),
+                        channel: t.input_channel.clone(),
+                        send: None,
+                        level: None,
+                    });
+                }
+            }
+
+            // Sends that are turned up
+            for (send, &level) in t.sends.iter().enumerate() {
+                if level > 0.0 {
+                    edges.push(RouteEdge {
+                        from: id.clone(),
+                        to: node_id(TrackAddr::return_track(send as u32)),
+                        kind: EdgeKind::Send,
+                        routing_type: None,
+                        channel: None,
+                        send: Some(send as u32),
+                        level: Some(level),
+                    });
+                }
+            }
+        }
+
+        let feedback_loops = find_cycles(&nodes, &edges);
+        Self {
+            nodes,
+            edges,
+            no_output,
+            feedback_loops,
+        }
+    }
+
+    /// Render the graph as Graphviz DOT.
+    ///
+    /// Sends are dashed, tracks with no output are filled red, and edges that
+    /// are part of a feedback loop are drawn red.
+    pub fn to_dot(&self) -> String {
+        let quote = |s: &str| format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""));
+        let in_loop = |from: &str, to: &str| {
+            self.feedback_loops
+                .iter()
+                .any(|cycle| cycle.windows(2).any(|w| w[0] == from && w[1] == to))
+        };
+
+        let mut lines = vec![
+            "digraph routing {".to_string(),
+            "  rankdir=LR;".to_string(),
+            "  node [shape=box];".to_string(),
+        ];
+        for node in &self.nodes {
+            let mut attrs = vec![format!("label={}", quote(&node.name))];
+            match node.kind {
+                Some(TrackKind::Return) => attrs.push("shape=ellipse".to_string()),
+                None if node.id == MASTER => attrs.push("shape=doubleoctagon".to_string()),
+                None => attrs.push("shape=plaintext".to_string()),
+                Some(TrackKind::Regular) if node.is_group => {
+                    attrs.push("shape=folder".to_string());
+                }
+                Some(TrackKind::Regular) => {}
+            }
+            if self.no_output.contains(&node.id) {
+                attrs.push("style=filled fillcolor=\"#f4cccc\"".to_string());
+            }
+            lines.push(format!("  {} [{}];", quote(&node.id), attrs.join(" ")));
+        }
+        for edge in &self.edges {
+            let label = match edge.kind {
+                EdgeKind::Send => format!("send {:.2}", edge.level.unwrap_or(0.0)),
+                EdgeKind::Output | EdgeKind::Input => {
+                    let routing = edge.routing_type.as_deref().unwrap_or("");
+                    match edge.channel.as_deref() {
+                        Some(channel) if !channel.is_empty() => format!("{routing} / {channel}"),
+                        _ => routing.to_string(),
+                    }
+                }
+            };
+            let mut attrs = vec![format!("label={}", quote(&label))];
+            if edge.kind == EdgeKind::Send {
+                attrs.push("style=dashed".to_string());
+            }
+            if in_loop(&edge.from, &edge.to) {
+                attrs.push("color=red".to_string());
+            }
+            lines.push(format!(
+                "  {} -> {} [{}];",
+                quote(&edge.from),
+                quote(&edge.to),
+                attrs.join(" ")
+            ));
+        }
+        lines.push("}".to_string());
+        lines.join("\n")
+    }
+}
+
+/// Find cycles through outputs and sends, one per edge that closes a loop.
+///
+/// Each cycle lists node ids and repeats the first id at the end.
+fn find_cycles(nodes: &[RouteNode], edges: &[RouteEdge]) -> Vec<Vec<String>> {
+    let index = |id: &str| nodes.iter().position(|n| n.id == id);
+    let mut next: Vec<Vec<usize>> = vec![Vec::new(); nodes.len()];
+    // Inputs only carry audio while a track monitors them, and a resampling
+    // track routed to the master is normal, so only outputs and sends count
+    for edge in edges.iter().filter(|e| e.kind != EdgeKind::Input) {
+        if let (Some(from), Some(to)) = (index(&edge.from), index(&edge.to)) {
+            if !next[from].contains(&to) {
+                next[from].push(to);
+            }
+        }
+    }
+
+    // Depth-first search; a back edge to a node on the stack closes a cycle
+    let mut marks = vec![Mark::New; nodes.len()];
+    let mut cycles = Vec::new();
+    for node in 0..nodes.len() {
+        if marks[node] == Mark::New {
+            visit(node, &next, &mut marks, &mut Vec::new(), &mut cycles);
+        }
+    }
+    cycles
+        .into_iter()
+        .map(|cycle| cycle.into_iter().map(|n| nodes[n].id.clone()).collect())
+        .collect()
+}
+
+/// Depth-first search state of a node.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Mark {
+    New,
+    OnStack,
+    Done,
+}
+
+/// Visit `node` depth first, recording a cycle for each edge back to a node
+/// on the stack.
+fn visit(
+    node: usize,
+    next: &[Vec<usize>],
+    marks: &mut [Mark],
+    stack: &mut Vec<usize>,
+    cycles: &mut Vec<Vec<usize>>,
+) {
+    marks[node] = Mark::OnStack;
+    stack.push(node);
+    for &to in &next[node] {
+        match marks[to] {
+            Mark::New => visit(to, next, marks, stack, cycles),
+            Mark::OnStack => {
+                let start = stack.iter().position(|&n| n == to).unwrap_or(0);
+                let mut cycle = stack[start..].to_vec();
+                cycle.push(to);
+                cycles.push(cycle);
+            }
+            Mark::Done => {}
+        }
+    }
+    stack.pop();
+    marks[node] = Mark::Done;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn routing(track: TrackAddr, name: &str, output: &str, sends: &[f32]) -> TrackRouting {
+        TrackRouting {
+            track,
+            name: name.to_string(),
+            is_group: false,
+            group: None,
+            output_type: output.to_string(),
+            output_channel: String::new(),
+            input_type: None,
+            input_channel: None,
+            sends: sends.to_vec(),
+        }
+    }
+
+    fn set() -> Vec<TrackRouting> {
+        let mut drums = routing(TrackAddr::regular(0), "Drums", "Master", &[]);
+        drums.is_group = true;
+        let mut kick = routing(TrackAddr::regular(1), "Kick", "Drums", &[0.5, 0.0]);
+        kick.group = Some(0);
+        let mut resample = routing(TrackAddr::regular(3), "Resample", "Master", &[]);
+        resample.input_type = Some("Resampling".to_string());
+        vec![
+            drums,
+            kick,
+            routing(TrackAddr::regular(2), "Pad", "No Output", &[]),
+            resample,
+            routing(
+                TrackAddr::return_track(0),
+                "A-Reverb",
+                "Master",
+                &[0.0, 0.3],
+            ),
+            routing(
+                TrackAddr::return_track(1),
+                "B-Delay",
+                "Ext. Out",
+                &[0.2, 0.0],
+            ),
+        ]
+    }
+
+    /// Outputs, inputs and sends become edges; unknown targets are external.
+    #[test]
+    fn builds_edges_from_routing() {
+        let graph = RoutingGraph::build(&set());
+        let has = |from: &str, to: &str, kind: EdgeKind| {
+            graph
+                .edges
+                .iter()
+                .any(|e| e.from == from && e.to == to && e.kind == kind)
+        };
+        assert!(has("track:1", "track:0", EdgeKind::Output));
+        assert!(has("track:0", "master", EdgeKind::Output));
+        assert!(has("track:1", "return:0", EdgeKind::Send));
+        assert!(!has("track:1", "return:1", EdgeKind::Send));
+        assert!(has("master", "track:3", EdgeKind::Input));
+        assert!(has("return:1", "external:Ext. Out", EdgeKind::Output));
+        assert_eq!(graph.no_output, vec!["track:2"]);
+    }
+
+    /// Returns sending to each other form a feedback loop.
+    #[test]
+    fn flags_feedback_loops() {
+        let graph = RoutingGraph::build(&set());
+        assert_eq!(
+            graph.feedback_loops,
+            vec![vec!["return:0", "return:1", "return:0"]]
+        );
+        let dot = graph.to_dot();
+        assert!(dot.contains(
+            "\"return:0\" -> \"return:1\" [label=\"send 0.30\" style=dashed color=red];"
+        ));
+        assert!(dot.contains("\"track:2\" [label=\"Pad\" style=filled fillcolor=\"#f4cccc\"];"));
+    }
+}
@@ -0,0 +1,363 @@
+//! Scheduled tool calls, fired at a song position.
+//!
+//! Each pending action runs as a tokio task that follows the song position
+//! and calls its tool once the song reaches the target beat. Positions are
+//! song beats; bars and grid sizes are converted with the song's time
+//! signature numerator, so a bar is that many beats. Finished actions are
+//! kept in a short history with the tool's result.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+use tokio::task::JoinHandle;
+
+use crate::error::Error;
+
+/// Fired actions kept before the oldest is dropped.
+const HISTORY_LEN: usize = 16;
+
+/// Grid names accepted for quantized scheduling, as (name, length in bars).
+const BAR_GRIDS: [(&str, f64); 5] = [
+    ("bar", 1.0),
+    ("1 bar", 1.0),
+    ("2 bars", 2.0),
+    ("4 bars", 4.0),
+    ("8 bars", 8.0),
+];
+
+/// Grid names accepted for quantized scheduling, as (name, length in beats).
+const BEAT_GRIDS: [(&str, f64); 6] = [
+    ("beat", 1.0),
+    ("1/2", 2.0),
+    ("1/4", 1.0),
+    ("1/8", 0.5),
+    ("1/16", 0.25),
+    ("1/32", 0.125),
+];
+
+/// A tool call waiting for its song position.
+#[derive(Debug, Clone, Serialize)]
+pub struct ScheduledAction {
+    /// Tool to call.
+    pub tool: String,
+    /// Arguments passed to the tool.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub arguments: Option<Map<String, Value>>,
+    /// Song beat the action fires at.
+    pub beat: f64,
+    /// Target as Live shows it, `bar.beat.sixteenth`.
+    pub position: String,
+}
+
+/// Snapshot of a pending action, returned by `list_scheduled_actions`.
+#[derive(Debug, Clone, Serialize)]
+pub struct ActionStatus {
+    /// Action id, used to cancel it.
+    pub id: u32,
+    #[serde(flatten)]
+    pub action: ScheduledAction,
+}
+
+/// An action that has fired, with the tool's result.
+#[derive(Debug, Clone, Serialize)]
+pub struct FiredAction {
+    pub id: u32,
+    pub tool: String,
+    pub position: String,
+    /// Text returned by the tool, or the error it failed with.
+    pub result: String,
+}
+
+/// A pending action task.
+struct Pending {
+    action: ScheduledAction,
+    task: JoinHandle<()>,
+}
+
+/// Registry of pending and fired actions.
+#[derive(Default)]
+struct Registry {
+    next_id: u32,
+    pending: HashMap<u32, Pending>,
+    fired: VecDeque<FiredAction>,
+}
+
+/// Scheduler that owns all pending actions.
+///
+/// Cloning the scheduler shares the same registry.
+#[derive(Clone, Default)]
+pub struct Scheduler {
+    registry: Arc<Mutex<Registry>>,
+}
+
+impl Scheduler {
+    /// Create a scheduler with no pending actions.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queue an action and return its id.
+    ///
+    /// `fire` waits for the target position, calls the tool and resolves to
+    /// the result text, which is moved to the history.
+    pub fn schedule<F>(&self, action: ScheduledAction, fire: F) -> u32
+    where
+        F: Future<Output = String> + Send + 'static,
+    {
+        let mut registry = self.registry.lock().unwrap();
+        let id = registry.next_id;
+        registry.next_id += 1;
+
+        let scheduler = self.clone();
+        let task = tokio::spawn(async move {
+            let result = fire.await;
+            scheduler.finish(id, result);
+        });
+        registry.pending.insert(id, Pending { action, task });
+        id
+    }
+
+    /// Move a fired action from the pending list to the history.
+    fn finish(&self, id: u32, result: String) {
+        let mut registry = self.registry.lock().unwrap();
+        let Some(pending) = registry.pending.remove(&id) else {
+            return;
+        };
+        if registry.fired.len() == HISTORY_LEN {
+            registry.fired.pop_back();
+        }
+        registry.fired.push_front(FiredAction {
+            id,
+            tool: pending.action.tool,
+            position: pending.action.position,
+            result,
+        });
+    }
+
+    /// List pending actions, soonest first.
+    pub fn pending(&self) -> Vec<ActionStatus> {
+        let registry = self.registry.lock().unwrap();
+        let mut statuses: Vec<_> = registry
+            .pending
+            .iter()
+            .map(|(&id, pending)| ActionStatus {
+                id,
+                action: pending.action.clone(),
+            })
+            .collect();
+        statuses.sort_by(|a, b| {
+            a.action
+                .beat
+                .total_cmp(&b.action.beat)
+                .then(a.id.cmp(&b.id))
+        });
+        statuses
+    }
+
+    /// Recently fired actions, most recent first.
+    pub fn fired(&self) -> Vec<FiredAction> {
+        self.registry
+            .lock()
+            .unwrap()
+            .fired
+            .iter()
+            .cloned()
+            .collect()
+    }
+
+    /// Cancel a pending action.
+    ///
+    /// Returns the cancelled action, or `None` if no such id is pending.
+    pub fn cancel(&self, id: u32) -> Option<ActionStatus> {
+        let pending = self.registry.lock().unwrap().pending.remove(&id)?;
+        pending.task.abort();
+        Some(ActionStatus {
+            id,
+            action: pending.action,
+        })
+    }
+
+    /// Cancel every pending action and return how many were cancelled.
+    pub fn cancel_all(&self) -> usize {
+        let mut registry = self.registry.lock().unwrap();
+        let count = registry.pending.len();
+        for (_, pending) in registry.pending.drain() {
+            pending.task.abort();
+        }
+        count
+    }
+}
+
+/// Song beat at `bar` and `beat`, both counted from 1 as in Live.
+///
+/// `beat` may be fractional, e.g. 2.5 for the "and" of beat 2.
+pub fn bar_beat(bar: u32, beat: f64, beats_per_bar: f64) -> Result<f64, Error> {
+    if bar == 0 {
+        return Err(Error::InvalidParameter(
+            "Bars are counted from 1".to_string(),
+        ));
+    }
+    if !(1.0..beats_per_bar + 1.0).contains(&beat) {
+        return Err(Error::InvalidParameter(format!(
+            "Beat {beat} is outside the bar (1 to {beats_per_bar} in this time signature)"
+        )));
+    }
+    Ok(f64::from(bar - 1) * beats_per_bar + beat - 1.0)
+}
+
+/// Song beat as Live shows it, `bar.beat.sixteenth`, counted from 1.
+pub fn format_position(song_beat: f64, beats_per_bar: f64) -> String {
+    let sixteenths = song_beat.max(0.0).mul_add(4.0, 1e-6).floor() as u64;
+    let per_bar = (beats_per_bar * 4.0).round().max(1.0) as u64;
+    let bar = sixteenths / per_bar + 1;
+    let beat = sixteenths % per_bar / 4 + 1;
+    let sixteenth = sixteenths % 4 + 1;
+    format!("{bar}.{beat}.{sixteenth}")
+}
+
+/// Length in beats of a named quantization grid: `"beat"`, `"bar"`,
+/// `"2 bars"`, `"4 bars"`, `"8 bars"` or a note value from `"1/2"` to
+/// `"1/32"`.
+pub fn parse_grid(name: &str, beats_per_bar: f64) -> Result<f64, Error> {
+    let name = name.trim().to_lowercase();
+    if let Some((_, bars)) = BAR_GRIDS.iter().find(|(n, _)| *n == name) {
+        return Ok(bars * beats_per_bar);
+    }
+    if let Some((_, beats)) = BEAT_GRIDS.iter().find(|(n, _)| *n == name) {
+        return Ok(*beats);
+    }
+    let names: Vec<&str> = BAR_GRIDS
+        .iter()
+        .chain(&BEAT_GRIDS)
+        .map(|(n, _)| *n)
+        .collect();
+    Err(Error::InvalidParameter(format!(
+        "Unknown quantization \"{name}\". Use \"global\" or one of: {}",
+        names.join(", ")
+    )))
+}
+
+/// Length in beats of Live's clip trigger quantization value, or `None`
+/// when quantization is off.
+pub fn trigger_quantization_grid(value: i32, beats_per_bar: f64) -> Option<f64> {
+    let triplet = 2.0 / 3.0;
+    match value {
+        1 => Some(8.0 * beats_per_bar),
+        2 => Some(4.0 * beats_per_bar),
+        3 => Some(2.0 * beats_per_bar),
+        4 => Some(beats_per_bar),
+        5 => Some(2.0),
+        6 => Some(2.0 * triplet),
+        7 => Some(1.0),
+        8 => Some(triplet),
+        9 => Some(0.5),
+        10 => Some(0.5 * triplet),
+        11 => Some(0.25),
+        12 => Some(0.25 * triplet),
+        13 => Some(0.125),
+        _ => None,
+    }
+}
+
+/// First grid line strictly after `song_beat`.
+pub fn next_boundary(song_beat: f64, grid: f64) -> f64 {
+    ((song_beat / grid + 1e-6).floor() + 1.0) * grid
+}
+
+/// Song beat at which to send a quantized launch so that it starts at
+/// `target`.
+///
+/// Live starts a launch on the next grid line after it arrives, so a launch
+/// aimed at a grid line is sent half a grid early, well inside the quantum
+/// before it. A target between grid lines cannot be hit; it is sent on time
+/// and starts on the next grid line.
+pub fn launch_send_beat(target: f64, grid: f64) -> f64 {
+    let lines = target / grid;
+    if (lines - lines.round()).abs() < 1e-6 {
+        (target - grid / 2.0).max(0.0)
+    } else {
+        target
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    fn action(beat: f64) -> ScheduledAction {
+        ScheduledAction {
+            tool: "play".to_string(),
+            arguments: None,
+            beat,
+            position: format_position(beat, 4.0),
+        }
+    }
+
+    /// Bars and beats count from 1; the beat must fall inside the bar.
+    #[test]
+    fn bar_beat_positions() {
+        assert!((bar_beat(1, 1.0, 4.0).unwrap()).abs() < 1e-9);
+        assert!((bar_beat(17, 1.0, 4.0).unwrap() - 64.0).abs() < 1e-9);
+        assert!((bar_beat(3, 2.5, 3.0).unwrap() - 7.5).abs() < 1e-9);
+        assert!(bar_beat(0, 1.0, 4.0).is_err());
+        assert!(bar_beat(2, 5.0, 4.0).is_err());
+        assert_eq!(format_position(64.0, 4.0), "17.1.1");
+        assert_eq!(format_position(7.75, 3.0), "3.2.4");
+    }
+
+    /// Launches aimed at a grid line go out half a grid early; others on time.
+    #[test]
+    fn launch_send_beats() {
+        assert!((launch_send_beat(16.0, 4.0) - 14.0).abs() < 1e-9);
+        assert!((launch_send_beat(16.0, 1.0) - 15.5).abs() < 1e-9);
+        assert!((launch_send_beat(17.0, 4.0) - 17.0).abs() < 1e-9);
+        assert!(launch_send_beat(0.0, 4.0).abs() < 1e-9);
+        assert!((next_boundary(launch_send_beat(16.0, 4.0), 4.0) - 16.0).abs() < 1e-9);
+    }
+
+    /// Grid names and Live's quantization values map to lengths in beats.
+    #[test]
+    fn grids() {
+        assert!((parse_grid("2 Bars", 3.0).unwrap() - 6.0).abs() < 1e-9);
+        assert!((parse_grid("1/16", 4.0).unwrap() - 0.25).abs() < 1e-9);
+        assert!(parse_grid("1/3", 4.0).is_err());
+        assert_eq!(trigger_quantization_grid(0, 4.0), None);
+        assert!((trigger_quantization_grid(4, 4.0).unwrap() - 4.0).abs() < 1e-9);
+        assert!((trigger_quantization_grid(8, 4.0).unwrap() - 2.0 / 3.0).abs() < 1e-9);
+    }
+
+    /// The next boundary is always ahead, even on a grid line.
+    #[test]
+    fn boundaries() {
+        assert!((next_boundary(17.25, 4.0) - 20.0).abs() < 1e-9);
+        assert!((next_boundary(16.0, 4.0) - 20.0).abs() < 1e-9);
+        assert!((next_boundary(1.1, 0.5) - 1.5).abs() < 1e-9);
+    }
+
+    /// Actions are listed soonest first, cancelled, or moved to the history
+    /// when they fire.
+    #[tokio::test]
+    async fn schedules_and_cancels_actions() {
+        let scheduler = Scheduler::new();
+        let late = scheduler.schedule(action(32.0), std::future::pending());
+        let soon = scheduler.schedule(action(8.0), std::future::pending());
+        let ids: Vec<u32> = scheduler.pending().iter().map(|s| s.id).collect();
+        assert_eq!(ids, vec![soon, late]);
+        assert_eq!(scheduler.cancel(late).unwrap().action.position, "9.1.1");
+        assert!(scheduler.cancel(late).is_none());
+
+        let fired = scheduler.schedule(action(0.0), async { "Started playback".to_string() });
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        let history = scheduler.fired();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].id, fired);
+        assert_eq!(history[0].result, "Started playback");
+
+        assert_eq!(scheduler.cancel_all(), 1);
+        assert!(scheduler.pending().is_empty());
+    }
+}
@@ -1,18 +1,38 @@
 //! MCP Server implementation for Ableton Live control.
 
+use std::sync::atomic::{AtomicU64, Ordering};
+
 use rmcp::handler::server::router::tool::ToolRouter;
 use rmcp::handler::server::tool::ToolCallContext;
 use rmcp::model::*;
-use rmcp::service::RequestContext;
+use rmcp::service::{Peer, RequestContext};
 use rmcp::{RoleServer, ServerHandler};
+use tokio_util::sync::CancellationToken;
 use tracing::info;
 
+use crate::audition::Auditioner;
+use crate::browser_index::BrowserIndex;
+use crate::macros::MacroVariationStore;
+use crate::mix_state::MixStateStack;
+use crate::modulation::ModulationEngine;
 use crate::osc::OscHandle;
+use crate::scheduler::Scheduler;
+use crate::tools::mixer::GainUndo;
+
+/// Request ids handed to tool calls the server makes itself.
+static INTERNAL_REQUESTS: AtomicU64 = AtomicU64::new(0);
 
 /// MCP Server for controlling Ableton Live via OSC.
 #[derive(Clone)]
 pub struct AbletonServer {
     pub(crate) osc: OscHandle,
+    pub(crate) modulations: ModulationEngine,
+    pub(crate) macro_variations: MacroVariationStore,
+    pub(crate) browser_index: BrowserIndex,
+    pub(crate) auditioner: Auditioner,
+    pub(crate) gain_undo: GainUndo,
+    pub(crate) mix_states: MixStateStack,
+    pub(crate) scheduler: Scheduler,
     tool_router: ToolRouter<Self>,
 }
 
@@ -42,14 +62,85 @@ impl AbletonServer {
             + Self::tracks_router()
             + Self::clips_router()
             + Self::application_router()
-            + Self::midimap_router();
+            + Self::midimap_router()
+            + Self::modulation_router()
+            + Self::macros_router()
+            + Self::presets_router()
+            + Self::sound_design_router()
+            + Self::audition_router()
+            + Self::mixer_router()
+            + Self::templates_router()
+            + Self::recording_router()
+            + Self::scheduler_router();
 
         info!(
             "AbletonServer initialized with {} tools",
             tool_router.list_all().len()
         );
 
-        Self { osc, tool_router }
+        Self {
+            osc,
+            modulations: ModulationEngine::new(),
+            macro_variations: MacroVariationStore::new(),
+            browser_index: BrowserIndex::new(),
+            auditioner: Auditioner::new(),
+            gain_undo: GainUndo::new(),
+            mix_states: MixStateStack::new(),
+            scheduler: Scheduler::new(),
+            tool_router,
+        }
+    }
+
+    /// Stop background work started by tools (running modulations, scheduled
+    /// actions, browser crawls, sample auditions).
+    ///
+    /// Call this when the MCP service ends so no task keeps writing to Live.
+    pub async fn shutdown(&self) {
+        let stopped = self.modulations.stop_all();
+        if stopped > 0 {
+            info!("Stopped {stopped} active modulation(s)");
+        }
+        let cancelled = self.scheduler.cancel_all();
+        if cancelled > 0 {
+            info!("Cancelled {cancelled} scheduled action(s)");
+        }
+        self.browser_index.stop();
+        self.auditioner.stop().await;
+    }
+
+    /// Whether a tool with this name is registered.
+    pub(crate) fn has_tool(&self, name: &str) -> bool {
+        self.tool_router.has_route(name)
+    }
+
+    /// Call a tool by name, as a client would.
+    ///
+    /// The call gets a request context of its own, with a fresh cancellation
+    /// token and request id, so it does not depend on the request that queued
+    /// it (e.g. a `schedule_action` call that has long since returned). Only
+    /// the `peer` is shared.
+    pub(crate) async fn call_tool_by_name(
+        &self,
+        name: String,
+        arguments: Option<JsonObject>,
+        peer: Peer<RoleServer>,
+    ) -> Result<CallToolResult, rmcp::ErrorData> {
+        let id = INTERNAL_REQUESTS.fetch_add(1, Ordering::Relaxed);
+        let context = RequestContext {
+            ct: CancellationToken::new(),
+            id: NumberOrString::String(format!("internal-{id}").into()),
+            meta: Meta::default(),
+            extensions: Extensions::default(),
+            peer,
+        };
+        let request = CallToolRequestParams {
+            meta: None,
+            name: name.into(),
+            arguments,
+            task: None,
+        };
+        let tool_context = ToolCallContext::new(self, request, context);
+        self.tool_router.call(tool_context).await
     }
 }
 
@@ -0,0 +1,353 @@
+//! Sound design helpers: seeded parameter mutation and state morphing.
+//!
+//! These work on the parameter list returned by `get_device_parameters_detailed`
+//! and produce a full value vector for `set_all_device_parameters`.
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::Error;
+use crate::rng::Rng;
+use crate::types::ParameterStructure;
+
+/// Parameter left alone unless explicitly included: mutating it would
+/// switch the device off half the time.
+const DEVICE_ON: &str = "Device On";
+
+/// Most variations one `mutate_device` call may generate.
+pub const MAX_VARIATIONS: u32 = 16;
+
+/// A parameter value before and after a change.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ParameterChange {
+    pub index: u32,
+    pub name: String,
+    pub before: f32,
+    pub after: f32,
+}
+
+/// One mutation of a device's parameters.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Variation {
+    /// Seed that reproduces this variation.
+    pub seed: u64,
+    /// Every parameter value, for `set_all_device_parameters`.
+    pub values: Vec<f32>,
+    pub changes: Vec<ParameterChange>,
+}
+
+/// Result of `mutate_device`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeviceMutation {
+    pub amount: f32,
+    /// Parameter values before the call, to restore the original sound.
+    pub original: Vec<f32>,
+    /// Seed of the variation applied to the device, if any.
+    pub applied: Option<u64>,
+    pub variations: Vec<Variation>,
+}
+
+/// Which parameters a mutation may touch.
+#[derive(Debug, Clone, Default)]
+pub struct ParameterFilter {
+    /// Only these parameters (case-insensitive names). Empty means all.
+    pub include: Vec<String>,
+    /// Never these parameters (case-insensitive names).
+    pub exclude: Vec<String>,
+}
+
+impl ParameterFilter {
+    /// Whether a parameter may be changed.
+    fn allows(&self, name: &str) -> bool {
+        let listed = |names: &[String]| names.iter().any(|n| n.eq_ignore_ascii_case(name));
+        if listed(&self.exclude) {
+            return false;
+        }
+        if self.include.is_empty() {
+            !name.eq_ignore_ascii_case(DEVICE_ON)
+        } else {
+            listed(&self.include)
+        }
+    }
+
+    /// Check that every included name exists on the device.
+    fn validate(&self, parameters: &[ParameterStructure]) -> Result<(), Error> {
+        for name in &self.include {
+            if !parameters.iter().any(|p| p.name.eq_ignore_ascii_case(name)) {
+                return Err(Error::NotFound {
+                    kind: "parameter",
+                    query: name.clone(),
+                    available: parameters.iter().map(|p| p.name.clone()).collect(),
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Perturb parameters by `amount` (0..1, a fraction of each parameter's range).
+///
+/// Continuous parameters move by up to `amount` of their range in either
+/// direction. Quantized parameters jump to another valid step with
+/// probability `amount`. Returns the full value vector and the values that
+/// changed.
+pub fn mutate_parameters(
+    parameters: &[ParameterStructure],
+    amount: f32,
+    filter: &ParameterFilter,
+    rng: &mut Rng,
+) -> Result<(Vec<f32>, Vec<ParameterChange>), Error> {
+    if !(0.0..=1.0).contains(&amount) {
+        return Err(Error::InvalidParameter(format!(
+            "Mutation amount must be between 0 and 1, got {amount}"
+        )));
+    }
+    filter.validate(parameters)?;
+
+    let mut values = Vec::with_capacity(parameters.len());
+    let mut changes = Vec::new();
+    for (index, parameter) in parameters.iter().enumerate() {
+        if !filter.allows(&parameter.name) || parameter.max <= parameter.min {
+            values.push(parameter.value);
+            continue;
+        }
+
+        let after = if parameter.is_quantized {
+            mutate_step(parameter, amount, rng)
+        } else {
+            let offset = (parameter.max - parameter.min) * amount * rng.range(-1.0, 1.0);
+            (parameter.value + offset).clamp(parameter.min, parameter.max)
+        };
+        values.push(after);
+        if (after - parameter.value).abs() > f32::EPSILON {
+            changes.push(ParameterChange {
+                index: index as u32,
+                name: parameter.name.clone(),
+                before: parameter.value,
+                after,
+            });
+        }
+    }
+    Ok((values, changes))
+}
+
+/// Generate `count` variations with seeds `seed`, `seed + 1`, ...
+///
+/// Every variation starts from the current values, so any one of them can be
+/// reproduced on its own by passing its seed with a count of 1.
+pub fn mutate_variations(
+    parameters: &[ParameterStructure],
+    amount: f32,
+    filter: &ParameterFilter,
+    seed: u64,
+    count: u32,
+) -> Result<Vec<Variation>, Error> {
+    if !(1..=MAX_VARIATIONS).contains(&count) {
+        return Err(Error::InvalidParameter(format!(
+            "count must be between 1 and {MAX_VARIATIONS}, got {count}"
+        )));
+    }
+    (0..u64::from(count))
+        .map(|offset| {
+            let seed = seed.wrapping_add(offset);
+            let (values, changes) =
+                mutate_parameters(parameters, amount, filter, &mut Rng::new(seed))?;
+            Ok(Variation {
+                seed,
+                values,
+                changes,
+            })
+        })
+        .collect()
+}
+
+/// Pick a different whole step of a quantized parameter with probability `amount`.
+fn mutate_step(parameter: &ParameterStructure, amount: f32, rng: &mut Rng) -> f32 {
+    let low = parameter.min.round() as i64;
+    let high = parameter.max.round() as i64;
+    let current = parameter.value.round() as i64;
+    if high <= low || rng.next_f64() >= f64::from(amount) {
+        return parameter.value;
+    }
+
+    // Draw from the other steps so a jump always changes the value
+    let mut step = rng.range_int(low, high - 1);
+    if step >= current {
+        step += 1;
+    }
+    step.clamp(low, high) as f32
+}
+
+/// Two full parameter states of a device to morph between.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MorphStates {
+    from: Vec<f32>,
+    to: Vec<f32>,
+    quantized: Vec<bool>,
+}
+
+impl MorphStates {
+    /// Build morph states for a device.
+    ///
+    /// Both states must have one value per parameter; values are clamped to
+    /// each parameter's range.
+    pub fn new(parameters: &[ParameterStructure], from: &[f32], to: &[f32]) -> Result<Self, Error> {
+        for (label, state) in [("from", from), ("to", to)] {
+            if state.len() != parameters.len() {
+                return Err(Error::InvalidParameter(format!(
+                    "State '{label}' has {} values, but the device has {} parameters",
+                    state.len(),
+                    parameters.len()
+                )));
+            }
+        }
+        let clamp = |state: &[f32]| {
+            state
+                .iter()
+                .zip(parameters)
+                .map(|(value, p)| value.clamp(p.min, p.max))
+                .collect()
+        };
+        Ok(Self {
+            from: clamp(from),
+            to: clamp(to),
+            quantized: parameters.iter().map(|p| p.is_quantized).collect(),
+        })
+    }
+
+    /// Parameter values at `position` (0.0 = `from`, 1.0 = `to`).
+    ///
+    /// Continuous parameters are interpolated linearly; quantized parameters
+    /// switch from `from` to `to` at the midpoint.
+    pub fn at(&self, position: f32) -> Vec<f32> {
+        let position = position.clamp(0.0, 1.0);
+        self.from
+            .iter()
+            .zip(&self.to)
+            .zip(&self.quantized)
+            .map(|((&from, &to), &quantized)| {
+                if quantized {
+                    if position < 0.5 { from } else { to }
+                } else {
+                    (to - from).mul_add(position, from)
+                }
+            })
+            .collect()
+    }
+
+    /// Number of parameters that differ between the two states.
+    pub fn changed_count(&self) -> usize {
+        self.from
+            .iter()
+            .zip(&self.to)
+            .filter(|(a, b)| (*a - *b).abs() > f32::EPSILON)
+            .count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parameter(name: &str, value: f32, max: f32, is_quantized: bool) -> ParameterStructure {
+        ParameterStructure {
+            name: name.to_string(),
+            value,
+            min: 0.0,
+            max,
+            is_quantized,
+        }
+    }
+
+    fn device() -> Vec<ParameterStructure> {
+        vec![
+            parameter("Device On", 1.0, 1.0, true),
+            parameter("Filter Freq", 0.5, 1.0, false),
+            parameter("Osc Shape", 2.0, 4.0, true),
+            parameter("Volume", 0.8, 1.0, false),
+        ]
+    }
+
+    /// Mutations stay in range, quantized values land on steps, and
+    /// "Device On" is left alone by default.
+    #[test]
+    fn mutations_respect_ranges_and_steps() {
+        let parameters = device();
+        for seed in 0..200 {
+            let (values, changes) = mutate_parameters(
+                &parameters,
+                1.0,
+                &ParameterFilter::default(),
+                &mut Rng::new(seed),
+            )
+            .unwrap();
+            assert_eq!(values.len(), parameters.len());
+            assert!((values[0] - 1.0).abs() < f32::EPSILON);
+            assert!((0.0..=1.0).contains(&values[1]));
+            assert!((values[2] - values[2].round()).abs() < f32::EPSILON);
+            assert!((0.0..=4.0).contains(&values[2]));
+            assert!(changes.iter().all(|c| c.name != "Device On"));
+        }
+    }
+
+    /// The same seed reproduces the same mutation.
+    #[test]
+    fn seed_reproduces_mutation() {
+        let parameters = device();
+        let filter = ParameterFilter::default();
+        let a = mutate_parameters(&parameters, 0.3, &filter, &mut Rng::new(9)).unwrap();
+        let b = mutate_parameters(&parameters, 0.3, &filter, &mut Rng::new(9)).unwrap();
+        assert_eq!(a, b);
+    }
+
+    /// Include and exclude lists pick which parameters change.
+    #[test]
+    fn filters_limit_changed_parameters() {
+        let parameters = device();
+        let filter = ParameterFilter {
+            include: vec!["filter freq".to_string(), "Volume".to_string()],
+            exclude: vec!["volume".to_string()],
+        };
+        let (_, changes) = mutate_parameters(&parameters, 0.5, &filter, &mut Rng::new(1)).unwrap();
+        let names: Vec<&str> = changes.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["Filter Freq"]);
+
+        let unknown = ParameterFilter {
+            include: vec!["Resonance".to_string()],
+            exclude: Vec::new(),
+        };
+        assert!(mutate_parameters(&parameters, 0.5, &unknown, &mut Rng::new(1)).is_err());
+    }
+
+    /// Each variation matches a single mutation with its own seed, and the
+    /// count is bounded.
+    #[test]
+    fn variations_reproduce_individually() {
+        let parameters = device();
+        let filter = ParameterFilter::default();
+        let variations = mutate_variations(&parameters, 0.3, &filter, 40, 3).unwrap();
+        let seeds: Vec<u64> = variations.iter().map(|v| v.seed).collect();
+        assert_eq!(seeds, vec![40, 41, 42]);
+        let (values, changes) =
+            mutate_parameters(&parameters, 0.3, &filter, &mut Rng::new(41)).unwrap();
+        assert_eq!(variations[1].values, values);
+        assert_eq!(variations[1].changes, changes);
+
+        assert!(mutate_variations(&parameters, 0.3, &filter, 0, 0).is_err());
+        assert!(mutate_variations(&parameters, 0.3, &filter, 0, MAX_VARIATIONS + 1).is_err());
+    }
+
+    /// Continuous parameters interpolate; quantized ones switch at the midpoint.
+    #[test]
+    fn morph_interpolates_and_switches_steps() {
+        let parameters = device();
+        let states =
+            MorphStates::new(&parameters, &[1.0, 0.0, 0.0, 0.5], &[1.0, 1.0, 3.0, 0.5]).unwrap();
+        assert_eq!(states.changed_count(), 2);
+        assert_eq!(states.at(0.0), vec![1.0, 0.0, 0.0, 0.5]);
+        assert_eq!(states.at(0.25), vec![1.0, 0.25, 0.0, 0.5]);
+        assert_eq!(states.at(0.5), vec![1.0, 0.5, 3.0, 0.5]);
+        assert_eq!(states.at(2.0), vec![1.0, 1.0, 3.0, 0.5]);
+
+        assert!(MorphStates::new(&parameters, &[0.0], &[1.0, 1.0, 3.0, 0.5]).is_err());
+    }
+}
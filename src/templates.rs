@@ -0,0 +1,273 @@
+//! Track templates: recipes for fully configured tracks.
+//!
+//! Templates live in `<config dir>/track_templates/<name>.toml` (or `.json`).
+//! A template names the track type, the instrument and effects to load by
+//! browser path, optional device presets to apply, and the mixer and routing
+//! settings; `create_track_from_template` runs the whole recipe on a new track.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use crate::config::{config_dir, file_stem};
+use crate::error::Error;
+use crate::presets::storage_error;
+use crate::resolve::match_name;
+use crate::types::{TrackTemplate, TrackType};
+
+impl TrackTemplate {
+    /// Check the parts of the recipe that do not need Live.
+    pub fn validate(&self) -> Result<(), Error> {
+        if self.instrument.is_some() && self.track_type == TrackType::Audio {
+            return Err(Error::InvalidParameter(
+                "Audio tracks cannot hold an instrument".to_string(),
+            ));
+        }
+        if let Some(volume) = &self.volume {
+            volume.to_fader()?;
+        }
+        if let Some(pan) = &self.pan {
+            pan.to_value()?;
+        }
+        if let Some(send) = self.sends.iter().find(|s| !(0.0..=1.0).contains(&s.level)) {
+            return Err(Error::InvalidParameter(format!(
+                "Send level {} must be between 0.0 and 1.0",
+                send.level
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Summary of a template, returned by `list_track_templates`.
+#[derive(Debug, Clone, Serialize)]
+pub struct TemplateSummary {
+    pub name: String,
+    pub description: Option<String>,
+    pub track_type: TrackType,
+    pub instrument: Option<String>,
+    pub effects: Vec<String>,
+    pub path: PathBuf,
+}
+
+/// Template files on disk.
+pub struct TemplateLibrary {
+    root: PathBuf,
+}
+
+impl TemplateLibrary {
+    /// Open a library rooted at `root`.
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    /// Open the library in the remix-mcp config directory.
+    pub fn open_default() -> Result<Self, Error> {
+        Ok(Self::new(config_dir()?.join("track_templates")))
+    }
+
+    /// Save a template as TOML and return its path.
+    ///
+    /// Fails if a template with that name exists, unless `overwrite` is set.
+    pub fn save(
+        &self,
+        name: &str,
+        template: &TrackTemplate,
+        overwrite: bool,
+    ) -> Result<PathBuf, Error> {
+        let stem = file_stem(name);
+        let existing = ["toml", "json"]
+            .iter()
+            .map(|ext| self.root.join(format!("{stem}.{ext}")))
+            .find(|path| path.exists());
+        if existing.is_some() && !overwrite {
+            return Err(Error::InvalidParameter(format!(
+                "Track template \"{name}\" already exists (set overwrite to replace it)"
+            )));
+        }
+        if let Some(existing) = existing {
+            fs::remove_file(&existing).map_err(|e| storage_error(&existing, &e))?;
+        }
+        fs::create_dir_all(&self.root).map_err(|e| storage_error(&self.root, &e))?;
+        let path = self.root.join(format!("{stem}.toml"));
+        let text = toml::to_string_pretty(template)
+            .map_err(|e| Error::Storage(format!("Could not encode track template: {e}")))?;
+        fs::write(&path, text).map_err(|e| storage_error(&path, &e))?;
+        Ok(path)
+    }
+
+    /// Load a template file, reading TOML or JSON by extension.
+    pub fn load(&self, path: &Path) -> Result<TrackTemplate, Error> {
+        let text = fs::read_to_string(path).map_err(|e| storage_error(path, &e))?;
+        let invalid = |e: &dyn std::fmt::Display| {
+            Error::Storage(format!("Invalid track template {}: {e}", path.display()))
+        };
+        if path.extension().is_some_and(|ext| ext == "json") {
+            serde_json::from_str(&text).map_err(|e| invalid(&e))
+        } else {
+            toml::from_str(&text).map_err(|e| invalid(&e))
+        }
+    }
+
+    /// List all templates, sorted by name.
+    ///
+    /// Unreadable files are skipped.
+    pub fn list(&self) -> Result<Vec<TemplateSummary>, Error> {
+        let mut templates = Vec::new();
+        let Ok(files) = fs::read_dir(&self.root) else {
+            return Ok(templates);
+        };
+
+        for file in files.flatten() {
+            let path = file.path();
+            if path
+                .extension()
+                .is_none_or(|ext| ext != "toml" && ext != "json")
+            {
+                continue;
+            }
+            let Some(name) = path.file_stem().map(|s| s.to_string_lossy().into_owned()) else {
+                continue;
+            };
+            let Ok(template) = self.load(&path) else {
+                continue;
+            };
+            templates.push(TemplateSummary {
+                name,
+                description: template.description,
+                track_type: template.track_type,
+                instrument: template.instrument.map(|d| d.path),
+                effects: template.effects.into_iter().map(|d| d.path).collect(),
+                path,
+            });
+        }
+
+        templates.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(templates)
+    }
+
+    /// Find a template by name and load it.
+    pub fn find(&self, query: &str) -> Result<(TemplateSummary, TrackTemplate), Error> {
+        let templates = self.list()?;
+        let names: Vec<String> = templates.iter().map(|t| t.name.clone()).collect();
+        let index = match_name("track template", query, &names)?;
+        let summary = templates.into_iter().nth(index as usize).ok_or_else(|| {
+            Error::InvalidParameter(format!("Track template \"{query}\" not found"))
+        })?;
+        let template = self.load(&summary.path)?;
+        Ok((summary, template))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{Pan, TemplateDevice, TemplateRouting, TemplateSend, TrackRef, Volume};
+
+    /// A unique scratch directory for a test.
+    fn scratch_dir(test: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("remix-mcp-{test}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn bass() -> TrackTemplate {
+        TrackTemplate {
+            description: Some("Sub bass with saturation".to_string()),
+            name: Some("Bass".to_string()),
+            color: Some(0x00FF_0000),
+            instrument: Some(TemplateDevice {
+                path: "instruments/Operator".to_string(),
+                preset: Some("Sub".to_string()),
+            }),
+            effects: vec![TemplateDevice {
+                path: "audio_effects/Saturator".to_string(),
+                preset: None,
+            }],
+            volume: Some(Volume::Db("-6 dB".to_string())),
+            pan: Some(Pan::Label("C".to_string())),
+            sends: vec![TemplateSend {
+                send: TrackRef::Name("A-Reverb".to_string()),
+                level: 0.2,
+            }],
+            output: Some(TemplateRouting {
+                routing_type: Some("Master".to_string()),
+                channel: None,
+            }),
+            ..TrackTemplate::default()
+        }
+    }
+
+    /// Templates read from hand-written TOML with defaults filled in.
+    #[test]
+    fn parses_toml() {
+        let template: TrackTemplate = toml::from_str(
+            r#"
+            name = "Vox"
+            track_type = "audio"
+            volume = 0.7
+            pan = "10L"
+
+            [input]
+            type = "Ext. In"
+            channel = "1"
+
+            [[effects]]
+            path = "audio_effects/EQ Eight"
+            preset = "Vocal Cut"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(template.track_type, TrackType::Audio);
+        assert_eq!(template.volume, Some(Volume::Fader(0.7)));
+        assert_eq!(template.effects[0].preset.as_deref(), Some("Vocal Cut"));
+        assert_eq!(
+            template.input.unwrap().routing_type.as_deref(),
+            Some("Ext. In")
+        );
+        assert!(template.sends.is_empty());
+    }
+
+    /// Recipes that cannot work are rejected before touching Live.
+    #[test]
+    fn validates_recipes() {
+        assert!(bass().validate().is_ok());
+        let audio = TrackTemplate {
+            track_type: TrackType::Audio,
+            ..bass()
+        };
+        assert!(audio.validate().is_err());
+        let loud = TrackTemplate {
+            volume: Some(Volume::Db("+9 dB".to_string())),
+            ..bass()
+        };
+        assert!(loud.validate().is_err());
+    }
+
+    /// Templates round-trip through the library, in TOML and JSON.
+    #[test]
+    fn saves_lists_and_finds_templates() {
+        let dir = scratch_dir("track-templates");
+        let library = TemplateLibrary::new(&dir);
+
+        let path = library.save("Sub Bass", &bass(), false).unwrap();
+        assert!(library.save("Sub Bass", &bass(), false).is_err());
+        assert_eq!(library.load(&path).unwrap(), bass());
+
+        let json =
+            serde_json::json!({ "name": "Keys", "instrument": { "path": "instruments/Electric" } });
+        fs::write(dir.join("Keys.json"), json.to_string()).unwrap();
+
+        let listed = library.list().unwrap();
+        let names: Vec<&str> = listed.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, ["Keys", "Sub Bass"]);
+        assert_eq!(listed[1].effects, ["audio_effects/Saturator"]);
+
+        let (summary, template) = library.find("keys").unwrap();
+        assert_eq!(summary.name, "Keys");
+        assert_eq!(template.track_type, TrackType::Midi);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
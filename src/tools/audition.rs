@@ -0,0 +1,204 @@
+//! Sample audition tools: preview candidates in turn, mark keepers, load them.
+//!
+//! Candidates come from the browser index. Keepers are loaded into empty clip
+//! slots by browser path, with the category's loader (e.g.
+//! `/live/browser/load_sample [name]`).
+
+use rmcp::handler::server::wrapper::Parameters;
+use rmcp::{tool, tool_router};
+use rosc::OscType;
+
+use crate::audition::lead_in_beats;
+use crate::browser_index::{BrowserItem, SearchFilter};
+use crate::error::Error;
+use crate::server::AbletonServer;
+use crate::types::{AuditionSamplesParams, LoadMarkedSamplesParams, MarkSampleParams};
+
+#[tool_router(router = audition_router, vis = "pub")]
+impl AbletonServer {
+    /// Preview a folder or search result one item at a time.
+    #[tool(
+        description = "Audition samples from a browser folder or index search: previews each candidate in turn for N beats at the song tempo, starting on the next boundary when the song plays. Returns the numbered candidate list; mark keepers with mark_sample"
+    )]
+    pub async fn audition_samples(
+        &self,
+        Parameters(params): Parameters<AuditionSamplesParams>,
+    ) -> Result<String, Error> {
+        let beats = params.beats.unwrap_or(4.0);
+        if beats <= 0.0 {
+            return Err(Error::InvalidParameter(
+                "beats must be greater than 0".to_string(),
+            ));
+        }
+        let limit = params.limit.unwrap_or(16) as usize;
+
+        self.browser_index.ensure_fresh();
+        let filter = SearchFilter {
+            loadable_only: true,
+            ..SearchFilter::default()
+        };
+        let (items, source) = match (&params.folder, &params.query) {
+            (Some(folder), None) => (
+                self.browser_index.within(folder, &filter, limit),
+                format!("folder \"{folder}\""),
+            ),
+            (None, Some(query)) => {
+                let filter = SearchFilter {
+                    category: Some(params.category.unwrap_or_else(|| "samples".to_string())),
+                    ..filter
+                };
+                (
+                    self.browser_index.search(query, &filter, limit),
+                    format!("search \"{query}\""),
+                )
+            }
+            _ => {
+                return Err(Error::InvalidParameter(
+                    "Give either folder or query".to_string(),
+                ));
+            }
+        };
+        if items.is_empty() {
+            return Err(Error::InvalidParameter(format!(
+                "No loadable items found for {source} (see browser_index_status if the index is still being built)"
+            )));
+        }
+
+        let tempo: f32 = self.osc.query("/live/song/get/tempo", vec![]).await?;
+        let song_time: f32 = self
+            .osc
+            .query("/live/song/get/current_song_time", vec![])
+            .await?;
+        let playing: bool = self
+            .osc
+            .query("/live/song/get/is_playing", vec![])
+            .await
+            .unwrap_or(false);
+        let lead_in = lead_in_beats(f64::from(song_time), f64::from(beats), playing);
+
+        let status = self
+            .auditioner
+            .start(self.osc.clone(), items, beats, tempo, lead_in);
+        let mut lines = vec![format!(
+            "Auditioning {} candidates from {source}, {beats} beats each at {tempo} BPM{}:",
+            status.candidates.len(),
+            if lead_in > 0.0 {
+                format!(" (starting in {lead_in:.2} beats)")
+            } else {
+                String::new()
+            }
+        )];
+        lines.extend(
+            status
+                .candidates
+                .iter()
+                .map(|c| format!("{}: {}", c.index, c.path)),
+        );
+        Ok(lines.join("\n"))
+    }
+
+    /// Mark the current (or a given) candidate as a keeper.
+    #[tool(
+        description = "Mark an auditioned sample as a keeper: the one playing now, or a candidate index. Set unmark to remove the mark"
+    )]
+    pub async fn mark_sample(
+        &self,
+        Parameters(params): Parameters<MarkSampleParams>,
+    ) -> Result<String, Error> {
+        let keep = !params.unmark.unwrap_or(false);
+        let candidate = self
+            .auditioner
+            .mark(params.index.map(|i| i as usize), keep)?;
+        let marked = self.auditioner.marked().len();
+        Ok(format!(
+            "{} {}: {} ({marked} marked)",
+            if keep { "Marked" } else { "Unmarked" },
+            candidate.index,
+            candidate.name
+        ))
+    }
+
+    /// Show the audition list, what is playing and what is marked.
+    #[tool(
+        description = "Get the current audition: candidates, the one playing now and the marked keepers"
+    )]
+    pub async fn get_audition(&self) -> Result<String, Error> {
+        let status = self
+            .auditioner
+            .status()
+            .ok_or_else(|| Error::InvalidParameter("No audition has been started".to_string()))?;
+        Ok(serde_json::to_string_pretty(&status).unwrap_or_else(|_| "{}".into()))
+    }
+
+    /// Load the marked samples into clip slots.
+    #[tool(
+        description = "Load the samples marked with mark_sample into empty clip slots on a track (default: selected track), one slot per sample"
+    )]
+    pub async fn load_marked_samples(
+        &self,
+        Parameters(params): Parameters<LoadMarkedSamplesParams>,
+    ) -> Result<String, Error> {
+        let items = self.auditioner.marked();
+        if items.is_empty() {
+            return Err(Error::InvalidParameter(
+                "No samples are marked; use mark_sample first".to_string(),
+            ));
+        }
+        self.auditioner.stop().await;
+
+        let track = self.resolve_load_track(params.track.as_ref()).await?;
+        let lines = self
+            .load_samples_into_slots(&items, track, params.start_slot.unwrap_or(0))
+            .await?;
+        Ok(lines.join("\n"))
+    }
+
+    // ========== Helper methods for internal use ==========
+
+    /// Load samples into the empty clip slots from `start` on, one line per sample.
+    async fn load_samples_into_slots(
+        &self,
+        items: &[BrowserItem],
+        track: u32,
+        start: u32,
+    ) -> Result<Vec<String>, Error> {
+        let scenes: i32 = self.osc.query("/live/song/get/num_scenes", vec![]).await?;
+        let scenes = u32::try_from(scenes).unwrap_or(0);
+        let mut lines = Vec::new();
+        let mut slot = start;
+        for item in items {
+            while slot < scenes {
+                let has_clip = self
+                    .query_flag(
+                        "/live/clip_slot/get/has_clip",
+                        vec![OscType::Int(track as i32), OscType::Int(slot as i32)],
+                    )
+                    .await?;
+                if !has_clip {
+                    break;
+                }
+                slot += 1;
+            }
+            if slot >= scenes {
+                lines.push(format!(
+                    "{}: not loaded, no empty clip slot left",
+                    item.path
+                ));
+                continue;
+            }
+            let loaded = match self.item_loader(&item.path) {
+                Ok((_, addr, args)) => {
+                    self.load_into_clip_slot(addr, args, &item.path, track, slot)
+                        .await
+                }
+                Err(e) => Err(e),
+            };
+            lines.push(match loaded {
+                Ok(message) => message,
+                Err(e) => format!("{}: {e}", item.path),
+            });
+            slot += 1;
+        }
+        Ok(lines)
+    }
+}
@@ -4,14 +4,93 @@ use rmcp::handler::server::wrapper::Parameters;
 use rmcp::{tool, tool_router};
 use rosc::{OscPacket, OscType};
 
+use crate::browser_index::{IndexSearch, SearchFilter};
 use crate::error::Error;
 use crate::server::AbletonServer;
+use crate::tools::devices::{POLL_ATTEMPTS, POLL_INTERVAL};
 use crate::types::{
-    BrowseParams, BrowsePathParams, DeviceParams, GetBrowserItemParams,
-    ListWithOptionalCategoryParams, LoadByNameParams, LoadDrumKitParams, LoadUserPresetParams,
-    SearchBrowserParams,
+    BrowseParams, BrowsePathParams, DeviceId, DeviceParams, GetBrowserItemParams,
+    ListWithOptionalCategoryParams, LoadBrowserItemParams, LoadByNameParams, LoadDeviceParams,
+    LoadDrumKitParams, LoadUserPresetParams, SearchBrowserIndexParams, SearchBrowserParams,
+    TrackId, TrackRef,
 };
 
+/// Where a loaded device should go.
+struct Placement<'a> {
+    /// Target track (defaults to the selected track).
+    track: Option<&'a TrackRef>,
+    /// Insert position in the track's device chain.
+    position: Option<u32>,
+}
+
+/// Loader for each browser category. Each takes the item's name, except
+/// `load_user_preset`, which takes the path within the user library.
+const LOADERS: [(&str, &str); 10] = [
+    ("sounds", "/live/browser/load_sound"),
+    ("drums", "/live/browser/load_drum_kit"),
+    ("instruments", "/live/browser/load_instrument"),
+    ("audio_effects", "/live/browser/load_audio_effect"),
+    ("midi_effects", "/live/browser/load_midi_effect"),
+    ("max_for_live", "/live/browser/load_max_device"),
+    ("plugins", "/live/browser/load_plugin"),
+    ("clips", "/live/browser/load_clip"),
+    ("samples", "/live/browser/load_sample"),
+    ("user_library", "/live/browser/load_user_preset"),
+];
+
+/// Loader address and arguments for a full browser path such as
+/// `sounds/Pad/Warm Analog Pad.adv`.
+///
+/// Returns the lowercase category with the address and arguments. The path
+/// is not checked against the browser index; a failed load names the
+/// closest indexed matches instead. See [`AbletonServer::item_loader`] for
+/// the check against same-name items.
+pub(crate) fn browser_loader(path: &str) -> Result<(String, &'static str, Vec<OscType>), Error> {
+    let invalid = || {
+        let categories: Vec<&str> = LOADERS.iter().map(|(category, _)| *category).collect();
+        Error::InvalidParameter(format!(
+            "Browser path \"{path}\" must start with a loadable category ({})",
+            categories.join(", ")
+        ))
+    };
+    let (category, relative) = path
+        .split_once('/')
+        .filter(|(_, relative)| !relative.is_empty())
+        .ok_or_else(invalid)?;
+    let category = category.to_lowercase();
+    let addr = LOADERS
+        .iter()
+        .find(|(name, _)| *name == category)
+        .map(|(_, addr)| *addr)
+        .ok_or_else(invalid)?;
+    let arg = if category == "user_library" {
+        relative
+    } else {
+        relative.rsplit('/').next().unwrap_or(relative)
+    };
+    Ok((category, addr, vec![OscType::String(arg.to_string())]))
+}
+
+/// Device to select before loading onto a track with `devices` devices.
+///
+/// Live inserts a loaded device after the selected one, so the device in
+/// front of `position` is selected, or the last device when no position is
+/// given. Live cannot insert in front of the first device, so position 0
+/// needs an empty track.
+fn insertion(position: Option<u32>, devices: usize, track: u32) -> Result<Option<u32>, Error> {
+    let end = devices as u32;
+    match position {
+        Some(position) if position > end => Err(Error::InvalidParameter(format!(
+            "Position {position} is past the end of track {track} ({devices} devices)"
+        ))),
+        Some(0) if devices > 0 => Err(Error::InvalidParameter(format!(
+            "Live loads devices after the selected device, so position 0 is only possible on an empty track (track {track} has {devices} devices)"
+        ))),
+        Some(position) => Ok(position.checked_sub(1)),
+        None => Ok(end.checked_sub(1)),
+    }
+}
+
 /// Extract strings from OSC packets.
 fn extract_strings_from_packets(packets: Vec<OscPacket>) -> Vec<String> {
     packets
@@ -52,20 +131,16 @@ impl AbletonServer {
         Ok("Loaded default instrument (Drift synth)".to_string())
     }
 
-    /// Load an instrument by name onto the selected track.
-    #[tool(description = "Load an instrument by name onto the selected track")]
+    /// Load an instrument by name onto a track.
+    #[tool(
+        description = "Load an instrument by name onto a track (default: selected), optionally at a device position. Returns the new device index"
+    )]
     pub async fn load_instrument(
         &self,
-        Parameters(params): Parameters<LoadByNameParams>,
+        Parameters(params): Parameters<LoadDeviceParams>,
     ) -> Result<String, Error> {
-        let name = params.name;
-        self.osc
-            .send(
-                "/live/browser/load_instrument",
-                vec![OscType::String(name.clone())],
-            )
-            .await?;
-        Ok(format!("Loaded instrument: {name}"))
+        self.load_device_named("/live/browser/load_instrument", "instrument", &params)
+            .await
     }
 
     /// Load a drum kit onto the selected track.
@@ -90,36 +165,28 @@ impl AbletonServer {
     // Audio & MIDI Effects
     // =========================================================================
 
-    /// Load an audio effect by name onto the selected track.
-    #[tool(description = "Load an audio effect by name onto the selected track")]
+    /// Load an audio effect by name onto a track.
+    #[tool(
+        description = "Load an audio effect by name onto a track (default: selected), optionally at a device position. Returns the new device index"
+    )]
     pub async fn load_audio_effect(
         &self,
-        Parameters(params): Parameters<LoadByNameParams>,
+        Parameters(params): Parameters<LoadDeviceParams>,
     ) -> Result<String, Error> {
-        let name = params.name;
-        self.osc
-            .send(
-                "/live/browser/load_audio_effect",
-                vec![OscType::String(name.clone())],
-            )
-            .await?;
-        Ok(format!("Loaded audio effect: {name}"))
+        self.load_device_named("/live/browser/load_audio_effect", "audio effect", &params)
+            .await
     }
 
-    /// Load a MIDI effect by name onto the selected track.
-    #[tool(description = "Load a MIDI effect by name onto the selected track")]
+    /// Load a MIDI effect by name onto a track.
+    #[tool(
+        description = "Load a MIDI effect by name onto a track (default: selected), optionally at a device position. Returns the new device index"
+    )]
     pub async fn load_midi_effect(
         &self,
-        Parameters(params): Parameters<LoadByNameParams>,
+        Parameters(params): Parameters<LoadDeviceParams>,
     ) -> Result<String, Error> {
-        let name = params.name;
-        self.osc
-            .send(
-                "/live/browser/load_midi_effect",
-                vec![OscType::String(name.clone())],
-            )
-            .await?;
-        Ok(format!("Loaded MIDI effect: {name}"))
+        self.load_device_named("/live/browser/load_midi_effect", "MIDI effect", &params)
+            .await
     }
 
     /// Load the default audio effect (Reverb) onto the selected track.
@@ -176,20 +243,16 @@ impl AbletonServer {
     // Sounds & Presets
     // =========================================================================
 
-    /// Load a sound preset by name onto the selected track.
-    #[tool(description = "Load a sound preset by name onto the selected track")]
+    /// Load a sound preset by name onto a track.
+    #[tool(
+        description = "Load a sound preset by name onto a track (default: selected), optionally at a device position. Returns the new device index"
+    )]
     pub async fn load_sound(
         &self,
-        Parameters(params): Parameters<LoadByNameParams>,
+        Parameters(params): Parameters<LoadDeviceParams>,
     ) -> Result<String, Error> {
-        let name = params.name;
-        self.osc
-            .send(
-                "/live/browser/load_sound",
-                vec![OscType::String(name.clone())],
-            )
-            .await?;
-        Ok(format!("Loaded sound: {name}"))
+        self.load_device_named("/live/browser/load_sound", "sound", &params)
+            .await
     }
 
     /// List available sound presets.
@@ -208,20 +271,16 @@ impl AbletonServer {
     // Samples & Clips
     // =========================================================================
 
-    /// Load a sample by name onto the selected track (via Simpler).
-    #[tool(description = "Load a sample by name onto the selected track (via Simpler)")]
+    /// Load a sample by name onto a track (via Simpler).
+    #[tool(
+        description = "Load a sample by name onto a track (default: selected) via Simpler, optionally at a device position. Returns the new device index"
+    )]
     pub async fn load_sample(
         &self,
-        Parameters(params): Parameters<LoadByNameParams>,
+        Parameters(params): Parameters<LoadDeviceParams>,
     ) -> Result<String, Error> {
-        let name = params.name;
-        self.osc
-            .send(
-                "/live/browser/load_sample",
-                vec![OscType::String(name.clone())],
-            )
-            .await?;
-        Ok(format!("Loaded sample: {name}"))
+        self.load_device_named("/live/browser/load_sample", "sample", &params)
+            .await
     }
 
     /// Load a clip by name.
@@ -292,36 +351,32 @@ impl AbletonServer {
     // Plugins & Max4Live
     // =========================================================================
 
-    /// Load a VST/AU plugin by name onto the selected track.
-    #[tool(description = "Load a VST/AU plugin by name onto the selected track")]
+    /// Load a VST/AU plugin by name onto a track.
+    #[tool(
+        description = "Load a VST/AU plugin by name onto a track (default: selected), optionally at a device position. Returns the new device index"
+    )]
     pub async fn load_plugin(
         &self,
-        Parameters(params): Parameters<LoadByNameParams>,
+        Parameters(params): Parameters<LoadDeviceParams>,
     ) -> Result<String, Error> {
-        let name = params.name;
-        self.osc
-            .send(
-                "/live/browser/load_plugin",
-                vec![OscType::String(name.clone())],
-            )
-            .await?;
-        Ok(format!("Loaded plugin: {name}"))
+        self.load_device_named("/live/browser/load_plugin", "plugin", &params)
+            .await
     }
 
-    /// Load a Max for Live device by name onto the selected track.
-    #[tool(description = "Load a Max for Live device by name onto the selected track")]
+    /// Load a Max for Live device by name onto a track.
+    #[tool(
+        description = "Load a Max for Live device by name onto a track (default: selected), optionally at a device position. Returns the new device index"
+    )]
     pub async fn load_max_device(
         &self,
-        Parameters(params): Parameters<LoadByNameParams>,
+        Parameters(params): Parameters<LoadDeviceParams>,
     ) -> Result<String, Error> {
-        let name = params.name;
-        self.osc
-            .send(
-                "/live/browser/load_max_device",
-                vec![OscType::String(name.clone())],
-            )
-            .await?;
-        Ok(format!("Loaded Max for Live device: {name}"))
+        self.load_device_named(
+            "/live/browser/load_max_device",
+            "Max for Live device",
+            &params,
+        )
+        .await
     }
 
     /// List available VST/AU plugins.
@@ -471,6 +526,85 @@ impl AbletonServer {
         }
     }
 
+    // =========================================================================
+    // Browser Index
+    // =========================================================================
+
+    /// Search the local browser index.
+    #[tool(
+        description = "Fuzzy-search a local index of Live's browser (e.g. 'warm analog pad'). Returns ranked JSON results with path, category, loadable and is_device. The index is crawled in the background and cached on disk"
+    )]
+    pub async fn search_browser_index(
+        &self,
+        Parameters(params): Parameters<SearchBrowserIndexParams>,
+    ) -> Result<String, Error> {
+        let status = self.browser_index.ensure_fresh();
+        let filter = SearchFilter {
+            category: params.category,
+            loadable_only: params.loadable_only.unwrap_or(true),
+            devices_only: params.devices_only.unwrap_or(false),
+        };
+        let limit = params.limit.unwrap_or(20) as usize;
+        let result = IndexSearch {
+            complete: status.items > 0,
+            results: self.browser_index.search(&params.query, &filter, limit),
+        };
+        Ok(serde_json::to_string_pretty(&result).unwrap_or_else(|_| "{}".into()))
+    }
+
+    /// Load a browser item by its full path and confirm what appeared.
+    #[tool(
+        description = "Load a browser item by full path (e.g. 'sounds/Pad/Warm Analog Pad.adv') onto a track as a device, or into a clip slot. Verifies the device or clip that appeared; on failure, names the closest matches"
+    )]
+    pub async fn load_browser_item(
+        &self,
+        Parameters(params): Parameters<LoadBrowserItemParams>,
+    ) -> Result<String, Error> {
+        let (category, addr, args) = self.item_loader(&params.path)?;
+        if let Some(slot) = params.slot {
+            let track = self.resolve_load_track(params.track.as_ref()).await?;
+            return self
+                .load_into_clip_slot(addr, args, &params.path, track, slot)
+                .await;
+        }
+        if category == "clips" {
+            return Err(Error::InvalidParameter(
+                "Clips are loaded into a clip slot; give a slot".to_string(),
+            ));
+        }
+
+        let placement = Placement {
+            track: params.track.as_ref(),
+            position: params.position,
+        };
+        self.load_device_with(addr, args, &params.path, "item", &placement)
+            .await
+    }
+
+    /// Show the state of the browser index.
+    #[tool(
+        description = "Show the browser index state: item count, age, whether a crawl is running, and the cache location"
+    )]
+    pub async fn browser_index_status(&self) -> Result<String, Error> {
+        let status = self.browser_index.ensure_fresh();
+        Ok(serde_json::to_string_pretty(&status).unwrap_or_else(|_| "{}".into()))
+    }
+
+    /// Rebuild the browser index.
+    #[tool(
+        description = "Re-crawl Live's browser in the background to pick up new packs, presets and samples"
+    )]
+    pub async fn refresh_browser_index(&self) -> Result<String, Error> {
+        if self.browser_index.refresh() {
+            Ok(
+                "Started crawling the browser; searches use the current index until it finishes"
+                    .to_string(),
+            )
+        } else {
+            Ok("A browser crawl is already running".to_string())
+        }
+    }
+
     // =========================================================================
     // User Library
     // =========================================================================
@@ -500,19 +634,24 @@ impl AbletonServer {
     }
 
     /// Load a preset from the user library.
-    #[tool(description = "Load a preset from the user library")]
+    #[tool(
+        description = "Load a preset from the user library onto a track (default: selected) and confirm the device that appeared"
+    )]
     pub async fn load_user_preset(
         &self,
         Parameters(params): Parameters<LoadUserPresetParams>,
     ) -> Result<String, Error> {
-        let path = params.path;
-        self.osc
-            .send(
-                "/live/browser/load_user_preset",
-                vec![OscType::String(path.clone())],
-            )
-            .await?;
-        Ok(format!("Loaded user preset: {path}"))
+        let placement = Placement {
+            track: params.track.as_ref(),
+            position: params.position,
+        };
+        self.load_device(
+            "/live/browser/load_user_preset",
+            &params.path,
+            "user preset",
+            &placement,
+        )
+        .await
     }
 
     // =========================================================================
@@ -525,8 +664,8 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<DeviceParams>,
     ) -> Result<String, Error> {
-        let track = params.track;
-        let device = params.device;
+        let TrackId(track) = self.resolve_track(&params.track).await?;
+        let DeviceId(device) = self.resolve_device(TrackId(track), &params.device).await?;
         self.osc
             .send(
                 "/live/browser/hotswap_start",
@@ -571,9 +710,286 @@ impl AbletonServer {
     }
 
     /// Stop sample preview playback.
-    #[tool(description = "Stop sample preview playback")]
+    #[tool(description = "Stop sample preview playback, including a running audition")]
     pub async fn stop_preview(&self) -> Result<String, Error> {
+        let audition = self.auditioner.stop().await;
         self.osc.send("/live/browser/stop_preview", vec![]).await?;
-        Ok("Stopped preview".to_string())
+        Ok(if audition {
+            "Stopped preview and audition (marks are kept)".to_string()
+        } else {
+            "Stopped preview".to_string()
+        })
+    }
+
+    // =========================================================================
+    // Helper methods for internal use
+    // =========================================================================
+
+    /// Load a browser item named by `LoadDeviceParams`.
+    async fn load_device_named(
+        &self,
+        addr: &str,
+        label: &str,
+        params: &LoadDeviceParams,
+    ) -> Result<String, Error> {
+        let placement = Placement {
+            track: params.track.as_ref(),
+            position: params.position,
+        };
+        self.load_device(addr, &params.name, label, &placement)
+            .await
+    }
+
+    /// Load a browser item by name as a device on a track, at the requested
+    /// position.
+    async fn load_device(
+        &self,
+        addr: &str,
+        name: &str,
+        label: &str,
+        placement: &Placement<'_>,
+    ) -> Result<String, Error> {
+        self.load_device_with(
+            addr,
+            vec![OscType::String(name.to_string())],
+            name,
+            label,
+            placement,
+        )
+        .await
+    }
+
+    /// Send a browser load request for a device and confirm where it ended up.
+    async fn load_device_with(
+        &self,
+        addr: &str,
+        args: Vec<OscType>,
+        item: &str,
+        label: &str,
+        placement: &Placement<'_>,
+    ) -> Result<String, Error> {
+        let track = self.resolve_load_track(placement.track).await?;
+        let (index, device_name) = self
+            .load_onto_track(addr, args, item, track, placement.position)
+            .await?;
+        Ok(format!(
+            "Loaded {label}: {device_name} on track {track} at device index {index}"
+        ))
+    }
+
+    /// Load a browser item onto a track and return the new device's index
+    /// and name.
+    ///
+    /// The track is selected through the view API first, since Live loads
+    /// browser items onto the selected track, after its selected device (see
+    /// [`insertion`]). A device that lands anywhere other than a requested
+    /// `position` is deleted again. `item` names what was requested, for
+    /// error messages.
+    pub(crate) async fn load_onto_track(
+        &self,
+        addr: &str,
+        args: Vec<OscType>,
+        item: &str,
+        track: u32,
+        position: Option<u32>,
+    ) -> Result<(u32, String), Error> {
+        let before = self.track_device_names(track).await?;
+        let select = insertion(position, before.len(), track)?;
+
+        self.osc
+            .send(
+                "/live/view/set/selected_track",
+                vec![OscType::Int(track as i32)],
+            )
+            .await?;
+        if let Some(device) = select {
+            self.osc
+                .send(
+                    "/live/view/set/selected_device",
+                    vec![OscType::Int(track as i32), OscType::Int(device as i32)],
+                )
+                .await?;
+        }
+        self.osc.send(addr, args).await?;
+        let (index, name) = self
+            .wait_for_new_device(track, &before)
+            .await
+            .map_err(|e| self.browser_not_found(item, e))?;
+
+        match position {
+            Some(position) if position != index => {
+                self.osc
+                    .send(
+                        "/live/track/delete_device",
+                        vec![OscType::Int(track as i32), OscType::Int(index as i32)],
+                    )
+                    .await?;
+                Err(Error::InvalidResponse(format!(
+                    "{name} was loaded at device index {index} instead of {position} on track {track}, so it was removed again"
+                )))
+            }
+            _ => Ok((index, name)),
+        }
+    }
+
+    /// Loader for a browser path, refusing names the loader cannot tell apart.
+    ///
+    /// Every loader except `load_user_preset` takes only the item's name, so
+    /// when the index holds several items with that name in the category,
+    /// there is no telling which one would load.
+    pub(crate) fn item_loader(
+        &self,
+        path: &str,
+    ) -> Result<(String, &'static str, Vec<OscType>), Error> {
+        let (category, addr, args) = browser_loader(path)?;
+        if category != "user_library" {
+            self.browser_index.ensure_fresh();
+            let name = path.rsplit('/').next().unwrap_or(path);
+            let paths = self.browser_index.named(&category, name);
+            if paths.len() > 1 {
+                return Err(Error::InvalidParameter(format!(
+                    "Cannot tell which \"{name}\" to load: {category} items load by name, and {} have it ({})",
+                    paths.len(),
+                    paths.join(", ")
+                )));
+            }
+        }
+        Ok((category, addr, args))
+    }
+
+    /// Load a browser item into an empty clip slot and confirm the clip.
+    pub(crate) async fn load_into_clip_slot(
+        &self,
+        addr: &str,
+        args: Vec<OscType>,
+        item: &str,
+        track: u32,
+        slot: u32,
+    ) -> Result<String, Error> {
+        let slot_args = vec![OscType::Int(track as i32), OscType::Int(slot as i32)];
+        let has_clip = self
+            .query_flag("/live/clip_slot/get/has_clip", slot_args.clone())
+            .await?;
+        if has_clip {
+            return Err(Error::InvalidParameter(format!(
+                "Clip slot {slot} on track {track} already has a clip"
+            )));
+        }
+
+        self.osc
+            .send("/live/view/set/selected_clip", slot_args.clone())
+            .await?;
+        self.osc.send(addr, args).await?;
+
+        for _ in 0..POLL_ATTEMPTS {
+            tokio::time::sleep(POLL_INTERVAL).await;
+            let has_clip = self
+                .query_flag("/live/clip_slot/get/has_clip", slot_args.clone())
+                .await?;
+            if has_clip {
+                let name: String = self
+                    .osc
+                    .query("/live/clip/get/name", slot_args)
+                    .await
+                    .unwrap_or_default();
+                return Ok(format!(
+                    "Loaded clip \"{name}\" into track {track}, slot {slot}"
+                ));
+            }
+        }
+        Err(self.browser_not_found(
+            item,
+            Error::InvalidResponse(format!(
+                "No clip appeared in track {track}, slot {slot} after loading \"{item}\""
+            )),
+        ))
+    }
+
+    /// Track a load goes to: the given one, or the selected track.
+    pub(crate) async fn resolve_load_track(&self, track: Option<&TrackRef>) -> Result<u32, Error> {
+        match track {
+            Some(track) => Ok(self.resolve_track(track).await?.0),
+            None => {
+                let track: i32 = self
+                    .osc
+                    .query("/live/view/get/selected_track", vec![])
+                    .await?;
+                u32::try_from(track)
+                    .map_err(|_| Error::InvalidResponse("No track is selected".to_string()))
+            }
+        }
+    }
+
+    /// Turn a failed load into an error naming the closest indexed items.
+    ///
+    /// Falls back to `error` when the browser index has nothing similar.
+    fn browser_not_found(&self, item: &str, error: Error) -> Error {
+        // Search by the last path segment without its file extension
+        let name = item.rsplit('/').next().unwrap_or(item);
+        let name = name.rsplit_once('.').map_or(name, |(stem, _)| stem);
+        let filter = SearchFilter {
+            loadable_only: true,
+            ..SearchFilter::default()
+        };
+        let matches: Vec<String> = self
+            .browser_index
+            .search(name, &filter, 5)
+            .into_iter()
+            .map(|item| item.path)
+            .collect();
+        if matches.is_empty() {
+            error
+        } else {
+            Error::NotFound {
+                kind: "browser item",
+                query: item.to_string(),
+                available: matches,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::recording::reply_flag;
+
+    /// Paths pick the category's loader and pass the item name.
+    #[test]
+    fn picks_browser_loader() {
+        let (category, addr, args) = browser_loader("Sounds/Pad/Warm Pad.adv").unwrap();
+        assert_eq!(category, "sounds");
+        assert_eq!(addr, "/live/browser/load_sound");
+        assert_eq!(args, vec![OscType::String("Warm Pad.adv".to_string())]);
+
+        let (_, addr, args) = browser_loader("user_library/Presets/Bass.adv").unwrap();
+        assert_eq!(addr, "/live/browser/load_user_preset");
+        assert_eq!(args, vec![OscType::String("Presets/Bass.adv".to_string())]);
+
+        assert!(browser_loader("packs/Drum Booth/Kit.adg").is_err());
+        assert!(browser_loader("sounds").is_err());
+    }
+
+    /// The device in front of the insert position is selected; without a
+    /// position, the last device is.
+    #[test]
+    fn selects_device_in_front_of_insertion() {
+        assert_eq!(insertion(None, 3, 0).unwrap(), Some(2));
+        assert_eq!(insertion(None, 0, 0).unwrap(), None);
+        assert_eq!(insertion(Some(1), 3, 0).unwrap(), Some(0));
+        assert_eq!(insertion(Some(3), 3, 0).unwrap(), Some(2));
+        assert_eq!(insertion(Some(0), 0, 0).unwrap(), None);
+        assert!(insertion(Some(0), 2, 0).is_err());
+        assert!(insertion(Some(4), 3, 0).is_err());
+    }
+
+    /// `has_clip` replies echo the track and scene, so the flag is the last
+    /// argument.
+    #[test]
+    fn reads_has_clip_after_echoed_slot() {
+        let addr = "/live/clip_slot/get/has_clip";
+        let reply = |value| [OscType::Int(1), OscType::Int(4), value];
+        assert!(!reply_flag(addr, &reply(OscType::Int(0))).unwrap());
+        assert!(reply_flag(addr, &reply(OscType::Bool(true))).unwrap());
     }
 }
@@ -14,7 +14,7 @@ use crate::types::{
     SetClipLoopPointParams, SetClipLoopingParams, SetClipMarkerParams, SetClipMutedParams,
     SetClipNameParams, SetClipPitchFineParams, SetClipPitchParams, SetClipPositionParams,
     SetClipRamModeParams, SetClipSlotHasStopButtonParams, SetClipVelocityAmountParams,
-    SetClipWarpModeParams, SetClipWarpParams,
+    SetClipWarpModeParams, SetClipWarpParams, TrackId,
 };
 
 #[tool_router(router = clips_router, vis = "pub")]
@@ -25,7 +25,7 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<ClipSlotParams>,
     ) -> Result<String, Error> {
-        let track = params.track;
+        let TrackId(track) = self.resolve_track(&params.track).await?;
         let slot = params.slot;
         self.osc
             .send(
@@ -42,7 +42,7 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<ClipSlotParams>,
     ) -> Result<String, Error> {
-        let track = params.track;
+        let TrackId(track) = self.resolve_track(&params.track).await?;
         let slot = params.slot;
         self.osc
             .send(
@@ -59,7 +59,7 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<ClipSlotParams>,
     ) -> Result<String, Error> {
-        let track = params.track;
+        let TrackId(track) = self.resolve_track(&params.track).await?;
         let slot = params.slot;
         let args = vec![OscType::Int(track as i32), OscType::Int(slot as i32)];
 
@@ -124,7 +124,7 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<SetClipNameParams>,
     ) -> Result<String, Error> {
-        let track = params.track;
+        let TrackId(track) = self.resolve_track(&params.track).await?;
         let slot = params.slot;
         let name = params.name;
         self.osc
@@ -148,7 +148,7 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<ClipSlotParams>,
     ) -> Result<String, Error> {
-        let track = params.track;
+        let TrackId(track) = self.resolve_track(&params.track).await?;
         let slot = params.slot;
         self.osc
             .send(
@@ -173,7 +173,7 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<ClipSlotParams>,
     ) -> Result<String, Error> {
-        let track = params.track;
+        let TrackId(track) = self.resolve_track(&params.track).await?;
         let slot = params.slot;
         self.osc
             .send(
@@ -190,7 +190,7 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<CreateClipParams>,
     ) -> Result<String, Error> {
-        let track = params.track;
+        let TrackId(track) = self.resolve_track(&params.track).await?;
         let slot = params.slot;
         let length = params.length;
         self.osc
@@ -214,7 +214,7 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<SetClipLoopPointParams>,
     ) -> Result<String, Error> {
-        let track = params.track;
+        let TrackId(track) = self.resolve_track(&params.track).await?;
         let slot = params.slot;
         let start = params.position;
         self.osc
@@ -238,7 +238,7 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<SetClipLoopPointParams>,
     ) -> Result<String, Error> {
-        let track = params.track;
+        let TrackId(track) = self.resolve_track(&params.track).await?;
         let slot = params.slot;
         let end = params.position;
         self.osc
@@ -262,7 +262,7 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<ClipSlotParams>,
     ) -> Result<String, Error> {
-        let track = params.track;
+        let TrackId(track) = self.resolve_track(&params.track).await?;
         let slot = params.slot;
         let args = vec![OscType::Int(track as i32), OscType::Int(slot as i32)];
 
@@ -342,7 +342,7 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<AddClipNotesParams>,
     ) -> Result<String, Error> {
-        let track = params.track;
+        let TrackId(track) = self.resolve_track(&params.track).await?;
         let slot = params.slot;
         let notes = params.notes;
         // Build OSC args: track, slot, then for each note: pitch, start, duration, velocity, mute
@@ -369,7 +369,7 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<RemoveClipNotesParams>,
     ) -> Result<String, Error> {
-        let track = params.track;
+        let TrackId(track) = self.resolve_track(&params.track).await?;
         let slot = params.slot;
         let start_time = params.start_time;
         let end_time = params.end_time;
@@ -400,7 +400,7 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<ClipSlotParams>,
     ) -> Result<String, Error> {
-        let track = params.track;
+        let TrackId(track) = self.resolve_track(&params.track).await?;
         let slot = params.slot;
         let color: i32 = self
             .osc
@@ -418,7 +418,7 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<SetClipColorParams>,
     ) -> Result<String, Error> {
-        let track = params.track;
+        let TrackId(track) = self.resolve_track(&params.track).await?;
         let slot = params.slot;
         let color = params.color;
         self.osc
@@ -442,7 +442,7 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<ClipSlotParams>,
     ) -> Result<String, Error> {
-        let track = params.track;
+        let TrackId(track) = self.resolve_track(&params.track).await?;
         let slot = params.slot;
         let gain: f32 = self
             .osc
@@ -460,7 +460,7 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<SetClipGainParams>,
     ) -> Result<String, Error> {
-        let track = params.track;
+        let TrackId(track) = self.resolve_track(&params.track).await?;
         let slot = params.slot;
         let gain = params.gain;
         self.osc
@@ -484,7 +484,7 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<ClipSlotParams>,
     ) -> Result<String, Error> {
-        let track = params.track;
+        let TrackId(track) = self.resolve_track(&params.track).await?;
         let slot = params.slot;
         let pitch: i32 = self
             .osc
@@ -504,7 +504,7 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<SetClipPitchParams>,
     ) -> Result<String, Error> {
-        let track = params.track;
+        let TrackId(track) = self.resolve_track(&params.track).await?;
         let slot = params.slot;
         let semitones = params.semitones;
         if !(-48..=48).contains(&semitones) {
@@ -533,7 +533,7 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<ClipSlotParams>,
     ) -> Result<String, Error> {
-        let track = params.track;
+        let TrackId(track) = self.resolve_track(&params.track).await?;
         let slot = params.slot;
         let result: i32 = self
             .osc
@@ -555,7 +555,7 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<SetClipWarpParams>,
     ) -> Result<String, Error> {
-        let track = params.track;
+        let TrackId(track) = self.resolve_track(&params.track).await?;
         let slot = params.slot;
         let enabled = params.enabled;
         self.osc
@@ -582,7 +582,7 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<ClipSlotParams>,
     ) -> Result<String, Error> {
-        let track = params.track;
+        let TrackId(track) = self.resolve_track(&params.track).await?;
         let slot = params.slot;
         let mode: i32 = self
             .osc
@@ -613,7 +613,7 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<SetClipWarpModeParams>,
     ) -> Result<String, Error> {
-        let track = params.track;
+        let TrackId(track) = self.resolve_track(&params.track).await?;
         let slot = params.slot;
         let mode = params.mode;
         if !(0..=5).contains(&mode) {
@@ -652,7 +652,7 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<ClipSlotParams>,
     ) -> Result<String, Error> {
-        let track = params.track;
+        let TrackId(track) = self.resolve_track(&params.track).await?;
         let slot = params.slot;
         let args = vec![OscType::Int(track as i32), OscType::Int(slot as i32)];
 
@@ -678,7 +678,7 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<SetClipLoopBoundsParams>,
     ) -> Result<String, Error> {
-        let track = params.track;
+        let TrackId(track) = self.resolve_track(&params.track).await?;
         let slot = params.slot;
         let start = params.start;
         let end = params.end;
@@ -722,7 +722,7 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<ClipSlotParams>,
     ) -> Result<String, Error> {
-        let track = params.track;
+        let TrackId(track) = self.resolve_track(&params.track).await?;
         let slot = params.slot;
         let mode: i32 = self
             .osc
@@ -749,7 +749,7 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<SetClipLaunchModeParams>,
     ) -> Result<String, Error> {
-        let track = params.track;
+        let TrackId(track) = self.resolve_track(&params.track).await?;
         let slot = params.slot;
         let mode = params.mode;
         if !(0..=3).contains(&mode) {
@@ -785,7 +785,7 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<ClipSlotParams>,
     ) -> Result<String, Error> {
-        let track = params.track;
+        let TrackId(track) = self.resolve_track(&params.track).await?;
         let slot = params.slot;
         let quant: i32 = self
             .osc
@@ -805,7 +805,7 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<SetClipLaunchQuantizationParams>,
     ) -> Result<String, Error> {
-        let track = params.track;
+        let TrackId(track) = self.resolve_track(&params.track).await?;
         let slot = params.slot;
         let quantization = params.quantization;
         self.osc
@@ -829,7 +829,7 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<ClipSlotParams>,
     ) -> Result<String, Error> {
-        let track = params.track;
+        let TrackId(track) = self.resolve_track(&params.track).await?;
         let slot = params.slot;
         self.osc
             .send(
@@ -848,7 +848,7 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<ClipSlotParams>,
     ) -> Result<String, Error> {
-        let track = params.track;
+        let TrackId(track) = self.resolve_track(&params.track).await?;
         let slot = params.slot;
         let result: i32 = self
             .osc
@@ -870,9 +870,9 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<DuplicateClipToParams>,
     ) -> Result<String, Error> {
-        let src_track = params.src_track;
+        let TrackId(src_track) = self.resolve_track(&params.src_track).await?;
         let src_slot = params.src_slot;
-        let dst_track = params.dst_track;
+        let TrackId(dst_track) = self.resolve_track(&params.dst_track).await?;
         let dst_slot = params.dst_slot;
         self.osc
             .send(
@@ -896,7 +896,7 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<ClipSlotParams>,
     ) -> Result<String, Error> {
-        let track = params.track;
+        let TrackId(track) = self.resolve_track(&params.track).await?;
         let slot = params.slot;
         let result: i32 = self
             .osc
@@ -918,7 +918,7 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<SetClipSlotHasStopButtonParams>,
     ) -> Result<String, Error> {
-        let track = params.track;
+        let TrackId(track) = self.resolve_track(&params.track).await?;
         let slot = params.slot;
         let has_stop_button = params.has_stop_button;
         self.osc
@@ -943,7 +943,7 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<ClipSlotParams>,
     ) -> Result<String, Error> {
-        let track = params.track;
+        let TrackId(track) = self.resolve_track(&params.track).await?;
         let slot = params.slot;
         let args = vec![OscType::Int(track as i32), OscType::Int(slot as i32)];
 
@@ -1078,7 +1078,7 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<ClipSlotParams>,
     ) -> Result<String, Error> {
-        let track = params.track;
+        let TrackId(track) = self.resolve_track(&params.track).await?;
         let slot = params.slot;
         let result: i32 = self
             .osc
@@ -1100,7 +1100,7 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<ClipSlotParams>,
     ) -> Result<String, Error> {
-        let track = params.track;
+        let TrackId(track) = self.resolve_track(&params.track).await?;
         let slot = params.slot;
         let result: i32 = self
             .osc
@@ -1122,7 +1122,7 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<ClipSlotParams>,
     ) -> Result<String, Error> {
-        let track = params.track;
+        let TrackId(track) = self.resolve_track(&params.track).await?;
         let slot = params.slot;
         let path: String = self
             .osc
@@ -1142,7 +1142,7 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<ClipSlotParams>,
     ) -> Result<String, Error> {
-        let track = params.track;
+        let TrackId(track) = self.resolve_track(&params.track).await?;
         let slot = params.slot;
         let pos: f32 = self
             .osc
@@ -1162,7 +1162,7 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<ClipSlotParams>,
     ) -> Result<String, Error> {
-        let track = params.track;
+        let TrackId(track) = self.resolve_track(&params.track).await?;
         let slot = params.slot;
         let time: f32 = self
             .osc
@@ -1182,7 +1182,7 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<ClipSlotParams>,
     ) -> Result<String, Error> {
-        let track = params.track;
+        let TrackId(track) = self.resolve_track(&params.track).await?;
         let slot = params.slot;
         let time: f32 = self
             .osc
@@ -1202,7 +1202,7 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<ClipSlotParams>,
     ) -> Result<String, Error> {
-        let track = params.track;
+        let TrackId(track) = self.resolve_track(&params.track).await?;
         let slot = params.slot;
         let result: i32 = self
             .osc
@@ -1224,7 +1224,7 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<SetClipLoopingParams>,
     ) -> Result<String, Error> {
-        let track = params.track;
+        let TrackId(track) = self.resolve_track(&params.track).await?;
         let slot = params.slot;
         let looping = params.looping;
         self.osc
@@ -1249,7 +1249,7 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<ClipSlotParams>,
     ) -> Result<String, Error> {
-        let track = params.track;
+        let TrackId(track) = self.resolve_track(&params.track).await?;
         let slot = params.slot;
         let result: i32 = self
             .osc
@@ -1271,7 +1271,7 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<SetClipMutedParams>,
     ) -> Result<String, Error> {
-        let track = params.track;
+        let TrackId(track) = self.resolve_track(&params.track).await?;
         let slot = params.slot;
         let muted = params.muted;
         self.osc
@@ -1296,7 +1296,7 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<ClipSlotParams>,
     ) -> Result<String, Error> {
-        let track = params.track;
+        let TrackId(track) = self.resolve_track(&params.track).await?;
         let slot = params.slot;
         let pos: f32 = self
             .osc
@@ -1316,7 +1316,7 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<SetClipPositionParams>,
     ) -> Result<String, Error> {
-        let track = params.track;
+        let TrackId(track) = self.resolve_track(&params.track).await?;
         let slot = params.slot;
         let position = params.position;
         self.osc
@@ -1340,7 +1340,7 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<ClipSlotParams>,
     ) -> Result<String, Error> {
-        let track = params.track;
+        let TrackId(track) = self.resolve_track(&params.track).await?;
         let slot = params.slot;
         let marker: f32 = self
             .osc
@@ -1360,7 +1360,7 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<SetClipMarkerParams>,
     ) -> Result<String, Error> {
-        let track = params.track;
+        let TrackId(track) = self.resolve_track(&params.track).await?;
         let slot = params.slot;
         let marker = params.marker;
         self.osc
@@ -1384,7 +1384,7 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<ClipSlotParams>,
     ) -> Result<String, Error> {
-        let track = params.track;
+        let TrackId(track) = self.resolve_track(&params.track).await?;
         let slot = params.slot;
         let marker: f32 = self
             .osc
@@ -1404,7 +1404,7 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<SetClipMarkerParams>,
     ) -> Result<String, Error> {
-        let track = params.track;
+        let TrackId(track) = self.resolve_track(&params.track).await?;
         let slot = params.slot;
         let marker = params.marker;
         self.osc
@@ -1428,7 +1428,7 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<ClipSlotParams>,
     ) -> Result<String, Error> {
-        let track = params.track;
+        let TrackId(track) = self.resolve_track(&params.track).await?;
         let slot = params.slot;
         let result: i32 = self
             .osc
@@ -1450,7 +1450,7 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<SetClipLegatoParams>,
     ) -> Result<String, Error> {
-        let track = params.track;
+        let TrackId(track) = self.resolve_track(&params.track).await?;
         let slot = params.slot;
         let legato = params.legato;
         self.osc
@@ -1475,7 +1475,7 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<ClipSlotParams>,
     ) -> Result<String, Error> {
-        let track = params.track;
+        let TrackId(track) = self.resolve_track(&params.track).await?;
         let slot = params.slot;
         let amount: f32 = self
             .osc
@@ -1495,7 +1495,7 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<SetClipVelocityAmountParams>,
     ) -> Result<String, Error> {
-        let track = params.track;
+        let TrackId(track) = self.resolve_track(&params.track).await?;
         let slot = params.slot;
         let amount = params.amount;
         self.osc
@@ -1519,7 +1519,7 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<ClipSlotParams>,
     ) -> Result<String, Error> {
-        let track = params.track;
+        let TrackId(track) = self.resolve_track(&params.track).await?;
         let slot = params.slot;
         let index: i32 = self
             .osc
@@ -1539,7 +1539,7 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<SetClipColorIndexParams>,
     ) -> Result<String, Error> {
-        let track = params.track;
+        let TrackId(track) = self.resolve_track(&params.track).await?;
         let slot = params.slot;
         let color_index = params.color_index;
         self.osc
@@ -1563,7 +1563,7 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<ClipSlotParams>,
     ) -> Result<String, Error> {
-        let track = params.track;
+        let TrackId(track) = self.resolve_track(&params.track).await?;
         let slot = params.slot;
         let cents: f32 = self
             .osc
@@ -1583,7 +1583,7 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<SetClipPitchFineParams>,
     ) -> Result<String, Error> {
-        let track = params.track;
+        let TrackId(track) = self.resolve_track(&params.track).await?;
         let slot = params.slot;
         let cents = params.cents;
         self.osc
@@ -1607,7 +1607,7 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<ClipSlotParams>,
     ) -> Result<String, Error> {
-        let track = params.track;
+        let TrackId(track) = self.resolve_track(&params.track).await?;
         let slot = params.slot;
         let result: i32 = self
             .osc
@@ -1629,7 +1629,7 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<SetClipRamModeParams>,
     ) -> Result<String, Error> {
-        let track = params.track;
+        let TrackId(track) = self.resolve_track(&params.track).await?;
         let slot = params.slot;
         let enabled = params.enabled;
         self.osc
@@ -1654,7 +1654,7 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<ClipSlotParams>,
     ) -> Result<String, Error> {
-        let track = params.track;
+        let TrackId(track) = self.resolve_track(&params.track).await?;
         let slot = params.slot;
         let result: i32 = self
             .osc
@@ -1676,7 +1676,7 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<ClipSlotParams>,
     ) -> Result<String, Error> {
-        let track = params.track;
+        let TrackId(track) = self.resolve_track(&params.track).await?;
         let slot = params.slot;
         let result: i32 = self
             .osc
@@ -1698,7 +1698,7 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<ClipSlotParams>,
     ) -> Result<String, Error> {
-        let track = params.track;
+        let TrackId(track) = self.resolve_track(&params.track).await?;
         let slot = params.slot;
         let length: f32 = self
             .osc
@@ -1718,7 +1718,7 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<ClipSlotParams>,
     ) -> Result<String, Error> {
-        let track = params.track;
+        let TrackId(track) = self.resolve_track(&params.track).await?;
         let slot = params.slot;
         let result: i32 = self
             .osc
@@ -1740,7 +1740,7 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<ClipSlotParams>,
     ) -> Result<String, Error> {
-        let track = params.track;
+        let TrackId(track) = self.resolve_track(&params.track).await?;
         let slot = params.slot;
         let display: String = self
             .osc
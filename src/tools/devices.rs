@@ -1,46 +1,279 @@
 //! Device and parameter control tools.
 
+use std::time::Duration;
+
 use rmcp::handler::server::wrapper::Parameters;
 use rmcp::{tool, tool_router};
-use rosc::{OscPacket, OscType};
+use rosc::{OscMessage, OscPacket, OscType};
 
 use crate::error::Error;
+use crate::osc::FromOsc;
+use crate::presets::{DevicePreset, PresetParameter, map_parameters};
+use crate::resolve::match_name;
 use crate::server::AbletonServer;
 use crate::types::{
-    DeviceInfo, DeviceParams, GetParameterValueStringParams, ParameterInfo, ParameterStructure,
-    SetAllDeviceParametersParams, SetDeviceEnabledParams, SetDeviceParameterParams, TrackParams,
+    CopyDeviceToTrackParams, DeviceId, DeviceInfo, DeviceParams, GetParameterValueStringParams,
+    ListDevicesParams, MoveDeviceParams, ParameterInfo, ParameterStructure,
+    SetAllDeviceParametersParams, SetDeviceEnabledParams, SetDeviceParameterByNameParams,
+    SetDeviceParameterParams, TrackAddr, TrackDeviceParams, TrackId,
 };
+use crate::units::{Quantity, Unit, parse_quantity, same_display};
+
+/// Interval between device list checks while waiting for Live to load or move a device.
+pub(crate) const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Device list checks before giving up (3 seconds in total).
+pub(crate) const POLL_ATTEMPTS: usize = 30;
+
+/// Index of the first device that differs between two device lists.
+///
+/// Used to find where Live inserted a newly loaded device.
+fn changed_index(before: &[String], after: &[String]) -> Option<usize> {
+    after
+        .iter()
+        .enumerate()
+        .find(|(i, name)| before.get(*i) != Some(*name))
+        .map(|(i, _)| i)
+}
+
+/// Values of a bulk per-parameter reply such as `get/parameters/is_quantized`.
+///
+/// Each reply starts with the `echoed` request arguments (track and device
+/// indices), which are skipped; `value` picks the arguments of the wanted type.
+fn parameter_list<T>(
+    packets: Vec<OscPacket>,
+    echoed: usize,
+    value: impl Fn(OscType) -> Option<T>,
+) -> Vec<T> {
+    packets
+        .into_iter()
+        .filter_map(|packet| match packet {
+            OscPacket::Message(msg) => Some(msg.args),
+            OscPacket::Bundle(_) => None,
+        })
+        .flat_map(|args| args.into_iter().skip(echoed).filter_map(&value))
+        .collect()
+}
+
+/// A device copied by reloading it from the browser.
+struct DeviceCopy {
+    /// Name of the copied device.
+    name: String,
+    /// Index of the copy on the target track.
+    index: u32,
+    /// Parameters copied onto the new device.
+    applied: usize,
+    /// Parameters on the original device.
+    total: usize,
+}
+
+/// Browser names of built-in devices whose class name differs from the
+/// name shown in Live's browser, as (class name, browser name).
+const BROWSER_NAMES: [(&str, &str); 36] = [
+    ("AutoFilter", "Auto Filter"),
+    ("AutoPan", "Auto Pan"),
+    ("BeatRepeat", "Beat Repeat"),
+    ("ChannelEq", "Channel EQ"),
+    ("Chorus2", "Chorus-Ensemble"),
+    ("Compressor2", "Compressor"),
+    ("DrumBuss", "Drum Buss"),
+    ("DynamicTube", "Dynamic Tube"),
+    ("Eq8", "EQ Eight"),
+    ("FilterDelay", "Filter Delay"),
+    ("FilterEQ3", "EQ Three"),
+    ("GlueCompressor", "Glue Compressor"),
+    ("GrainDelay", "Grain Delay"),
+    ("Hybrid", "Hybrid Reverb"),
+    ("InstrumentImpulse", "Impulse"),
+    ("InstrumentMeld", "Meld"),
+    ("InstrumentVector", "Wavetable"),
+    ("LoungeLizard", "Electric"),
+    ("MidiArpeggiator", "Arpeggiator"),
+    ("MidiChord", "Chord"),
+    ("MidiNoteLength", "Note Length"),
+    ("MidiPitcher", "Pitch"),
+    ("MidiRandom", "Random"),
+    ("MidiScale", "Scale"),
+    ("MidiVelocity", "Velocity"),
+    ("MultiSampler", "Sampler"),
+    ("MultibandDynamics", "Multiband Dynamics"),
+    ("OriginalSimpler", "Simpler"),
+    ("PhaserNew", "Phaser-Flanger"),
+    ("Redux2", "Redux"),
+    ("SpectralResonator", "Spectral Resonator"),
+    ("SpectralTime", "Spectral Time"),
+    ("StereoGain", "Utility"),
+    ("StringStudio", "Tension"),
+    ("UltraAnalog", "Analog"),
+    ("Vinyl", "Vinyl Distortion"),
+];
+
+/// Name to load a device of `class_name` by from the browser.
+///
+/// Built-in devices are loaded by their class, so a device the user renamed
+/// still reloads. Plugins and Max devices share generic class names, so they
+/// can only be found by their display name.
+fn browser_name(class_name: &str, display_name: &str) -> String {
+    if let Some((_, name)) = BROWSER_NAMES.iter().find(|(class, _)| *class == class_name) {
+        (*name).to_string()
+    } else if class_name.starts_with("MxDevice") || class_name.contains("PluginDevice") {
+        display_name.to_string()
+    } else {
+        class_name.to_string()
+    }
+}
+
+/// Index of the device `(name, class_name)` in a track's device lists.
+///
+/// Prefers `expected`; otherwise the device must be the only match, not
+/// counting `exclude` (a copy with the same name and class).
+fn locate_device(
+    names: &[String],
+    classes: &[String],
+    (name, class_name): (&str, &str),
+    expected: usize,
+    exclude: Option<usize>,
+) -> Option<usize> {
+    let matches = |i: usize| {
+        Some(i) != exclude
+            && names.get(i).is_some_and(|n| n == name)
+            && classes.get(i).is_some_and(|c| c == class_name)
+    };
+    if matches(expected) {
+        return Some(expected);
+    }
+    let mut found = (0..names.len()).filter(|&i| matches(i));
+    match (found.next(), found.next()) {
+        (Some(index), None) => Some(index),
+        _ => None,
+    }
+}
+
+/// Browser load endpoint that recreates a device of the given class and type.
+///
+/// Racks have no browser item of their own, so they cannot be reloaded.
+fn browser_loader(class_name: &str, device_type: i32) -> Option<&'static str> {
+    if class_name.ends_with("GroupDevice") {
+        None
+    } else if class_name.starts_with("MxDevice") {
+        Some("/live/browser/load_max_device")
+    } else if class_name.contains("PluginDevice") {
+        Some("/live/browser/load_plugin")
+    } else {
+        match device_type {
+            0 => Some("/live/browser/load_audio_effect"),
+            1 => Some("/live/browser/load_instrument"),
+            2 => Some("/live/browser/load_midi_effect"),
+            _ => None,
+        }
+    }
+}
+
+/// Whether a `/live/track/get/devices/type` reply lists an instrument. The
+/// reply starts with the echoed track index.
+fn has_instrument(reply: &[OscType]) -> bool {
+    reply.iter().skip(1).any(|t| matches!(t, OscType::Int(1)))
+}
+
+/// `count` raw values evenly spread from `low` to `high`, both included.
+fn spread(low: f32, high: f32, count: usize) -> Vec<f32> {
+    let last = count.saturating_sub(1).max(1) as f32;
+    (0..count)
+        .map(|i| (high - low).mul_add(i as f32 / last, low))
+        .collect()
+}
+
+/// Raw value displayed as `target`, interpolated from `(raw, displayed)`
+/// samples in raw order.
+///
+/// Returns the estimate and the raw values of the samples around it. When no
+/// pair of samples brackets the target, the closest sample is used, so a
+/// target outside the parameter's range lands on the nearest end. Infinite
+/// displays (`-inf dB`) only match an equal target.
+fn interpolate_raw(table: &[(f32, f64)], target: f64) -> Option<(f32, (f32, f32))> {
+    for pair in table.windows(2) {
+        let ((raw_a, a), (raw_b, b)) = (pair[0], pair[1]);
+        if !a.is_finite() || !b.is_finite() || target < a.min(b) || target > a.max(b) {
+            continue;
+        }
+        let fraction = if (b - a).abs() < f64::EPSILON {
+            0.0
+        } else {
+            ((target - a) / (b - a)) as f32
+        };
+        return Some(((raw_b - raw_a).mul_add(fraction, raw_a), (raw_a, raw_b)));
+    }
+
+    let distance = |shown: f64| {
+        if shown.is_infinite() && shown.to_bits() == target.to_bits() {
+            0.0
+        } else {
+            (shown - target).abs()
+        }
+    };
+    let closest =
+        (0..table.len()).min_by(|&i, &j| distance(table[i].1).total_cmp(&distance(table[j].1)))?;
+    let before = table[closest.saturating_sub(1)].0;
+    let after = table[(closest + 1).min(table.len() - 1)].0;
+    Some((table[closest].0, (before, after)))
+}
+
+/// Raw value of the quantized option displayed as `target`.
+///
+/// Prefers an exact display match (e.g. `"Saw"`) and otherwise the
+/// numerically closest option in the same unit.
+fn pick_option(options: &[(f32, String)], target: &str) -> Result<f32, Error> {
+    if let Some((raw, _)) = options
+        .iter()
+        .find(|(_, display)| same_display(display, target))
+    {
+        return Ok(*raw);
+    }
+
+    let wanted = parse_quantity(target);
+    let mut closest: Option<(f32, f64)> = None;
+    for (raw, display) in options {
+        if let (Some(wanted), Some(shown)) = (wanted, parse_quantity(display)) {
+            let error = (shown.value - wanted.value).abs();
+            let same_unit = wanted.unit == Unit::None || wanted.unit == shown.unit;
+            if same_unit && closest.is_none_or(|(_, best)| error < best) {
+                closest = Some((*raw, error));
+            }
+        }
+    }
+
+    closest.map(|(raw, _)| raw).ok_or_else(|| Error::NotFound {
+        kind: "value",
+        query: target.to_string(),
+        available: options.iter().map(|(_, display)| display.clone()).collect(),
+    })
+}
 
 #[tool_router(router = devices_router, vis = "pub")]
 impl AbletonServer {
     /// List all devices on a track.
-    #[tool(description = "List all devices on a track")]
+    #[tool(description = "List all devices on a track or return track")]
     pub async fn list_devices(
         &self,
-        Parameters(params): Parameters<TrackParams>,
+        Parameters(params): Parameters<ListDevicesParams>,
     ) -> Result<String, Error> {
-        let track = params.track;
-        let count: i32 = self
-            .osc
-            .query(
-                "/live/track/get/num_devices",
-                vec![OscType::Int(track as i32)],
-            )
-            .await?;
+        let track = self.resolve_track_of(params.kind, &params.track).await?;
+        let (addr, args) = track.track_message("get/num_devices");
+        let count: i32 = self.osc.query(&addr, args).await?;
 
         let mut devices = Vec::new();
         for i in 0..count {
-            let args = vec![OscType::Int(track as i32), OscType::Int(i)];
-
+            let (addr, args) = track.device_message(i as u32, "get/name");
             let name: String = self
                 .osc
-                .query("/live/device/get/name", args.clone())
+                .query(&addr, args)
                 .await
                 .unwrap_or_else(|_| format!("Device {}", i + 1));
 
+            let (addr, args) = track.device_message(i as u32, "get/class_name");
             let class_name: String = self
                 .osc
-                .query("/live/device/get/class_name", args.clone())
+                .query(&addr, args)
                 .await
                 .unwrap_or_else(|_| "Unknown".to_string());
 
@@ -58,49 +291,36 @@ impl AbletonServer {
     #[tool(description = "Get all parameters for a device")]
     pub async fn get_device_parameters(
         &self,
-        Parameters(params): Parameters<DeviceParams>,
+        Parameters(params): Parameters<TrackDeviceParams>,
     ) -> Result<String, Error> {
-        let track = params.track;
-        let device = params.device;
-        let count: i32 = self
-            .osc
-            .query(
-                "/live/device/get/num_parameters",
-                vec![OscType::Int(track as i32), OscType::Int(device as i32)],
-            )
-            .await?;
+        let track = self.resolve_track_of(params.kind, &params.track).await?;
+        let DeviceId(device) = self.resolve_device_on(track, &params.device).await?;
+        let (addr, args) = track.device_message(device, "get/num_parameters");
+        let count: i32 = self.osc.query(&addr, args).await?;
 
         let mut parameters = Vec::new();
         for i in 0..count {
-            let args = vec![
-                OscType::Int(track as i32),
-                OscType::Int(device as i32),
-                OscType::Int(i),
-            ];
+            let parameter = |action: &str| {
+                let (addr, mut args) = track.device_message(device, action);
+                args.push(OscType::Int(i));
+                (addr, args)
+            };
 
+            let (addr, args) = parameter("get/parameter/name");
             let name: String = self
                 .osc
-                .query("/live/device/get/parameter/name", args.clone())
+                .query(&addr, args)
                 .await
                 .unwrap_or_else(|_| format!("Param {}", i + 1));
 
-            let value: f32 = self
-                .osc
-                .query("/live/device/get/parameter/value", args.clone())
-                .await
-                .unwrap_or(0.0);
+            let (addr, args) = parameter("get/parameter/value");
+            let value: f32 = self.osc.query(&addr, args).await.unwrap_or(0.0);
 
-            let min: f32 = self
-                .osc
-                .query("/live/device/get/parameter/min", args.clone())
-                .await
-                .unwrap_or(0.0);
+            let (addr, args) = parameter("get/parameter/min");
+            let min: f32 = self.osc.query(&addr, args).await.unwrap_or(0.0);
 
-            let max: f32 = self
-                .osc
-                .query("/live/device/get/parameter/max", args.clone())
-                .await
-                .unwrap_or(1.0);
+            let (addr, args) = parameter("get/parameter/max");
+            let max: f32 = self.osc.query(&addr, args).await.unwrap_or(1.0);
 
             parameters.push(ParameterInfo {
                 index: i as u32,
@@ -120,23 +340,15 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<SetDeviceParameterParams>,
     ) -> Result<String, Error> {
-        let track = params.track;
-        let device = params.device;
+        let track = self.resolve_track_of(params.kind, &params.track).await?;
+        let DeviceId(device) = self.resolve_device_on(track, &params.device).await?;
         let param = params.param;
         let value = params.value;
-        self.osc
-            .send(
-                "/live/device/set/parameter/value",
-                vec![
-                    OscType::Int(track as i32),
-                    OscType::Int(device as i32),
-                    OscType::Int(param as i32),
-                    OscType::Float(value),
-                ],
-            )
-            .await?;
+        let (addr, mut args) = track.device_message(device, "set/parameter/value");
+        args.extend([OscType::Int(param as i32), OscType::Float(value)]);
+        self.osc.send(&addr, args).await?;
         Ok(format!(
-            "Set parameter {param} on device {device} (track {track}) to {value}"
+            "Set parameter {param} on device {device} ({track}) to {value}"
         ))
     }
 
@@ -146,21 +358,14 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<SetDeviceEnabledParams>,
     ) -> Result<String, Error> {
-        let track = params.track;
-        let device = params.device;
+        let track = self.resolve_track_of(params.kind, &params.track).await?;
+        let DeviceId(device) = self.resolve_device_on(track, &params.device).await?;
         let enabled = params.enabled;
-        self.osc
-            .send(
-                "/live/device/set/is_enabled",
-                vec![
-                    OscType::Int(track as i32),
-                    OscType::Int(device as i32),
-                    OscType::Int(if enabled { 1 } else { 0 }),
-                ],
-            )
-            .await?;
+        let (addr, mut args) = track.device_message(device, "set/is_enabled");
+        args.push(OscType::Int(i32::from(enabled)));
+        self.osc.send(&addr, args).await?;
         Ok(format!(
-            "Device {device} on track {track} {}",
+            "Device {device} on {track} {}",
             if enabled { "enabled" } else { "disabled" }
         ))
     }
@@ -169,34 +374,135 @@ impl AbletonServer {
     #[tool(description = "Delete a device from a track")]
     pub async fn delete_device(
         &self,
-        Parameters(params): Parameters<DeviceParams>,
+        Parameters(params): Parameters<TrackDeviceParams>,
     ) -> Result<String, Error> {
-        let track = params.track;
-        let device = params.device;
+        let track = self.resolve_track_of(params.kind, &params.track).await?;
+        let DeviceId(device) = self.resolve_device_on(track, &params.device).await?;
+        let (addr, mut args) = track.track_message("delete_device");
+        args.push(OscType::Int(device as i32));
+        self.osc.send(&addr, args).await?;
+        Ok(format!("Deleted device {device} from {track}"))
+    }
+
+    /// Move a device to another position or track.
+    #[tool(
+        description = "Move a device to another position on its track or to another track by reloading it from the browser, copying its parameter values and deleting the original. Instruments can only go to a track without one"
+    )]
+    pub async fn move_device(
+        &self,
+        Parameters(params): Parameters<MoveDeviceParams>,
+    ) -> Result<String, Error> {
+        let TrackId(track) = self.resolve_track(&params.track).await?;
+        let DeviceId(device) = self.resolve_device(TrackId(track), &params.device).await?;
+        let target_track = match &params.target_track {
+            Some(target) => self.resolve_track(target).await?.0,
+            None => track,
+        };
+        let (name, class_name) = self.query_device_identity(track, device).await?;
+
+        // Within one track, the original is still in the list while the copy goes in
+        let same_track = target_track == track;
+        let position = params
+            .position
+            .map(|p| if same_track && p > device { p + 1 } else { p });
+        let copy = self
+            .reload_device_copy(track, device, target_track, position)
+            .await?;
+
+        // Find the original again by name and class rather than trusting its
+        // old index, in case the device list changed while the copy loaded
+        let expected = if same_track && copy.index <= device {
+            device + 1
+        } else {
+            device
+        };
+        let track_arg = vec![OscType::Int(track as i32)];
+        let names = self
+            .query_names("/live/track/get/devices/name", track_arg.clone())
+            .await?;
+        let classes = self
+            .query_names("/live/track/get/devices/class_name", track_arg)
+            .await?;
+        let original = locate_device(
+            &names,
+            &classes,
+            (&name, &class_name),
+            expected as usize,
+            same_track.then_some(copy.index as usize),
+        )
+        .ok_or_else(|| {
+            Error::InvalidResponse(format!(
+                "Copied {name} to track {target_track} at device index {}, but could not tell which device on track {track} is the original, so it was not deleted",
+                copy.index
+            ))
+        })? as u32;
         self.osc
             .send(
                 "/live/track/delete_device",
-                vec![OscType::Int(track as i32), OscType::Int(device as i32)],
+                vec![OscType::Int(track as i32), OscType::Int(original as i32)],
             )
             .await?;
-        Ok(format!("Deleted device {device} from track {track}"))
+        let index = if same_track && original < copy.index {
+            copy.index - 1
+        } else {
+            copy.index
+        };
+
+        Ok(format!(
+            "Moved {name} from track {track} (device {device}) to track {target_track} at device index {index} by reloading it from the browser ({} of {} parameters copied)",
+            copy.applied, copy.total
+        ))
+    }
+
+    /// Duplicate a device, placing the copy right after it.
+    #[tool(
+        description = "Duplicate a device by reloading it from the browser right after the original and copying its parameter values. Instruments cannot be duplicated this way; use duplicate_track"
+    )]
+    pub async fn duplicate_device(
+        &self,
+        Parameters(params): Parameters<DeviceParams>,
+    ) -> Result<String, Error> {
+        let TrackId(track) = self.resolve_track(&params.track).await?;
+        let DeviceId(device) = self.resolve_device(TrackId(track), &params.device).await?;
+        let copy = self
+            .reload_device_copy(track, device, track, Some(device + 1))
+            .await?;
+        Ok(format!(
+            "Duplicated {} (device {device}) to device index {} on track {track} ({} of {} parameters copied)",
+            copy.name, copy.index, copy.applied, copy.total
+        ))
+    }
+
+    /// Copy a device to another track.
+    #[tool(
+        description = "Copy a device to another track by reloading it from the browser and copying its parameter values. Instruments can only go to a track without one. Returns the new device index"
+    )]
+    pub async fn copy_device_to_track(
+        &self,
+        Parameters(params): Parameters<CopyDeviceToTrackParams>,
+    ) -> Result<String, Error> {
+        let TrackId(track) = self.resolve_track(&params.track).await?;
+        let DeviceId(device) = self.resolve_device(TrackId(track), &params.device).await?;
+        let TrackId(target_track) = self.resolve_track(&params.target_track).await?;
+        let copy = self
+            .reload_device_copy(track, device, target_track, params.position)
+            .await?;
+        Ok(format!(
+            "Copied {} from track {track} (device {device}) to track {target_track} at device index {} ({} of {} parameters copied)",
+            copy.name, copy.index, copy.applied, copy.total
+        ))
     }
 
     /// Get device type (0 = audio effect, 1 = instrument, 2 = midi effect).
     #[tool(description = "Get device type (0 = audio effect, 1 = instrument, 2 = midi effect)")]
     pub async fn get_device_type(
         &self,
-        Parameters(params): Parameters<DeviceParams>,
+        Parameters(params): Parameters<TrackDeviceParams>,
     ) -> Result<String, Error> {
-        let track = params.track;
-        let device = params.device;
-        let device_type: i32 = self
-            .osc
-            .query(
-                "/live/device/get/type",
-                vec![OscType::Int(track as i32), OscType::Int(device as i32)],
-            )
-            .await?;
+        let track = self.resolve_track_of(params.kind, &params.track).await?;
+        let DeviceId(device) = self.resolve_device_on(track, &params.device).await?;
+        let (addr, args) = track.device_message(device, "get/type");
+        let device_type: i32 = self.osc.query(&addr, args).await?;
         let type_name = match device_type {
             0 => "audio effect",
             1 => "instrument",
@@ -204,7 +510,7 @@ impl AbletonServer {
             _ => "unknown",
         };
         Ok(format!(
-            "Device {device} on track {track} is a {type_name} (type {device_type})"
+            "Device {device} on {track} is a {type_name} (type {device_type})"
         ))
     }
 
@@ -214,22 +520,14 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<GetParameterValueStringParams>,
     ) -> Result<String, Error> {
-        let track = params.track;
-        let device = params.device;
+        let track = self.resolve_track_of(params.kind, &params.track).await?;
+        let DeviceId(device) = self.resolve_device_on(track, &params.device).await?;
         let param = params.param;
-        let value_string: String = self
-            .osc
-            .query(
-                "/live/device/get/parameter/value_string",
-                vec![
-                    OscType::Int(track as i32),
-                    OscType::Int(device as i32),
-                    OscType::Int(param as i32),
-                ],
-            )
-            .await?;
+        let (addr, mut args) = track.device_message(device, "get/parameter/value_string");
+        args.push(OscType::Int(param as i32));
+        let value_string: String = self.osc.query(&addr, args).await?;
         Ok(format!(
-            "Parameter {param} on device {device} (track {track}): {value_string}"
+            "Parameter {param} on device {device} ({track}): {value_string}"
         ))
     }
 
@@ -237,20 +535,15 @@ impl AbletonServer {
     #[tool(description = "Check if device can have chains (e.g., racks)")]
     pub async fn can_device_have_chains(
         &self,
-        Parameters(params): Parameters<DeviceParams>,
+        Parameters(params): Parameters<TrackDeviceParams>,
     ) -> Result<String, Error> {
-        let track = params.track;
-        let device = params.device;
-        let result: i32 = self
-            .osc
-            .query(
-                "/live/device/get/can_have_chains",
-                vec![OscType::Int(track as i32), OscType::Int(device as i32)],
-            )
-            .await?;
+        let track = self.resolve_track_of(params.kind, &params.track).await?;
+        let DeviceId(device) = self.resolve_device_on(track, &params.device).await?;
+        let (addr, args) = track.device_message(device, "get/can_have_chains");
+        let result: i32 = self.osc.query(&addr, args).await?;
         let can_have_chains = result != 0;
         Ok(format!(
-            "Device {device} on track {track} {} have chains",
+            "Device {device} on {track} {} have chains",
             if can_have_chains { "can" } else { "cannot" }
         ))
     }
@@ -261,20 +554,15 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<SetAllDeviceParametersParams>,
     ) -> Result<String, Error> {
-        let track = params.track;
-        let device = params.device;
+        let track = self.resolve_track_of(params.kind, &params.track).await?;
+        let DeviceId(device) = self.resolve_device_on(track, &params.device).await?;
         let values = params.values;
 
-        let mut args = vec![OscType::Int(track as i32), OscType::Int(device as i32)];
-        for value in &values {
-            args.push(OscType::Float(*value));
-        }
-
-        self.osc
-            .send("/live/device/set/parameters/value", args)
-            .await?;
+        let (addr, mut args) = track.device_message(device, "set/parameters/value");
+        args.extend(values.iter().map(|value| OscType::Float(*value)));
+        self.osc.send(&addr, args).await?;
         Ok(format!(
-            "Set {} parameters on device {device} (track {track})",
+            "Set {} parameters on device {device} ({track})",
             values.len()
         ))
     }
@@ -283,119 +571,194 @@ impl AbletonServer {
     #[tool(description = "Get detailed information about all device parameters")]
     pub async fn get_device_parameters_detailed(
         &self,
-        Parameters(params): Parameters<DeviceParams>,
+        Parameters(params): Parameters<TrackDeviceParams>,
     ) -> Result<String, Error> {
-        let track = params.track;
-        let device = params.device;
+        let track = self.resolve_track_of(params.kind, &params.track).await?;
+        let DeviceId(device) = self.resolve_device_on(track, &params.device).await?;
 
-        // Get all parameter names
-        let names_packets = self
-            .osc
-            .query_all(
-                "/live/device/get/parameters/name",
-                vec![OscType::Int(track as i32), OscType::Int(device as i32)],
-            )
-            .await
-            .unwrap_or_default();
-
-        let mut names = Vec::new();
-        for packet in names_packets {
-            if let OscPacket::Message(msg) = packet {
-                for arg in msg.args {
-                    if let OscType::String(s) = arg {
-                        names.push(s);
-                    }
-                }
-            }
-        }
+        let parameters = self.query_device_parameters_on(track, device).await?;
+        Ok(serde_json::to_string_pretty(&parameters).unwrap_or_else(|_| "[]".into()))
+    }
 
-        // Get all parameter values
-        let values_packets = self
-            .osc
-            .query_all(
-                "/live/device/get/parameters/value",
-                vec![OscType::Int(track as i32), OscType::Int(device as i32)],
+    /// Set a device parameter by name, using a value in display units.
+    #[tool(
+        description = "Set a device parameter by name (fuzzy-matched) using a display value such as \"2.5 kHz\", \"-6 dB\", \"35%\", \"1/8\" or \"Saw\". Reads the value back and reports the final display string"
+    )]
+    pub async fn set_device_parameter_by_name(
+        &self,
+        Parameters(params): Parameters<SetDeviceParameterByNameParams>,
+    ) -> Result<String, Error> {
+        let TrackId(track) = self.resolve_track(&params.track).await?;
+        let DeviceId(device) = self.resolve_device(TrackId(track), &params.device).await?;
+
+        let (param, param_name) = self.resolve_parameter(track, device, &params.name).await?;
+        let quantized = self
+            .query_device_bools(
+                TrackAddr::regular(track),
+                device,
+                "get/parameters/is_quantized",
             )
-            .await
-            .unwrap_or_default();
-
-        let mut values = Vec::new();
-        for packet in values_packets {
-            if let OscPacket::Message(msg) = packet {
-                for arg in msg.args {
-                    if let OscType::Float(f) = arg {
-                        values.push(f);
-                    }
-                }
-            }
-        }
+            .await?
+            .get(param as usize)
+            .copied()
+            .unwrap_or(false);
 
-        // Get all parameter mins
-        let mins_packets = self
+        let args = vec![
+            OscType::Int(track as i32),
+            OscType::Int(device as i32),
+            OscType::Int(param as i32),
+        ];
+        let min: f32 = self
             .osc
-            .query_all(
-                "/live/device/get/parameters/min",
-                vec![OscType::Int(track as i32), OscType::Int(device as i32)],
-            )
-            .await
-            .unwrap_or_default();
-
-        let mut mins = Vec::new();
-        for packet in mins_packets {
-            if let OscPacket::Message(msg) = packet {
-                for arg in msg.args {
-                    if let OscType::Float(f) = arg {
-                        mins.push(f);
-                    }
-                }
-            }
-        }
+            .query("/live/device/get/parameter/min", args.clone())
+            .await?;
+        let max: f32 = self
+            .osc
+            .query("/live/device/get/parameter/max", args.clone())
+            .await?;
+        let raw = if quantized {
+            self.find_quantized_value(&args, min, max, &params.value)
+                .await?
+        } else if let Some(target) = parse_quantity(&params.value) {
+            self.find_continuous_value(&args, min, max, target).await?
+        } else {
+            return Err(Error::InvalidParameter(format!(
+                "Cannot interpret \"{}\" as a value for {param_name}",
+                params.value
+            )));
+        };
 
-        // Get all parameter maxs
-        let maxs_packets = self
+        let mut set_args = args.clone();
+        set_args.push(OscType::Float(raw));
+        self.osc
+            .send("/live/device/set/parameter/value", set_args)
+            .await?;
+        let value: f32 = self
             .osc
-            .query_all(
-                "/live/device/get/parameters/max",
-                vec![OscType::Int(track as i32), OscType::Int(device as i32)],
-            )
-            .await
-            .unwrap_or_default();
-
-        let mut maxs = Vec::new();
-        for packet in maxs_packets {
-            if let OscPacket::Message(msg) = packet {
-                for arg in msg.args {
-                    if let OscType::Float(f) = arg {
-                        maxs.push(f);
-                    }
-                }
-            }
+            .query("/live/device/get/parameter/value", args.clone())
+            .await?;
+        let display: String = self
+            .osc
+            .query("/live/device/get/parameter/value_string", args)
+            .await?;
+        Ok(format!(
+            "Set {param_name} (parameter {param}) on device {device} (track {track}) to {display} (raw value {value})"
+        ))
+    }
+
+    // ========== Helper methods for internal use ==========
+
+    /// Resolve a parameter name (fuzzy-matched) or index string to its index and name.
+    pub(crate) async fn resolve_parameter(
+        &self,
+        track: u32,
+        device: u32,
+        query: &str,
+    ) -> Result<(u32, String), Error> {
+        let names = self
+            .query_device_strings(TrackAddr::regular(track), device, "get/parameters/name")
+            .await?;
+        let param = match_name("parameter", query, &names)?;
+        let name = names.get(param as usize).cloned().ok_or_else(|| {
+            Error::InvalidParameter(format!(
+                "Parameter {param} does not exist on device {device} (track {track})"
+            ))
+        })?;
+        Ok((param, name))
+    }
+
+    /// Query a per-parameter string list for a device (e.g. all parameter names).
+    async fn query_device_strings(
+        &self,
+        track: TrackAddr,
+        device: u32,
+        action: &str,
+    ) -> Result<Vec<String>, Error> {
+        self.query_parameter_list(track, device, action, |arg| match arg {
+            OscType::String(s) => Some(s),
+            _ => None,
+        })
+        .await
+    }
+
+    /// Query a per-parameter float list for a device (e.g. all parameter values).
+    async fn query_device_floats(
+        &self,
+        track: TrackAddr,
+        device: u32,
+        action: &str,
+    ) -> Result<Vec<f32>, Error> {
+        self.query_parameter_list(track, device, action, |arg| match arg {
+            OscType::Float(f) => Some(f),
+            _ => None,
+        })
+        .await
+    }
+
+    /// Query a per-parameter flag list for a device (e.g. `is_quantized`).
+    async fn query_device_bools(
+        &self,
+        track: TrackAddr,
+        device: u32,
+        action: &str,
+    ) -> Result<Vec<bool>, Error> {
+        self.query_parameter_list(track, device, action, |arg| match arg {
+            OscType::Int(i) => Some(i != 0),
+            OscType::Bool(b) => Some(b),
+            _ => None,
+        })
+        .await
+    }
+
+    /// Query a bulk per-parameter list, failing if Live does not answer.
+    async fn query_parameter_list<T>(
+        &self,
+        track: TrackAddr,
+        device: u32,
+        action: &str,
+        value: impl Fn(OscType) -> Option<T>,
+    ) -> Result<Vec<T>, Error> {
+        let (addr, args) = track.device_message(device, action);
+        let echoed = args.len();
+        let packets = self.osc.query_all(&addr, args).await?;
+        if packets.is_empty() {
+            return Err(Error::Timeout);
         }
+        Ok(parameter_list(packets, echoed, value))
+    }
 
-        // Get all parameter quantized flags
-        let quantized_packets = self
-            .osc
-            .query_all(
-                "/live/device/get/parameters/is_quantized",
-                vec![OscType::Int(track as i32), OscType::Int(device as i32)],
-            )
+    /// Query names, values, ranges and quantization of every parameter on a device.
+    pub(crate) async fn query_device_parameters(
+        &self,
+        track: u32,
+        device: u32,
+    ) -> Result<Vec<ParameterStructure>, Error> {
+        self.query_device_parameters_on(TrackAddr::regular(track), device)
             .await
-            .unwrap_or_default();
-
-        let mut quantized = Vec::new();
-        for packet in quantized_packets {
-            if let OscPacket::Message(msg) = packet {
-                for arg in msg.args {
-                    match arg {
-                        OscType::Int(i) => quantized.push(i != 0),
-                        OscType::Bool(b) => quantized.push(b),
-                        _ => {}
-                    }
-                }
-            }
-        }
+    }
+
+    /// Query every parameter of a device on a track of any kind.
+    async fn query_device_parameters_on(
+        &self,
+        track: TrackAddr,
+        device: u32,
+    ) -> Result<Vec<ParameterStructure>, Error> {
+        let names = self
+            .query_device_strings(track, device, "get/parameters/name")
+            .await?;
+        let values = self
+            .query_device_floats(track, device, "get/parameters/value")
+            .await?;
+        let mins = self
+            .query_device_floats(track, device, "get/parameters/min")
+            .await?;
+        let maxs = self
+            .query_device_floats(track, device, "get/parameters/max")
+            .await?;
+        let quantized = self
+            .query_device_bools(track, device, "get/parameters/is_quantized")
+            .await?;
 
-        // Build parameter structures
         let len = names
             .len()
             .min(values.len())
@@ -413,7 +776,407 @@ impl AbletonServer {
                 is_quantized: quantized[i],
             });
         }
+        Ok(parameters)
+    }
 
-        Ok(serde_json::to_string_pretty(&parameters).unwrap_or_else(|_| "[]".into()))
+    /// Display strings of a parameter at each of `raws`, read in one pass.
+    ///
+    /// `args` is the `[track, device, parameter]` address of the parameter.
+    /// Each value is written and its display read back, and the parameter is
+    /// restored, all in one OSC bundle. `AbletonOSC` handles a bundle in a
+    /// single pass, so the parameter is back at its value before Live plays
+    /// another buffer instead of audibly stepping through every sample.
+    async fn display_table(
+        &self,
+        args: &[OscType],
+        raws: &[f32],
+    ) -> Result<Vec<(f32, String)>, Error> {
+        let original: f32 = self
+            .osc
+            .query("/live/device/get/parameter/value", args.to_vec())
+            .await?;
+        let message = |addr: &str, value: Option<f32>| OscMessage {
+            addr: addr.to_string(),
+            args: args
+                .iter()
+                .cloned()
+                .chain(value.map(OscType::Float))
+                .collect(),
+        };
+
+        let mut messages = Vec::with_capacity(raws.len() * 2 + 1);
+        for &raw in raws {
+            messages.push(message("/live/device/set/parameter/value", Some(raw)));
+            messages.push(message("/live/device/get/parameter/value_string", None));
+        }
+        messages.push(message("/live/device/set/parameter/value", Some(original)));
+
+        let replies = self
+            .osc
+            .query_bundle(messages, "/live/device/get/parameter/value_string")
+            .await?;
+        if replies.len() != raws.len() {
+            return Err(Error::Timeout);
+        }
+        raws.iter()
+            .zip(replies)
+            .map(|(&raw, reply)| Ok((raw, String::from_osc(reply)?)))
+            .collect()
+    }
+
+    /// Find the raw value of a continuous parameter whose display matches `target`.
+    ///
+    /// Reads the display across the raw range, interpolates between the two
+    /// samples around the target, then refines between them. Percentages on
+    /// parameters that are not displayed in percent map linearly onto the raw
+    /// range.
+    async fn find_continuous_value(
+        &self,
+        args: &[OscType],
+        min: f32,
+        max: f32,
+        target: Quantity,
+    ) -> Result<f32, Error> {
+        /// Samples across the whole raw range.
+        const COARSE_SAMPLES: usize = 65;
+        /// Samples between the two coarse samples around the target.
+        const FINE_SAMPLES: usize = 33;
+
+        let read = |display: String| {
+            parse_quantity(&display).ok_or_else(|| {
+                Error::InvalidParameter(format!(
+                    "Parameter is displayed as \"{display}\", which is not numeric"
+                ))
+            })
+        };
+
+        let shown: String = self
+            .osc
+            .query("/live/device/get/parameter/value_string", args.to_vec())
+            .await?;
+        let unit = read(shown)?.unit;
+        if target.unit == Unit::Percent && unit != Unit::Percent {
+            let fraction = (target.value / 100.0).clamp(0.0, 1.0) as f32;
+            return Ok((max - min).mul_add(fraction, min));
+        }
+        if target.unit != Unit::None && target.unit != unit {
+            return Err(Error::InvalidParameter(format!(
+                "Parameter is measured in {unit:?}, not {:?}",
+                target.unit
+            )));
+        }
+
+        let mut estimate = None;
+        let (mut lo, mut hi) = (min, max);
+        for samples in [COARSE_SAMPLES, FINE_SAMPLES] {
+            let table = self
+                .display_table(args, &spread(lo, hi, samples))
+                .await?
+                .into_iter()
+                .map(|(raw, display)| Ok((raw, read(display)?.value)))
+                .collect::<Result<Vec<_>, Error>>()?;
+            let Some((raw, around)) = interpolate_raw(&table, target.value) else {
+                break;
+            };
+            estimate = Some(raw);
+            (lo, hi) = around;
+        }
+        estimate.ok_or_else(|| Error::InvalidParameter("Parameter has no values".to_string()))
+    }
+
+    /// Find the raw value of a quantized parameter whose display matches `target`.
+    async fn find_quantized_value(
+        &self,
+        args: &[OscType],
+        min: f32,
+        max: f32,
+        target: &str,
+    ) -> Result<f32, Error> {
+        /// Upper bound on options to read.
+        const MAX_OPTIONS: usize = 256;
+
+        let steps = ((max - min).round().max(0.0) as usize).min(MAX_OPTIONS);
+        let raws: Vec<f32> = (0..=steps).map(|step| min + step as f32).collect();
+        let options = self.display_table(args, &raws).await?;
+        pick_option(&options, target)
+    }
+
+    /// Poll a device list until `done` accepts it.
+    ///
+    /// Returns `None` if Live did not reach the expected state in time.
+    pub(crate) async fn poll_device_names(
+        &self,
+        track: u32,
+        done: impl Fn(&[String]) -> bool,
+    ) -> Result<Option<Vec<String>>, Error> {
+        for _ in 0..POLL_ATTEMPTS {
+            let names = self.track_device_names(track).await?;
+            if done(&names) {
+                return Ok(Some(names));
+            }
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+        Ok(None)
+    }
+
+    /// Wait for a device to appear on a track and return its index and name.
+    pub(crate) async fn wait_for_new_device(
+        &self,
+        track: u32,
+        before: &[String],
+    ) -> Result<(u32, String), Error> {
+        let after = self
+            .poll_device_names(track, |names| names != before)
+            .await?
+            .ok_or_else(|| {
+                Error::InvalidResponse(format!(
+                    "No new device appeared on track {track} (is the item loadable?)"
+                ))
+            })?;
+        let index = changed_index(before, &after).ok_or_else(|| {
+            Error::InvalidResponse(format!(
+                "A device was removed from track {track} while loading"
+            ))
+        })?;
+        Ok((index as u32, after[index].clone()))
+    }
+
+    /// Load a fresh copy of a device from the browser and copy its parameters.
+    ///
+    /// The copy is placed like any browser load (see `load_onto_track`), so it
+    /// either lands at `position` or is removed again. Instruments are refused
+    /// when the target track already has one, since loading an instrument
+    /// replaces the track's instrument rather than adding a second.
+    async fn reload_device_copy(
+        &self,
+        track: u32,
+        device: u32,
+        target_track: u32,
+        position: Option<u32>,
+    ) -> Result<DeviceCopy, Error> {
+        let (name, class_name) = self.query_device_identity(track, device).await?;
+        let device_type: i32 = self
+            .osc
+            .query(
+                "/live/device/get/type",
+                vec![OscType::Int(track as i32), OscType::Int(device as i32)],
+            )
+            .await?;
+        let loader = browser_loader(&class_name, device_type).ok_or_else(|| {
+            Error::InvalidParameter(format!(
+                "{name} ({class_name}) cannot be reloaded from the browser"
+            ))
+        })?;
+        if device_type == 1 {
+            let types: Vec<OscType> = self
+                .osc
+                .query(
+                    "/live/track/get/devices/type",
+                    vec![OscType::Int(target_track as i32)],
+                )
+                .await?;
+            if has_instrument(&types) {
+                return Err(Error::InvalidParameter(format!(
+                    "{name} is an instrument and track {target_track} already has one, which loading it would replace; use duplicate_track to copy a track with its instrument"
+                )));
+            }
+        }
+        let source_parameters = self.query_device_parameters(track, device).await?;
+
+        let item = browser_name(&class_name, &name);
+        let (index, _) = self
+            .load_onto_track(
+                loader,
+                vec![OscType::String(item.clone())],
+                &item,
+                target_track,
+                position,
+            )
+            .await?;
+
+        let (_, loaded_class) = self.query_device_identity(target_track, index).await?;
+        if loaded_class != class_name {
+            self.osc
+                .send(
+                    "/live/track/delete_device",
+                    vec![
+                        OscType::Int(target_track as i32),
+                        OscType::Int(index as i32),
+                    ],
+                )
+                .await?;
+            return Err(Error::InvalidResponse(format!(
+                "Loading \"{item}\" from the browser gave a {loaded_class}, not a {class_name}"
+            )));
+        }
+
+        let snapshot = DevicePreset {
+            name: name.clone(),
+            class_name,
+            device_name: name.clone(),
+            parameters: source_parameters
+                .iter()
+                .map(|p| PresetParameter {
+                    name: p.name.clone(),
+                    value: p.value,
+                })
+                .collect(),
+        };
+        let current = self.query_device_parameters(target_track, index).await?;
+        let mapping = map_parameters(&snapshot, &current);
+        let mut args = vec![
+            OscType::Int(target_track as i32),
+            OscType::Int(index as i32),
+        ];
+        args.extend(mapping.values.iter().map(|v| OscType::Float(*v)));
+        self.osc
+            .send("/live/device/set/parameters/value", args)
+            .await?;
+
+        Ok(DeviceCopy {
+            name,
+            index,
+            applied: mapping.applied,
+            total: source_parameters.len(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(list: &[&str]) -> Vec<String> {
+        list.iter().map(ToString::to_string).collect()
+    }
+
+    /// The inserted device is found wherever Live put it.
+    #[test]
+    fn finds_inserted_device() {
+        let before = names(&["EQ Eight", "Compressor"]);
+        assert_eq!(
+            changed_index(&before, &names(&["EQ Eight", "Compressor", "Reverb"])),
+            Some(2)
+        );
+        assert_eq!(
+            changed_index(&before, &names(&["EQ Eight", "Reverb", "Compressor"])),
+            Some(1)
+        );
+        assert_eq!(changed_index(&before, &before), None);
+    }
+
+    /// Echoed track and device indices are not read as parameter flags, so
+    /// `get_device_parameters_detailed` lines flags up with names.
+    #[test]
+    fn parameter_list_skips_echoed_indices() {
+        let reply = vec![OscPacket::Message(rosc::OscMessage {
+            addr: "/live/device/get/parameters/is_quantized".to_string(),
+            args: vec![
+                OscType::Int(2),
+                OscType::Int(1),
+                OscType::Int(0),
+                OscType::Int(1),
+                OscType::Bool(false),
+            ],
+        })];
+        let flags = parameter_list(reply, 2, |arg| match arg {
+            OscType::Int(i) => Some(i != 0),
+            OscType::Bool(b) => Some(b),
+            _ => None,
+        });
+        assert_eq!(flags, [false, true, false]);
+    }
+
+    /// Devices reload through the browser category matching their class.
+    #[test]
+    fn picks_browser_loader() {
+        assert_eq!(
+            browser_loader("Reverb", 0),
+            Some("/live/browser/load_audio_effect")
+        );
+        assert_eq!(
+            browser_loader("InstrumentVector", 1),
+            Some("/live/browser/load_instrument")
+        );
+        assert_eq!(
+            browser_loader("AuPluginDevice", 1),
+            Some("/live/browser/load_plugin")
+        );
+        assert_eq!(
+            browser_loader("MxDeviceAudioEffect", 0),
+            Some("/live/browser/load_max_device")
+        );
+        assert_eq!(browser_loader("InstrumentGroupDevice", 1), None);
+    }
+
+    /// The echoed track index is not mistaken for an instrument.
+    #[test]
+    fn finds_instruments_after_echoed_track() {
+        let reply = |types: &[i32]| {
+            let mut reply = vec![OscType::Int(1)];
+            reply.extend(types.iter().map(|t| OscType::Int(*t)));
+            reply
+        };
+        assert!(!has_instrument(&reply(&[2, 0, 0])));
+        assert!(has_instrument(&reply(&[2, 1, 0])));
+        assert!(!has_instrument(&reply(&[])));
+    }
+
+    /// Targets between samples are interpolated; infinite displays only
+    /// match themselves; targets out of range land on the nearest end.
+    #[test]
+    fn interpolates_raw_value_from_display_table() {
+        let table = [
+            (0.0, f64::NEG_INFINITY),
+            (0.5, -12.0),
+            (0.75, -6.0),
+            (1.0, 0.0),
+        ];
+        let (raw, around) = interpolate_raw(&table, -9.0).unwrap();
+        assert!((raw - 0.625).abs() < 1e-6);
+        assert_eq!(around, (0.5, 0.75));
+        assert!(interpolate_raw(&table, f64::NEG_INFINITY).unwrap().0.abs() < 1e-6);
+        assert_eq!(interpolate_raw(&table, 6.0).unwrap(), (1.0, (0.75, 1.0)));
+        assert!(interpolate_raw(&[], 0.0).is_none());
+    }
+
+    /// Quantized options match by display first, then by closest number.
+    #[test]
+    fn picks_quantized_option() {
+        let options = vec![
+            (0.0, "Sine".to_string()),
+            (1.0, "Saw".to_string()),
+            (2.0, "1/8".to_string()),
+        ];
+        assert!((pick_option(&options, "saw").unwrap() - 1.0).abs() < 1e-6);
+        let numeric = vec![(0.0, "2".to_string()), (1.0, "4".to_string())];
+        assert!((pick_option(&numeric, "3.5").unwrap() - 1.0).abs() < 1e-6);
+        assert!(pick_option(&options, "Square").is_err());
+    }
+
+    /// Built-ins reload by class, so renaming them does not matter; plugins
+    /// and Max devices reload by display name.
+    #[test]
+    fn picks_browser_name() {
+        assert_eq!(browser_name("Eq8", "Vocal EQ"), "EQ Eight");
+        assert_eq!(browser_name("Reverb", "Big Room"), "Reverb");
+        assert_eq!(browser_name("AuPluginDevice", "Serum"), "Serum");
+        assert_eq!(browser_name("MxDeviceAudioEffect", "LFO"), "LFO");
+    }
+
+    /// The original is found at its expected index, or as the only other
+    /// device with its name and class.
+    #[test]
+    fn locates_original_device() {
+        let names = names(&["Reverb", "EQ Eight", "Reverb"]);
+        let classes = names.clone();
+        let reverb = ("Reverb", "Reverb");
+        assert_eq!(locate_device(&names, &classes, reverb, 2, None), Some(2));
+        assert_eq!(locate_device(&names, &classes, reverb, 1, Some(0)), Some(2));
+        assert_eq!(locate_device(&names, &classes, reverb, 1, None), None);
+        assert_eq!(
+            locate_device(&names, &classes, ("Delay", "Delay"), 0, None),
+            None
+        );
     }
 }
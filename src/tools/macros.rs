@@ -0,0 +1,333 @@
+//! Rack macro tools: listing, setting, randomizing and macro variations.
+//!
+//! Macros are the rack parameters following "Device On" (parameters 1-16).
+//! Variations are kept in a [`MacroVariationStore`](crate::macros::MacroVariationStore).
+
+use rmcp::handler::server::wrapper::Parameters;
+use rmcp::{tool, tool_router};
+use rosc::OscType;
+
+use crate::error::Error;
+use crate::macros::{MacroVariation, StoredMacro};
+use crate::resolve::match_name;
+use crate::rng::Rng;
+use crate::server::AbletonServer;
+use crate::types::{
+    DeviceId, DeviceParams, MacroChange, MacroInfo, MacroRandomization, MacroVariationParams,
+    RandomizeRackMacrosParams, SetRackMacroParams, TrackId,
+};
+
+/// Number of macros on a rack.
+const MACRO_COUNT: usize = 16;
+
+/// Find a macro by number (1-16) or name.
+fn pick_macro<'a>(macros: &'a [MacroInfo], query: &str) -> Result<&'a MacroInfo, Error> {
+    if let Ok(number) = query.trim().parse::<u32>() {
+        return macros.iter().find(|m| m.number == number).ok_or_else(|| {
+            Error::InvalidParameter(format!(
+                "Macro number must be between 1 and {}",
+                macros.len()
+            ))
+        });
+    }
+    let names: Vec<String> = macros.iter().map(|m| m.name.clone()).collect();
+    let index = match_name("macro", query, &names)?;
+    Ok(&macros[index as usize])
+}
+
+/// Whether a macro still has Live's default name ("Macro 3"), i.e. is probably unmapped.
+fn has_default_name(info: &MacroInfo) -> bool {
+    info.name == format!("Macro {}", info.number)
+}
+
+#[tool_router(router = macros_router, vis = "pub")]
+impl AbletonServer {
+    /// List a rack's macros.
+    #[tool(description = "List a rack's macros with number (1-16), name, value and range")]
+    pub async fn list_rack_macros(
+        &self,
+        Parameters(params): Parameters<DeviceParams>,
+    ) -> Result<String, Error> {
+        let TrackId(track) = self.resolve_track(&params.track).await?;
+        let DeviceId(device) = self.resolve_device(TrackId(track), &params.device).await?;
+        let macros = self.query_rack_macros(track, device).await?;
+        Ok(serde_json::to_string_pretty(&macros).unwrap_or_else(|_| "[]".into()))
+    }
+
+    /// Set a rack macro by number or name.
+    #[tool(description = "Set a rack macro by number (1-16) or name")]
+    pub async fn set_rack_macro(
+        &self,
+        Parameters(params): Parameters<SetRackMacroParams>,
+    ) -> Result<String, Error> {
+        let TrackId(track) = self.resolve_track(&params.track).await?;
+        let DeviceId(device) = self.resolve_device(TrackId(track), &params.device).await?;
+        let macros = self.query_rack_macros(track, device).await?;
+        let target = pick_macro(&macros, &params.macro_ref)?;
+
+        let value = params.value;
+        if !(target.min..=target.max).contains(&value) {
+            return Err(Error::InvalidParameter(format!(
+                "Value {value} is outside the range of {} ({} to {})",
+                target.name, target.min, target.max
+            )));
+        }
+
+        self.write_macro(track, device, target, value).await?;
+        Ok(format!(
+            "Set macro {} ({}) on device {device} (track {track}) to {value}",
+            target.number, target.name
+        ))
+    }
+
+    /// Randomize rack macros within bounds.
+    #[tool(
+        description = "Randomize rack macros within optional bounds. Defaults to all renamed (mapped) macros. Returns the seed and before/after values"
+    )]
+    pub async fn randomize_rack_macros(
+        &self,
+        Parameters(params): Parameters<RandomizeRackMacrosParams>,
+    ) -> Result<String, Error> {
+        let TrackId(track) = self.resolve_track(&params.track).await?;
+        let DeviceId(device) = self.resolve_device(TrackId(track), &params.device).await?;
+        let macros = self.query_rack_macros(track, device).await?;
+
+        let selected: Vec<&MacroInfo> = match &params.macros {
+            Some(queries) => queries
+                .iter()
+                .map(|query| pick_macro(&macros, query))
+                .collect::<Result<_, _>>()?,
+            None => {
+                let mapped: Vec<&MacroInfo> =
+                    macros.iter().filter(|m| !has_default_name(m)).collect();
+                if mapped.is_empty() {
+                    macros.iter().collect()
+                } else {
+                    mapped
+                }
+            }
+        };
+
+        // Check every macro's bounds before writing anything
+        let mut bounded = Vec::new();
+        for info in selected {
+            let low = params.min.map_or(info.min, |min| min.max(info.min));
+            let high = params.max.map_or(info.max, |max| max.min(info.max));
+            if low > high {
+                return Err(Error::InvalidParameter(format!(
+                    "Bounds {low} to {high} do not overlap the range of {}",
+                    info.name
+                )));
+            }
+            bounded.push((info, low, high));
+        }
+
+        let seed = params.seed.unwrap_or_else(Rng::seed_from_time);
+        let mut rng = Rng::new(seed);
+        let mut changes = Vec::new();
+        for (info, low, high) in bounded {
+            let value = rng.range(low, high);
+            self.write_macro(track, device, info, value).await?;
+            changes.push(MacroChange {
+                number: info.number,
+                name: info.name.clone(),
+                before: info.value,
+                after: value,
+            });
+        }
+
+        let result = MacroRandomization { seed, changes };
+        Ok(serde_json::to_string_pretty(&result).unwrap_or_else(|_| "{}".into()))
+    }
+
+    /// Store the current macro values as a named variation.
+    #[tool(
+        description = "Store the rack's current macro values as a named variation (kept until the server exits)"
+    )]
+    pub async fn store_macro_variation(
+        &self,
+        Parameters(params): Parameters<MacroVariationParams>,
+    ) -> Result<String, Error> {
+        let TrackId(track) = self.resolve_track(&params.track).await?;
+        let DeviceId(device) = self.resolve_device(TrackId(track), &params.device).await?;
+        let macros = self.query_rack_macros(track, device).await?;
+        let rack_name = self.query_rack_name(track, device).await?;
+
+        let variation = MacroVariation {
+            name: params.name.clone(),
+            macros: macros
+                .iter()
+                .map(|m| StoredMacro {
+                    number: m.number,
+                    name: m.name.clone(),
+                    value: m.value,
+                })
+                .collect(),
+        };
+        self.macro_variations
+            .store((track, device), &rack_name, variation);
+        Ok(format!(
+            "Stored macro variation \"{}\" for {rack_name} (device {device}, track {track})",
+            params.name
+        ))
+    }
+
+    /// Recall a stored macro variation.
+    #[tool(
+        description = "Recall a stored macro variation by name (fuzzy-matched). Returns before/after values"
+    )]
+    pub async fn recall_macro_variation(
+        &self,
+        Parameters(params): Parameters<MacroVariationParams>,
+    ) -> Result<String, Error> {
+        let TrackId(track) = self.resolve_track(&params.track).await?;
+        let DeviceId(device) = self.resolve_device(TrackId(track), &params.device).await?;
+        let rack_name = self.query_rack_name(track, device).await?;
+
+        let variations = self.macro_variations.list((track, device), &rack_name);
+        let names: Vec<String> = variations.iter().map(|v| v.name.clone()).collect();
+        let index = match_name("variation", &params.name, &names)?;
+        let variation = variations
+            .get(index as usize)
+            .ok_or_else(|| Error::InvalidParameter(format!("Variation {index} does not exist")))?;
+
+        let macros = self.query_rack_macros(track, device).await?;
+        let mut changes = Vec::new();
+        for stored in &variation.macros {
+            let Some(info) = macros.iter().find(|m| m.number == stored.number) else {
+                continue;
+            };
+            let value = stored.value.clamp(info.min, info.max);
+            self.write_macro(track, device, info, value).await?;
+            changes.push(MacroChange {
+                number: info.number,
+                name: info.name.clone(),
+                before: info.value,
+                after: value,
+            });
+        }
+
+        Ok(serde_json::to_string_pretty(&changes).unwrap_or_else(|_| "[]".into()))
+    }
+
+    /// List stored macro variations for a rack.
+    #[tool(description = "List stored macro variations for a rack")]
+    pub async fn list_macro_variations(
+        &self,
+        Parameters(params): Parameters<DeviceParams>,
+    ) -> Result<String, Error> {
+        let TrackId(track) = self.resolve_track(&params.track).await?;
+        let DeviceId(device) = self.resolve_device(TrackId(track), &params.device).await?;
+        let rack_name = self.query_rack_name(track, device).await?;
+        let variations = self.macro_variations.list((track, device), &rack_name);
+        Ok(serde_json::to_string_pretty(&variations).unwrap_or_else(|_| "[]".into()))
+    }
+
+    // ========== Helper methods for internal use ==========
+
+    /// Query a rack's macros, failing if the device is not a rack.
+    async fn query_rack_macros(&self, track: u32, device: u32) -> Result<Vec<MacroInfo>, Error> {
+        let can_have_chains: i32 = self
+            .osc
+            .query(
+                "/live/device/get/can_have_chains",
+                vec![OscType::Int(track as i32), OscType::Int(device as i32)],
+            )
+            .await?;
+        if can_have_chains == 0 {
+            return Err(Error::InvalidParameter(format!(
+                "Device {device} on track {track} is not a rack"
+            )));
+        }
+
+        // Parameter 0 is "Device On"; the macros follow it
+        let parameters = self.query_device_parameters(track, device).await?;
+        Ok(parameters
+            .into_iter()
+            .skip(1)
+            .take(MACRO_COUNT)
+            .enumerate()
+            .map(|(i, p)| MacroInfo {
+                number: i as u32 + 1,
+                parameter: i as u32 + 1,
+                name: p.name,
+                value: p.value,
+                min: p.min,
+                max: p.max,
+            })
+            .collect())
+    }
+
+    /// Query a device's name.
+    async fn query_rack_name(&self, track: u32, device: u32) -> Result<String, Error> {
+        self.osc
+            .query(
+                "/live/device/get/name",
+                vec![OscType::Int(track as i32), OscType::Int(device as i32)],
+            )
+            .await
+    }
+
+    /// Write a macro value.
+    async fn write_macro(
+        &self,
+        track: u32,
+        device: u32,
+        info: &MacroInfo,
+        value: f32,
+    ) -> Result<(), Error> {
+        self.osc
+            .send(
+                "/live/device/set/parameter/value",
+                vec![
+                    OscType::Int(track as i32),
+                    OscType::Int(device as i32),
+                    OscType::Int(info.parameter as i32),
+                    OscType::Float(value),
+                ],
+            )
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn macros() -> Vec<MacroInfo> {
+        ["Cutoff", "Macro 2", "Drive"]
+            .iter()
+            .enumerate()
+            .map(|(i, name)| MacroInfo {
+                number: i as u32 + 1,
+                parameter: i as u32 + 1,
+                name: (*name).to_string(),
+                value: 0.0,
+                min: 0.0,
+                max: 127.0,
+            })
+            .collect()
+    }
+
+    /// Numbers are 1-based macro numbers; other strings are matched against names.
+    #[test]
+    fn picks_macro_by_number_or_name() {
+        let macros = macros();
+        assert_eq!(pick_macro(&macros, "1").unwrap().name, "Cutoff");
+        assert_eq!(pick_macro(&macros, "drive").unwrap().number, 3);
+        assert!(pick_macro(&macros, "0").is_err());
+        assert!(pick_macro(&macros, "17").is_err());
+    }
+
+    /// Macros still named "Macro N" are treated as unmapped.
+    #[test]
+    fn detects_default_macro_names() {
+        let macros = macros();
+        let unmapped: Vec<u32> = macros
+            .iter()
+            .filter(|m| has_default_name(m))
+            .map(|m| m.number)
+            .collect();
+        assert_eq!(unmapped, vec![2]);
+    }
+}
@@ -6,7 +6,7 @@ use rosc::OscType;
 
 use crate::error::Error;
 use crate::server::AbletonServer;
-use crate::types::MapMidiCcParams;
+use crate::types::{DeviceId, MapMidiCcParams, TrackId};
 
 #[tool_router(router = midimap_router, vis = "pub")]
 impl AbletonServer {
@@ -18,8 +18,8 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<MapMidiCcParams>,
     ) -> Result<String, Error> {
-        let track = params.track;
-        let device = params.device;
+        let TrackId(track) = self.resolve_track(&params.track).await?;
+        let DeviceId(device) = self.resolve_device(TrackId(track), &params.device).await?;
         let parameter = params.parameter;
         let channel = params.channel;
         let cc = params.cc;
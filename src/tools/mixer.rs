@@ -0,0 +1,1024 @@
+//! Mixer tools: routing overview, batch changes, solo and mute helpers, level
+//! measurement and gain staging.
+//!
+//! Solo and mute helpers save the mute/solo state of every track on a stack
+//! before changing anything, so each one can be undone with
+//! `restore_mix_state`.
+//!
+//! Levels are measured by polling `/live/track/get/output_meter_level` for
+//! each track at a fixed rate while the song plays. Gain staging alternates
+//! measuring and moving faders, and keeps the original fader values so the
+//! last run can be reverted once.
+//!
+//! Return track names come from `/live/song/get/return_tracks`; return track
+//! routing and sends are read from the track endpoints with a `return_track`
+//! index (see [`TrackAddr`]). `AbletonOSC` cannot reach the master track, so
+//! the routing graph adds a `master` node that tracks routed to "Master" point
+//! at. Group membership comes from each track's group track (see
+//! [`crate::tools::tracks`]).
+
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use rmcp::handler::server::wrapper::Parameters;
+use rmcp::{tool, tool_router};
+use rosc::OscType;
+use tokio::time::{Instant, MissedTickBehavior};
+
+use crate::error::Error;
+use crate::fader::{UNITY, format_db, format_pan, volume_to_db};
+use crate::metering::{GainReport, GainStatus, LevelStats, TrackLevels, next_volume};
+use crate::mix_state::{MixSnapshot, TrackMix, locate, matches_pattern};
+use crate::resolve::match_name;
+use crate::routing::{RoutingGraph, TrackRouting};
+use crate::server::AbletonServer;
+use crate::types::{
+    GainStageParams, GainTarget, GetRoutingGraphParams, GraphFormat, MeasureLevelsParams,
+    MixerChange, MuteMatchingParams, SaveMixStateParams, SetMixerParams, SoloExclusiveParams,
+    TrackAddr, TrackId, TrackKind, TrackParams, TrackRef,
+};
+
+/// Meter samples per second used when no rate is given.
+const DEFAULT_METER_RATE: f32 = 20.0;
+
+/// Track volumes as `(track, volume)` pairs.
+type Volumes = Vec<(u32, f32)>;
+
+/// Fader values from before the last `gain_stage` run, shared by all server
+/// clones.
+#[derive(Clone, Default)]
+pub struct GainUndo {
+    volumes: Arc<Mutex<Option<Volumes>>>,
+}
+
+impl GainUndo {
+    /// Create an empty undo slot.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Remember fader values, replacing those of an earlier run.
+    fn save(&self, volumes: Volumes) {
+        *self.volumes.lock().unwrap() = Some(volumes);
+    }
+
+    /// Take the saved fader values; a second call returns `None`.
+    fn take(&self) -> Option<Volumes> {
+        self.volumes.lock().unwrap().take()
+    }
+}
+
+/// A value written by `set_mixer`.
+#[derive(Debug, Clone, PartialEq)]
+enum MixerValue {
+    Volume(f32),
+    Pan(f32),
+    Level(f32),
+    Switch(bool),
+    Color(i32),
+    Name(String),
+}
+
+impl MixerValue {
+    fn to_osc(&self) -> OscType {
+        match self {
+            Self::Volume(v) | Self::Pan(v) | Self::Level(v) => OscType::Float(*v),
+            Self::Switch(on) => OscType::Int(i32::from(*on)),
+            Self::Color(rgb) => OscType::Int(*rgb),
+            Self::Name(name) => OscType::String(name.clone()),
+        }
+    }
+
+    /// Parse a value read back from Live as the same kind of value as `self`.
+    fn read_like(&self, arg: &OscType) -> Option<Self> {
+        match (self, arg) {
+            (Self::Volume(_), OscType::Float(v)) => Some(Self::Volume(*v)),
+            (Self::Pan(_), OscType::Float(v)) => Some(Self::Pan(*v)),
+            (Self::Level(_), OscType::Float(v)) => Some(Self::Level(*v)),
+            (Self::Switch(_), OscType::Int(v)) => Some(Self::Switch(*v != 0)),
+            (Self::Switch(_), OscType::Bool(v)) => Some(Self::Switch(*v)),
+            (Self::Color(_), OscType::Int(v)) => Some(Self::Color(*v)),
+            (Self::Name(_), OscType::String(v)) => Some(Self::Name(v.clone())),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for MixerValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Volume(v) => write!(f, "{v:.2} ({})", format_db(volume_to_db(*v))),
+            Self::Pan(v) => f.write_str(&format_pan(*v)),
+            Self::Level(v) => write!(f, "{v:.2}"),
+            Self::Switch(on) => f.write_str(if *on { "on" } else { "off" }),
+            Self::Color(rgb) => write!(f, "#{rgb:06X}"),
+            Self::Name(name) => write!(f, "\"{name}\""),
+        }
+    }
+}
+
+/// One checked setting change for `set_mixer`.
+#[derive(Debug, Clone)]
+struct MixerWrite {
+    track: TrackAddr,
+    /// Track property, e.g. `volume`; read and written with `get/` and `set/`.
+    property: &'static str,
+    /// Return track index and name for sends.
+    send: Option<(u32, String)>,
+    value: MixerValue,
+}
+
+impl MixerWrite {
+    fn new(track: TrackAddr, property: &'static str, value: MixerValue) -> Self {
+        Self {
+            track,
+            property,
+            send: None,
+            value,
+        }
+    }
+
+    /// Address and arguments for reading (`get`) or writing (`set`) the setting.
+    fn message(&self, verb: &str) -> (String, Vec<OscType>) {
+        let (addr, mut args) = self
+            .track
+            .track_message(&format!("{verb}/{}", self.property));
+        if let Some((send, _)) = &self.send {
+            args.push(OscType::Int(*send as i32));
+        }
+        (addr, args)
+    }
+
+    /// Name of the setting in the change table.
+    fn label(&self) -> String {
+        match &self.send {
+            Some((_, name)) => format!("send {name}"),
+            None => self.property.to_string(),
+        }
+    }
+}
+
+/// Resolve a track reference against a list of names, checking indices.
+fn resolve_in(kind: &'static str, track: &TrackRef, names: &[String]) -> Result<u32, Error> {
+    match track {
+        TrackRef::Index(index) if (*index as usize) < names.len() => Ok(*index),
+        TrackRef::Index(index) => Err(Error::InvalidParameter(format!(
+            "There is no {kind} {index} (the set has {})",
+            names.len()
+        ))),
+        TrackRef::Name(name) => match_name(kind, name, names),
+    }
+}
+
+/// Check one track's changes and turn them into writes.
+fn mixer_writes(
+    change: &MixerChange,
+    tracks: &[String],
+    returns: &[String],
+) -> Result<Vec<MixerWrite>, Error> {
+    let track = match change.kind {
+        TrackKind::Regular => TrackAddr::regular(resolve_in("track", &change.track, tracks)?),
+        TrackKind::Return => {
+            TrackAddr::return_track(resolve_in("return track", &change.track, returns)?)
+        }
+    };
+    let invalid = |message: &str| Err(Error::InvalidParameter(format!("{track}: {message}")));
+
+    let mut writes = Vec::new();
+    let context = |e: Error| Error::InvalidParameter(format!("{track}: {e}"));
+    if let Some(volume) = &change.volume {
+        let volume = volume.to_fader().map_err(context)?;
+        writes.push(MixerWrite::new(track, "volume", MixerValue::Volume(volume)));
+    }
+    if let Some(pan) = &change.pan {
+        let pan = pan.to_value().map_err(context)?;
+        writes.push(MixerWrite::new(track, "panning", MixerValue::Pan(pan)));
+    }
+    for send in change.sends.iter().flatten() {
+        if !(0.0..=1.0).contains(&send.level) {
+            return invalid("send levels must be between 0.0 and 1.0");
+        }
+        let index = resolve_in("return track", &send.send, returns)?;
+        writes.push(MixerWrite {
+            send: Some((index, returns[index as usize].clone())),
+            ..MixerWrite::new(track, "send", MixerValue::Level(send.level))
+        });
+    }
+    if let Some(mute) = change.mute {
+        writes.push(MixerWrite::new(track, "mute", MixerValue::Switch(mute)));
+    }
+    if let Some(solo) = change.solo {
+        writes.push(MixerWrite::new(track, "solo", MixerValue::Switch(solo)));
+    }
+    if let Some(arm) = change.arm {
+        if track.kind != TrackKind::Regular {
+            return invalid("only regular tracks can be armed");
+        }
+        writes.push(MixerWrite::new(track, "arm", MixerValue::Switch(arm)));
+    }
+    if let Some(color) = change.color {
+        writes.push(MixerWrite::new(track, "color", MixerValue::Color(color)));
+    }
+    if let Some(name) = &change.name {
+        writes.push(MixerWrite::new(
+            track,
+            "name",
+            MixerValue::Name(name.clone()),
+        ));
+    }
+    if writes.is_empty() {
+        return invalid("nothing to change");
+    }
+    Ok(writes)
+}
+
+/// Whether `track` sits inside `group`, directly or in a nested group.
+pub(crate) fn is_inside(track: u32, group: u32, parents: &[Option<u32>]) -> bool {
+    let mut current = parents.get(track as usize).copied().flatten();
+    while let Some(parent) = current {
+        if parent == group {
+            return true;
+        }
+        current = parents.get(parent as usize).copied().flatten();
+    }
+    false
+}
+
+#[tool_router(router = mixer_router, vis = "pub")]
+impl AbletonServer {
+    /// Build a graph of how audio flows through the set.
+    #[tool(
+        description = "Get the routing graph of all tracks, groups and returns, with the master as a target: output and input routing edges with type and channel, and send edges with their level. Flags feedback loops and tracks routed to No Output. Format 'json' (default) or 'dot' for Graphviz"
+    )]
+    pub async fn get_routing_graph(
+        &self,
+        Parameters(params): Parameters<GetRoutingGraphParams>,
+    ) -> Result<String, Error> {
+        let tracks = self.query_all_routing().await?;
+        let graph = RoutingGraph::build(&tracks);
+        Ok(match params.format.unwrap_or_default() {
+            GraphFormat::Json => {
+                serde_json::to_string_pretty(&graph).unwrap_or_else(|_| "{}".into())
+            }
+            GraphFormat::Dot => graph.to_dot(),
+        })
+    }
+
+    /// Apply many mixer changes in one call.
+    #[tool(
+        description = "Apply mixer changes to many tracks in one call: volume, pan, sends (by return index or name), mute, solo, arm, color and name per track. Everything is validated before anything is sent. Returns a table of each setting with its value before and after"
+    )]
+    pub async fn set_mixer(
+        &self,
+        Parameters(params): Parameters<SetMixerParams>,
+    ) -> Result<String, Error> {
+        if params.changes.is_empty() {
+            return Err(Error::InvalidParameter(
+                "Give at least one change".to_string(),
+            ));
+        }
+        let tracks = self
+            .query_names("/live/song/get/track_names", vec![])
+            .await?;
+        let returns = self.return_track_names().await?;
+
+        // Resolve and check everything before sending anything
+        let mut writes = Vec::new();
+        for change in &params.changes {
+            writes.extend(mixer_writes(change, &tracks, &returns)?);
+        }
+
+        let mut rows = vec![
+            "| Track | Setting | Before | After |".to_string(),
+            "|-------|---------|--------|-------|".to_string(),
+        ];
+        for write in &writes {
+            let (addr, args) = write.message("get");
+            let echoed = args.len();
+            let before = self
+                .osc
+                .query::<Vec<OscType>>(&addr, args)
+                .await
+                .ok()
+                .and_then(|reply| reply.into_iter().nth(echoed))
+                .and_then(|arg| write.value.read_like(&arg));
+
+            let (addr, mut args) = write.message("set");
+            args.push(write.value.to_osc());
+            self.osc.send(&addr, args).await?;
+            rows.push(format!(
+                "| {} | {} | {} | {} |",
+                write.track,
+                write.label(),
+                before.map_or_else(|| "?".to_string(), |v| v.to_string()),
+                write.value
+            ));
+        }
+        Ok(rows.join("\n"))
+    }
+
+    /// Solo some tracks and unsolo all others.
+    #[tool(
+        description = "Solo a set of tracks exclusively: the given tracks are soloed and every other track unsoloed. Saves the previous mute/solo state first; undo with restore_mix_state"
+    )]
+    pub async fn solo_exclusive(
+        &self,
+        Parameters(params): Parameters<SoloExclusiveParams>,
+    ) -> Result<String, Error> {
+        if params.tracks.is_empty() {
+            return Err(Error::InvalidParameter(
+                "Give at least one track to solo".to_string(),
+            ));
+        }
+        let mut tracks = Vec::with_capacity(params.tracks.len());
+        for track in &params.tracks {
+            let TrackId(track) = self.resolve_track(track).await?;
+            if !tracks.contains(&track) {
+                tracks.push(track);
+            }
+        }
+        self.solo_only(&tracks, "solo_exclusive").await
+    }
+
+    /// Solo one group track on its own.
+    #[tool(
+        description = "Audition one group: solo the group track (and so everything inside it) and unsolo all other tracks. Saves the previous mute/solo state first; undo with restore_mix_state"
+    )]
+    pub async fn audition_group(
+        &self,
+        Parameters(params): Parameters<TrackParams>,
+    ) -> Result<String, Error> {
+        let TrackId(track) = self.resolve_track(&params.track).await?;
+        let foldable: i32 = self
+            .osc
+            .query(
+                "/live/track/get/is_foldable",
+                vec![OscType::Int(track as i32)],
+            )
+            .await?;
+        if foldable == 0 {
+            return Err(Error::InvalidParameter(format!(
+                "Track {track} is not a group track"
+            )));
+        }
+        self.solo_only(&[track], "audition_group").await
+    }
+
+    /// Mute tracks by name pattern or color.
+    #[tool(
+        description = "Mute all tracks whose name matches a pattern ('*' and '?' wildcards) and/or that have a given color; with except=true, mute everything that does not match. Saves the previous mute/solo state first; undo with restore_mix_state"
+    )]
+    pub async fn mute_matching(
+        &self,
+        Parameters(params): Parameters<MuteMatchingParams>,
+    ) -> Result<String, Error> {
+        if params.pattern.is_none() && params.color.is_none() {
+            return Err(Error::InvalidParameter(
+                "Give a name pattern, a color, or both".to_string(),
+            ));
+        }
+        let except = params.except.unwrap_or(false);
+        let state = self.read_mix_state().await?;
+
+        let mut targets = Vec::new();
+        for track in &state {
+            let mut matched = params
+                .pattern
+                .as_deref()
+                .is_none_or(|pattern| matches_pattern(pattern, &track.name));
+            if let Some(color) = params.color {
+                if matched {
+                    let current: i32 = self
+                        .osc
+                        .query(
+                            "/live/track/get/color",
+                            vec![OscType::Int(track.index as i32)],
+                        )
+                        .await?;
+                    matched = current == color;
+                }
+            }
+            if matched != except && !track.mute {
+                targets.push(track);
+            }
+        }
+        if targets.is_empty() {
+            return Ok("No unmuted tracks match; nothing changed".to_string());
+        }
+
+        let label = match (&params.pattern, params.color) {
+            (Some(pattern), Some(color)) => format!("\"{pattern}\" and color {color}"),
+            (Some(pattern), None) => format!("\"{pattern}\""),
+            (None, Some(color)) => format!("color {color}"),
+            (None, None) => String::new(),
+        };
+        let label = format!(
+            "mute_matching {}{label}",
+            if except { "all except " } else { "" }
+        );
+        let names: Vec<String> = targets.iter().map(|t| t.name.clone()).collect();
+        for track in &targets {
+            self.set_track_switch(track.index, "mute", true).await?;
+        }
+        let depth = self.mix_states.push(MixSnapshot {
+            label,
+            tracks: state.clone(),
+        });
+        Ok(format!(
+            "Muted {} track(s): {} (saved state {depth}; undo with restore_mix_state)",
+            names.len(),
+            names.join(", ")
+        ))
+    }
+
+    /// Save the mute/solo state of every track.
+    #[tool(
+        description = "Save the mute and solo state of every track on the state stack, to bring back later with restore_mix_state"
+    )]
+    pub async fn save_mix_state(
+        &self,
+        Parameters(params): Parameters<SaveMixStateParams>,
+    ) -> Result<String, Error> {
+        let tracks = self.read_mix_state().await?;
+        let label = params.label.unwrap_or_else(|| "saved".to_string());
+        let depth = self.mix_states.push(MixSnapshot {
+            label: label.clone(),
+            tracks,
+        });
+        Ok(format!("Saved mix state \"{label}\" ({depth} saved)"))
+    }
+
+    /// Restore the most recently saved mute/solo state.
+    #[tool(
+        description = "Restore the most recently saved mute/solo state (from save_mix_state, solo_exclusive, audition_group or mute_matching) and remove it from the stack"
+    )]
+    pub async fn restore_mix_state(&self) -> Result<String, Error> {
+        let snapshot = self
+            .mix_states
+            .pop()
+            .ok_or_else(|| Error::InvalidParameter("No mix state is saved".to_string()))?;
+        let names = self
+            .query_names("/live/song/get/track_names", vec![])
+            .await?;
+        let (found, missing) = locate(&snapshot.tracks, &names);
+        for (index, track) in &found {
+            self.set_track_switch(*index, "mute", track.mute).await?;
+            self.set_track_switch(*index, "solo", track.solo).await?;
+        }
+
+        let restored = format!(
+            "Restored \"{}\" on {} track(s)",
+            snapshot.label,
+            found.len()
+        );
+        if missing.is_empty() {
+            return Ok(restored);
+        }
+        let missing: Vec<&str> = missing.iter().map(|t| t.name.as_str()).collect();
+        Ok(format!("{restored}; not found: {}", missing.join(", ")))
+    }
+
+    /// List saved mute/solo states.
+    #[tool(
+        description = "List saved mute/solo states, most recent (restored next) first, with the tracks each one had muted and soloed"
+    )]
+    pub async fn list_mix_states(&self) -> Result<String, Error> {
+        Ok(serde_json::to_string_pretty(&self.mix_states.list()).unwrap_or_else(|_| "[]".into()))
+    }
+
+    /// Sample output meters over a window and summarise them.
+    #[tool(
+        description = "Measure track levels while the song plays: samples output meters at a fixed rate over N beats and returns per-track peak, average (RMS), approximate crest factor in dB, and the share of samples near clipping"
+    )]
+    pub async fn measure_levels(
+        &self,
+        Parameters(params): Parameters<MeasureLevelsParams>,
+    ) -> Result<String, Error> {
+        let beats = params.beats.unwrap_or(8.0);
+        let rate = params.rate.unwrap_or(DEFAULT_METER_RATE);
+        let clip_threshold = params.clip_threshold.unwrap_or(0.98);
+        if beats <= 0.0 {
+            return Err(Error::InvalidParameter(
+                "beats must be greater than 0".to_string(),
+            ));
+        }
+        if !(1.0..=50.0).contains(&rate) {
+            return Err(Error::InvalidParameter(
+                "rate must be between 1 and 50 samples per second".to_string(),
+            ));
+        }
+
+        let names = self
+            .query_names("/live/song/get/track_names", vec![])
+            .await?;
+        let tracks = match &params.tracks {
+            Some(refs) => {
+                let mut tracks = Vec::with_capacity(refs.len());
+                for track in refs {
+                    let TrackId(track) = self.resolve_track(track).await?;
+                    if !tracks.contains(&track) {
+                        tracks.push(track);
+                    }
+                }
+                tracks
+            }
+            None => (0..names.len() as u32).collect(),
+        };
+
+        let samples = self.sample_meters(&tracks, beats, rate).await?;
+        let levels: Vec<TrackLevels> = tracks
+            .iter()
+            .zip(samples)
+            .map(|(&track, samples)| TrackLevels {
+                track,
+                name: names.get(track as usize).cloned().unwrap_or_default(),
+                stats: LevelStats::from_samples(&samples, clip_threshold),
+            })
+            .collect();
+        Ok(serde_json::to_string_pretty(&levels).unwrap_or_else(|_| "[]".into()))
+    }
+
+    /// Move faders until tracks average close to their target levels.
+    #[tool(
+        description = "Gain stage tracks while the song plays: measures each track's average level, adjusts its volume towards a target in dB (per track, or per group for every track inside it), and repeats until within the tolerance. Never raises a fader above the ceiling. Returns before/after levels and volumes; undo with revert_gain_stage"
+    )]
+    pub async fn gain_stage(
+        &self,
+        Parameters(params): Parameters<GainStageParams>,
+    ) -> Result<String, Error> {
+        let tolerance = params.tolerance_db.unwrap_or(1.0);
+        let ceiling = match &params.ceiling {
+            Some(ceiling) => ceiling.to_fader()?,
+            None => UNITY,
+        };
+        let beats = params.beats.unwrap_or(4.0);
+        let max_passes = params.max_passes.unwrap_or(4);
+        if params.targets.is_empty() {
+            return Err(Error::InvalidParameter(
+                "Give at least one target".to_string(),
+            ));
+        }
+        if tolerance <= 0.0 || beats <= 0.0 {
+            return Err(Error::InvalidParameter(
+                "tolerance_db and beats must be greater than 0".to_string(),
+            ));
+        }
+
+        let targets = self.expand_gain_targets(&params.targets).await?;
+        let names = self
+            .query_names("/live/song/get/track_names", vec![])
+            .await?;
+        let mut reports = Vec::with_capacity(targets.len());
+        for (track, target_db) in targets {
+            let volume: f32 = self
+                .osc
+                .query("/live/track/get/volume", vec![OscType::Int(track as i32)])
+                .await?;
+            reports.push(GainReport {
+                track,
+                name: names.get(track as usize).cloned().unwrap_or_default(),
+                target_db,
+                before_db: None,
+                after_db: None,
+                volume_before: volume,
+                volume_after: volume,
+                status: GainStatus::NotConverged,
+            });
+        }
+        self.gain_undo
+            .save(reports.iter().map(|r| (r.track, r.volume_before)).collect());
+
+        // Measure, then adjust the tracks that are still off; the last pass
+        // only measures so the report reflects the final fader values
+        let mut active: Vec<usize> = (0..reports.len()).collect();
+        for pass in 0..=max_passes {
+            let tracks: Vec<u32> = active.iter().map(|&i| reports[i].track).collect();
+            let samples = self
+                .sample_meters(&tracks, beats, DEFAULT_METER_RATE)
+                .await?;
+            let mut adjusted = Vec::new();
+            for (&i, samples) in active.iter().zip(samples) {
+                let report = &mut reports[i];
+                let level = LevelStats::from_samples(&samples, 1.0).average_db;
+                if pass == 0 {
+                    report.before_db = level;
+                }
+                report.after_db = level;
+                let Some(level) = level else {
+                    report.status = GainStatus::Silent;
+                    continue;
+                };
+                if (level - report.target_db).abs() <= tolerance {
+                    report.status = GainStatus::Converged;
+                    continue;
+                }
+                report.status = GainStatus::NotConverged;
+                if pass == max_passes {
+                    continue;
+                }
+                let volume = next_volume(report.volume_after, level, report.target_db, ceiling);
+                if (volume - report.volume_after).abs() < 1e-4 {
+                    if level < report.target_db {
+                        report.status = GainStatus::AtCeiling;
+                    }
+                    continue;
+                }
+                self.osc
+                    .send(
+                        "/live/track/set/volume",
+                        vec![OscType::Int(report.track as i32), OscType::Float(volume)],
+                    )
+                    .await?;
+                report.volume_after = volume;
+                adjusted.push(i);
+            }
+            if adjusted.is_empty() {
+                break;
+            }
+            active = adjusted;
+        }
+
+        Ok(serde_json::to_string_pretty(&reports).unwrap_or_else(|_| "[]".into()))
+    }
+
+    /// Put faders back where they were before the last `gain_stage`.
+    #[tool(
+        description = "Revert the last gain_stage run, restoring every track volume it changed. Works once per run"
+    )]
+    pub async fn revert_gain_stage(&self) -> Result<String, Error> {
+        let volumes = self.gain_undo.take().ok_or_else(|| {
+            Error::InvalidParameter(
+                "Nothing to revert; run gain_stage first (each run can be reverted once)"
+                    .to_string(),
+            )
+        })?;
+        for &(track, volume) in &volumes {
+            self.osc
+                .send(
+                    "/live/track/set/volume",
+                    vec![OscType::Int(track as i32), OscType::Float(volume)],
+                )
+                .await?;
+        }
+        Ok(format!("Restored the volume of {} track(s)", volumes.len()))
+    }
+
+    // ========== Helper methods for internal use ==========
+
+    /// Check and apply one track's mixer changes without reading them back.
+    ///
+    /// Returns each setting as written, e.g. `volume 0.70 (-6.0 dB)`.
+    pub(crate) async fn apply_mixer_change(
+        &self,
+        change: &MixerChange,
+    ) -> Result<Vec<String>, Error> {
+        let tracks = self
+            .query_names("/live/song/get/track_names", vec![])
+            .await?;
+        let returns = self.return_track_names().await?;
+        let mut applied = Vec::new();
+        for write in mixer_writes(change, &tracks, &returns)? {
+            let (addr, mut args) = write.message("set");
+            args.push(write.value.to_osc());
+            self.osc.send(&addr, args).await?;
+            applied.push(format!("{} {}", write.label(), write.value));
+        }
+        Ok(applied)
+    }
+
+    /// Resolve targets to `(track, target_db)`, expanding group tracks to the
+    /// tracks inside them. A track's own target wins over its group's.
+    async fn expand_gain_targets(&self, targets: &[GainTarget]) -> Result<Vec<(u32, f32)>, Error> {
+        let mut groups = Vec::new();
+        let mut tracks = Vec::new();
+        for target in targets {
+            let TrackId(track) = self.resolve_track(&target.track).await?;
+            let foldable: i32 = self
+                .osc
+                .query(
+                    "/live/track/get/is_foldable",
+                    vec![OscType::Int(track as i32)],
+                )
+                .await?;
+            if foldable == 0 {
+                tracks.push((track, target.target_db));
+            } else {
+                groups.push((track, target.target_db));
+            }
+        }
+
+        let mut expanded = BTreeMap::new();
+        if !groups.is_empty() {
+            let (is_group, parents) = self.track_groups().await?;
+            for (group, target_db) in groups {
+                for track in 0..parents.len() as u32 {
+                    if !is_group[track as usize] && is_inside(track, group, &parents) {
+                        expanded.insert(track, target_db);
+                    }
+                }
+            }
+        }
+        expanded.extend(tracks);
+        if expanded.is_empty() {
+            return Err(Error::InvalidParameter(
+                "The target groups contain no tracks".to_string(),
+            ));
+        }
+        Ok(expanded.into_iter().collect())
+    }
+
+    /// Mute and solo state of every regular track.
+    async fn read_mix_state(&self) -> Result<Vec<TrackMix>, Error> {
+        let names = self
+            .query_names("/live/song/get/track_names", vec![])
+            .await?;
+        let mut tracks = Vec::with_capacity(names.len());
+        for (index, name) in names.into_iter().enumerate() {
+            let index = index as u32;
+            tracks.push(TrackMix {
+                index,
+                name,
+                mute: self.track_switch(index, "mute").await?,
+                solo: self.track_switch(index, "solo").await?,
+            });
+        }
+        Ok(tracks)
+    }
+
+    /// Read an on/off track property such as `mute`.
+    pub(crate) async fn track_switch(&self, track: u32, property: &str) -> Result<bool, Error> {
+        // The reply echoes the track index before the value
+        let reply: Vec<OscType> = self
+            .osc
+            .query(
+                &format!("/live/track/get/{property}"),
+                vec![OscType::Int(track as i32)],
+            )
+            .await?;
+        match reply.last() {
+            Some(OscType::Int(v)) => Ok(*v != 0),
+            Some(OscType::Bool(v)) => Ok(*v),
+            other => Err(Error::InvalidResponse(format!(
+                "Expected track {track} {property} state, got {other:?}"
+            ))),
+        }
+    }
+
+    /// Set an on/off track property such as `mute`.
+    pub(crate) async fn set_track_switch(
+        &self,
+        track: u32,
+        property: &str,
+        on: bool,
+    ) -> Result<(), Error> {
+        self.osc
+            .send(
+                &format!("/live/track/set/{property}"),
+                vec![OscType::Int(track as i32), OscType::Int(i32::from(on))],
+            )
+            .await
+    }
+
+    /// Save the mix state, then solo `tracks` and unsolo everything else.
+    async fn solo_only(&self, tracks: &[u32], tool: &str) -> Result<String, Error> {
+        let state = self.read_mix_state().await?;
+        for track in &state {
+            let solo = tracks.contains(&track.index);
+            if track.solo != solo {
+                self.set_track_switch(track.index, "solo", solo).await?;
+            }
+        }
+        let names: Vec<String> = tracks
+            .iter()
+            .map(|&t| {
+                state
+                    .get(t as usize)
+                    .map_or_else(|| format!("track {t}"), |track| track.name.clone())
+            })
+            .collect();
+        let depth = self.mix_states.push(MixSnapshot {
+            label: format!("{tool} {}", names.join(", ")),
+            tracks: state,
+        });
+        Ok(format!(
+            "Soloed {} (saved state {depth}; undo with restore_mix_state)",
+            names.join(", ")
+        ))
+    }
+
+    /// Poll the output meters of `tracks` at `rate` Hz for `beats` beats.
+    ///
+    /// Returns one list of samples per track. Fails if the song is not
+    /// playing, since stopped tracks read as silence.
+    pub(crate) async fn sample_meters(
+        &self,
+        tracks: &[u32],
+        beats: f32,
+        rate: f32,
+    ) -> Result<Vec<Vec<f32>>, Error> {
+        let playing: bool = self.osc.query("/live/song/get/is_playing", vec![]).await?;
+        if !playing {
+            return Err(Error::InvalidParameter(
+                "The song is not playing; start playback before measuring levels".to_string(),
+            ));
+        }
+        let tempo: f32 = self.osc.query("/live/song/get/tempo", vec![]).await?;
+        let end = Instant::now() + Duration::from_secs_f32(beats * 60.0 / tempo);
+
+        let mut samples = vec![Vec::new(); tracks.len()];
+        let mut ticks = tokio::time::interval(Duration::from_secs_f32(1.0 / rate));
+        ticks.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        while ticks.tick().await < end {
+            for (i, &track) in tracks.iter().enumerate() {
+                // A dropped reply loses one sample rather than the window
+                if let Ok(level) = self
+                    .osc
+                    .query::<f32>(
+                        "/live/track/get/output_meter_level",
+                        vec![OscType::Int(track as i32)],
+                    )
+                    .await
+                {
+                    samples[i].push(level);
+                }
+            }
+        }
+        Ok(samples)
+    }
+
+    /// All regular tracks, then the return tracks.
+    pub(crate) async fn all_track_addrs(&self) -> Result<Vec<(TrackAddr, String)>, Error> {
+        let names = self
+            .query_names("/live/song/get/track_names", vec![])
+            .await?;
+        let returns = self.return_track_names().await?;
+        let mut addrs: Vec<(TrackAddr, String)> = names
+            .into_iter()
+            .enumerate()
+            .map(|(i, name)| (TrackAddr::regular(i as u32), name))
+            .collect();
+        addrs.extend(
+            returns
+                .into_iter()
+                .enumerate()
+                .map(|(i, name)| (TrackAddr::return_track(i as u32), name)),
+        );
+        Ok(addrs)
+    }
+
+    /// Routing, group membership and sends of every track.
+    async fn query_all_routing(&self) -> Result<Vec<TrackRouting>, Error> {
+        let addrs = self.all_track_addrs().await?;
+        let returns = addrs
+            .iter()
+            .filter(|(addr, _)| addr.kind == TrackKind::Return)
+            .count() as u32;
+        let (groups, parents) = self.track_groups().await?;
+
+        let mut tracks = Vec::with_capacity(addrs.len());
+        for (track, name) in addrs {
+            let string = |action: &str| {
+                let (addr, args) = track.track_message(action);
+                async move { self.osc.query::<String>(&addr, args).await }
+            };
+            let output_type = string("get/output_routing_type").await?;
+            let output_channel = string("get/output_routing_channel")
+                .await
+                .unwrap_or_default();
+
+            let (mut is_group, mut group, mut input_type, mut input_channel) =
+                (false, None, None, None);
+            if track.kind == TrackKind::Regular {
+                let index = track.index as usize;
+                is_group = groups.get(index).copied().unwrap_or(false);
+                group = parents.get(index).copied().flatten();
+                input_type = string("get/input_routing_type").await.ok();
+                input_channel = string("get/input_routing_channel").await.ok();
+            }
+
+            let mut sends = Vec::new();
+            for send in 0..returns {
+                let (addr, mut args) = track.track_message("get/send");
+                args.push(OscType::Int(send as i32));
+                sends.push(self.osc.query::<f32>(&addr, args).await.unwrap_or(0.0));
+            }
+
+            tracks.push(TrackRouting {
+                track,
+                name,
+                is_group,
+                group,
+                output_type,
+                output_channel,
+                input_type,
+                input_channel,
+                sends,
+            });
+        }
+        Ok(tracks)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{Pan, SendChange, Volume};
+
+    /// Membership follows nested groups up to the outermost one.
+    #[test]
+    fn is_inside_follows_nested_groups() {
+        // 0: Drums (group), 1: Kick, 2: Tops (group in Drums), 3: Hats, 4: Bass
+        let parents = [None, Some(0), Some(0), Some(2), None];
+        assert!(is_inside(1, 0, &parents));
+        assert!(is_inside(3, 0, &parents));
+        assert!(is_inside(3, 2, &parents));
+        assert!(!is_inside(1, 2, &parents));
+        assert!(!is_inside(4, 0, &parents));
+    }
+
+    fn names(list: &[&str]) -> Vec<String> {
+        list.iter().map(ToString::to_string).collect()
+    }
+
+    fn change(track: TrackRef) -> MixerChange {
+        MixerChange {
+            track,
+            kind: TrackKind::Regular,
+            volume: None,
+            pan: None,
+            sends: None,
+            mute: None,
+            solo: None,
+            arm: None,
+            color: None,
+            name: None,
+        }
+    }
+
+    /// Changes expand to one write per setting, with sends resolved by name.
+    #[test]
+    fn mixer_writes_resolve_tracks_and_sends() {
+        let tracks = names(&["Kick", "Bass"]);
+        let returns = names(&["A-Reverb", "B-Delay"]);
+        let writes = mixer_writes(
+            &MixerChange {
+                volume: Some(Volume::Db("-6 dB".to_string())),
+                sends: Some(vec![SendChange {
+                    send: TrackRef::Name("B-Delay".to_string()),
+                    level: 0.3,
+                }]),
+                mute: Some(true),
+                ..change(TrackRef::Name("bass".to_string()))
+            },
+            &tracks,
+            &returns,
+        )
+        .unwrap();
+        let labels: Vec<String> = writes.iter().map(MixerWrite::label).collect();
+        assert_eq!(labels, ["volume", "send B-Delay", "mute"]);
+        assert_eq!(writes[0].track, TrackAddr::regular(1));
+        assert!(matches!(writes[0].value, MixerValue::Volume(v) if (v - 0.7).abs() < 1e-4));
+        assert_eq!(writes[1].message("set").1.len(), 2);
+    }
+
+    /// Bad indices and values are rejected before anything is sent.
+    #[test]
+    fn mixer_writes_validate_up_front() {
+        let tracks = names(&["Kick"]);
+        let returns = names(&["A-Reverb"]);
+        let check = |change: MixerChange| mixer_writes(&change, &tracks, &returns).is_err();
+        assert!(check(change(TrackRef::Index(0))));
+        assert!(check(MixerChange {
+            volume: Some(Volume::Fader(0.5)),
+            ..change(TrackRef::Index(3))
+        }));
+        assert!(check(MixerChange {
+            pan: Some(Pan::Value(1.5)),
+            ..change(TrackRef::Index(0))
+        }));
+        assert!(check(MixerChange {
+            sends: Some(vec![SendChange {
+                send: TrackRef::Index(1),
+                level: 0.5,
+            }]),
+            ..change(TrackRef::Index(0))
+        }));
+        assert!(check(MixerChange {
+            kind: TrackKind::Return,
+            arm: Some(true),
+            ..change(TrackRef::Index(0))
+        }));
+    }
+
+    /// Saved volumes can be taken back once.
+    #[test]
+    fn gain_undo_is_one_shot() {
+        let undo = GainUndo::new();
+        assert_eq!(undo.take(), None);
+        undo.save(vec![(0, 0.85), (2, 0.6)]);
+        undo.save(vec![(1, 0.7)]);
+        assert_eq!(undo.take(), Some(vec![(1, 0.7)]));
+        assert_eq!(undo.take(), None);
+    }
+}
@@ -1,13 +1,22 @@
 //! Tool implementations for the Ableton MCP server.
 
 pub mod application;
+pub mod audition;
 pub mod browser;
 pub mod clips;
 pub mod cue_points;
 pub mod devices;
+pub mod macros;
 pub mod midimap;
+pub mod mixer;
+pub mod modulation;
+pub mod presets;
+pub mod recording;
 pub mod scenes;
+pub mod scheduler;
 pub mod song;
+pub mod sound_design;
+pub mod templates;
 pub mod tracks;
 pub mod transport;
 pub mod view;
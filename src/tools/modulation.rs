@@ -0,0 +1,145 @@
+//! Parameter modulation tools (timed ramps and LFOs).
+
+use rmcp::handler::server::wrapper::Parameters;
+use rmcp::{tool, tool_router};
+use rosc::OscType;
+
+use crate::error::Error;
+use crate::modulation::{Modulation, Target};
+use crate::server::AbletonServer;
+use crate::types::{DeviceId, StartModulationParams, StopModulationParams, TargetKind, TrackId};
+
+#[tool_router(router = modulation_router, vis = "pub")]
+impl AbletonServer {
+    /// Start a ramp or LFO on a device or mixer parameter.
+    #[tool(
+        description = "Start a tempo-synced ramp (linear, exponential) or LFO (sine, triangle, square, sample_and_hold) on a device parameter, track volume, pan or send. Values are raw parameter values. Returns a modulation id"
+    )]
+    pub async fn start_modulation(
+        &self,
+        Parameters(params): Parameters<StartModulationParams>,
+    ) -> Result<String, Error> {
+        let rate_beats = params.rate_beats.unwrap_or(1.0);
+        if rate_beats <= 0.0 {
+            return Err(Error::InvalidParameter(
+                "rate_beats must be greater than 0".to_string(),
+            ));
+        }
+        if params.duration_beats.is_some_and(|d| d <= 0.0) {
+            return Err(Error::InvalidParameter(
+                "duration_beats must be greater than 0".to_string(),
+            ));
+        }
+        if params.shape.is_ramp() && params.duration_beats.is_none() {
+            return Err(Error::InvalidParameter(
+                "Ramps require duration_beats".to_string(),
+            ));
+        }
+
+        let TrackId(track) = self.resolve_track(&params.track).await?;
+        let (target, (min, max)) = match params.target {
+            TargetKind::Device => {
+                let (Some(device), Some(parameter)) = (&params.device, &params.parameter) else {
+                    return Err(Error::InvalidParameter(
+                        "Device modulation requires device and parameter".to_string(),
+                    ));
+                };
+                let DeviceId(device) = self.resolve_device(TrackId(track), device).await?;
+                let (parameter, name) = self.resolve_parameter(track, device, parameter).await?;
+                let args = vec![
+                    OscType::Int(track as i32),
+                    OscType::Int(device as i32),
+                    OscType::Int(parameter as i32),
+                ];
+                let min: f32 = self
+                    .osc
+                    .query("/live/device/get/parameter/min", args.clone())
+                    .await?;
+                let max: f32 = self
+                    .osc
+                    .query("/live/device/get/parameter/max", args)
+                    .await?;
+                let target = Target::Device {
+                    track,
+                    device,
+                    parameter,
+                    name,
+                };
+                (target, (min, max))
+            }
+            TargetKind::Volume => (Target::Volume { track }, (0.0, 1.0)),
+            TargetKind::Pan => (Target::Pan { track }, (-1.0, 1.0)),
+            TargetKind::Send => {
+                let Some(send) = params.send else {
+                    return Err(Error::InvalidParameter(
+                        "Send modulation requires send".to_string(),
+                    ));
+                };
+                (Target::Send { track, send }, (0.0, 1.0))
+            }
+        };
+
+        for value in [params.from, params.to] {
+            if !(min..=max).contains(&value) {
+                return Err(Error::InvalidParameter(format!(
+                    "Value {value} is outside the parameter range {min} to {max}"
+                )));
+            }
+        }
+
+        let tempo: f32 = self.osc.query("/live/song/get/tempo", vec![]).await?;
+        let song_time: f32 = self
+            .osc
+            .query("/live/song/get/current_song_time", vec![])
+            .await?;
+
+        let modulation = Modulation {
+            target,
+            shape: params.shape,
+            from: params.from,
+            to: params.to,
+            rate_beats,
+            duration_beats: params.duration_beats,
+        };
+        let length = modulation
+            .duration_beats
+            .map_or_else(|| "until stopped".to_string(), |d| format!("for {d} beats"));
+        let id = self
+            .modulations
+            .start(self.osc.clone(), modulation, tempo, f64::from(song_time));
+
+        Ok(format!(
+            "Started modulation {id} ({:?}, {} to {}) {length} at {tempo} BPM",
+            params.shape, params.from, params.to
+        ))
+    }
+
+    /// List active modulations.
+    #[tool(description = "List active modulations with their targets, shapes and elapsed beats")]
+    pub async fn list_modulations(&self) -> Result<String, Error> {
+        let active = self.modulations.list();
+        Ok(serde_json::to_string_pretty(&active).unwrap_or_else(|_| "[]".into()))
+    }
+
+    /// Stop one modulation, or all of them.
+    #[tool(
+        description = "Stop a modulation by id, or all modulations if no id is given. Parameters keep their current value"
+    )]
+    pub async fn stop_modulation(
+        &self,
+        Parameters(params): Parameters<StopModulationParams>,
+    ) -> Result<String, Error> {
+        match params.id {
+            Some(id) => match self.modulations.stop(id) {
+                Some(_) => Ok(format!("Stopped modulation {id}")),
+                None => Err(Error::InvalidParameter(format!(
+                    "No active modulation with id {id}"
+                ))),
+            },
+            None => {
+                let count = self.modulations.stop_all();
+                Ok(format!("Stopped {count} modulation(s)"))
+            }
+        }
+    }
+}
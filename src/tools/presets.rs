@@ -0,0 +1,153 @@
+//! Device preset tools: save and recall JSON parameter snapshots.
+
+use rmcp::handler::server::wrapper::Parameters;
+use rmcp::{tool, tool_router};
+use rosc::OscType;
+
+use crate::error::Error;
+use crate::presets::{DevicePreset, PresetLibrary, PresetParameter, map_parameters};
+use crate::server::AbletonServer;
+use crate::types::{
+    DeviceId, ListDevicePresetsParams, LoadDevicePresetParams, SaveDevicePresetParams, TrackId,
+};
+
+#[tool_router(router = presets_router, vis = "pub")]
+impl AbletonServer {
+    /// Save a device's parameters as a named preset.
+    #[tool(
+        description = "Save a device's current parameter values as a named preset in the local preset library"
+    )]
+    pub async fn save_device_preset(
+        &self,
+        Parameters(params): Parameters<SaveDevicePresetParams>,
+    ) -> Result<String, Error> {
+        let TrackId(track) = self.resolve_track(&params.track).await?;
+        let DeviceId(device) = self.resolve_device(TrackId(track), &params.device).await?;
+        let (device_name, class_name) = self.query_device_identity(track, device).await?;
+
+        let parameters = self.query_device_parameters(track, device).await?;
+        if parameters.is_empty() {
+            return Err(Error::InvalidResponse(format!(
+                "Device {device} on track {track} returned no parameters"
+            )));
+        }
+
+        let preset = DevicePreset {
+            name: params.name,
+            class_name,
+            device_name,
+            parameters: parameters
+                .into_iter()
+                .map(|p| PresetParameter {
+                    name: p.name,
+                    value: p.value,
+                })
+                .collect(),
+        };
+        let library = PresetLibrary::open_default()?;
+        let path = library.save(&preset, params.overwrite.unwrap_or(false))?;
+
+        Ok(format!(
+            "Saved preset \"{}\" ({} parameters of {}) to {}",
+            preset.name,
+            preset.parameters.len(),
+            preset.class_name,
+            path.display()
+        ))
+    }
+
+    /// Load a saved preset onto a device.
+    #[tool(
+        description = "Load a saved preset onto a device, mapping parameters by name. Refuses presets from a different device class unless force is set"
+    )]
+    pub async fn load_device_preset(
+        &self,
+        Parameters(params): Parameters<LoadDevicePresetParams>,
+    ) -> Result<String, Error> {
+        let TrackId(track) = self.resolve_track(&params.track).await?;
+        let DeviceId(device) = self.resolve_device(TrackId(track), &params.device).await?;
+        self.apply_device_preset(track, device, &params.name, params.force.unwrap_or(false))
+            .await
+    }
+
+    /// List saved device presets.
+    #[tool(description = "List saved device presets, optionally for one device class")]
+    pub async fn list_device_presets(
+        &self,
+        Parameters(params): Parameters<ListDevicePresetsParams>,
+    ) -> Result<String, Error> {
+        let library = PresetLibrary::open_default()?;
+        let presets = library.list(params.class_name.as_deref())?;
+        Ok(serde_json::to_string_pretty(&presets).unwrap_or_else(|_| "[]".into()))
+    }
+
+    // ========== Helper methods for internal use ==========
+
+    /// Apply a saved preset to a device, returning a report of what was set.
+    ///
+    /// Presets from another device class are refused unless `force` is set.
+    pub(crate) async fn apply_device_preset(
+        &self,
+        track: u32,
+        device: u32,
+        name: &str,
+        force: bool,
+    ) -> Result<String, Error> {
+        let (_, class_name) = self.query_device_identity(track, device).await?;
+
+        let library = PresetLibrary::open_default()?;
+        let summary = library.find(&class_name, name)?;
+        let preset = library.load(&summary.path)?;
+        let mismatch = preset.class_name != class_name;
+        if mismatch && !force {
+            return Err(Error::InvalidParameter(format!(
+                "Preset \"{}\" was saved from a {} device, but device {device} on track {track} is a {class_name} (set force to load anyway)",
+                preset.name, preset.class_name
+            )));
+        }
+
+        let current = self.query_device_parameters(track, device).await?;
+        let mapping = map_parameters(&preset, &current);
+
+        let mut args = vec![OscType::Int(track as i32), OscType::Int(device as i32)];
+        args.extend(mapping.values.iter().map(|v| OscType::Float(*v)));
+        self.osc
+            .send("/live/device/set/parameters/value", args)
+            .await?;
+
+        let mut lines = vec![format!(
+            "Loaded preset \"{}\" onto device {device} (track {track}): {} of {} parameters applied",
+            preset.name,
+            mapping.applied,
+            preset.parameters.len()
+        )];
+        if mismatch {
+            lines.push(format!(
+                "Warning: preset was saved from {}, device is {class_name}",
+                preset.class_name
+            ));
+        }
+        if !mapping.missing.is_empty() {
+            lines.push(format!(
+                "Not found on device: {}",
+                mapping.missing.join(", ")
+            ));
+        }
+        Ok(lines.join("\n"))
+    }
+
+    /// Query a device's name and class name.
+    pub(crate) async fn query_device_identity(
+        &self,
+        track: u32,
+        device: u32,
+    ) -> Result<(String, String), Error> {
+        let args = vec![OscType::Int(track as i32), OscType::Int(device as i32)];
+        let name: String = self
+            .osc
+            .query("/live/device/get/name", args.clone())
+            .await?;
+        let class_name: String = self.osc.query("/live/device/get/class_name", args).await?;
+        Ok((name, class_name))
+    }
+}
@@ -0,0 +1,666 @@
+//! Recording workflow tools: resample a track to audio and record takes.
+//!
+//! `AbletonOSC` does not expose track freezing, so committing a CPU-heavy track
+//! is done by resampling it: a new audio track takes the source track as its
+//! input, records it into a clip slot for a whole number of bars, and the
+//! source is muted afterwards. `restore_resampled_track` undoes that.
+//!
+//! Takes are recorded back to back: launching the next empty slot while a
+//! take records ends that take on the bar line where the next one starts.
+//!
+//! Recordings wait for Live by polling `/live/song/get/current_song_time` and
+//! `/live/clip/get/is_recording`, so a tool call lasts as long as the
+//! recording.
+
+use std::time::Duration;
+
+use rmcp::handler::server::wrapper::Parameters;
+use rmcp::{tool, tool_router};
+use rosc::OscType;
+use tokio::time::Instant;
+
+use crate::error::Error;
+use crate::recording::{
+    QUANTIZE_ONE_BAR, RecordedClip, find_track_input, fire_beat, next_bar, next_take_number,
+    recording_start, take_name,
+};
+use crate::server::AbletonServer;
+use crate::tools::devices::POLL_INTERVAL;
+use crate::tools::mixer::is_inside;
+use crate::types::{RecordTakeParams, ResampleTrackParams, RestoreResampledTrackParams, TrackId};
+
+/// Longest sleep between song position checks while waiting for a beat.
+const MAX_WAIT_STEP: f64 = 0.25;
+
+/// Monitoring state that keeps a recording track silent.
+const MONITOR_OFF: i32 = 2;
+
+#[tool_router(router = recording_router, vis = "pub")]
+impl AbletonServer {
+    /// Resample a track to a new audio track.
+    #[tool(
+        description = "Commit a track to audio (the freeze alternative, since AbletonOSC cannot freeze): creates an audio track after it (after all of its tracks, for a group) with the source as input, records a clip (the source clip's length or N bars, starting and ending on bar lines), then disarms the new track and mutes the source. Blocks while recording; returns the new clip"
+    )]
+    pub async fn resample_track(
+        &self,
+        Parameters(params): Parameters<ResampleTrackParams>,
+    ) -> Result<String, Error> {
+        let TrackId(source) = self.resolve_track(&params.track).await?;
+        let source_name: String = self
+            .osc
+            .query("/live/track/get/name", vec![OscType::Int(source as i32)])
+            .await?;
+        let numerator: i32 = self
+            .osc
+            .query("/live/song/get/signature_numerator", vec![])
+            .await?;
+        let beats = match (params.bars, params.slot) {
+            (Some(0), _) => {
+                return Err(Error::InvalidParameter(
+                    "bars must be at least 1".to_string(),
+                ));
+            }
+            (Some(bars), _) => f64::from(bars) * f64::from(numerator),
+            (None, Some(slot)) => {
+                let args = vec![OscType::Int(source as i32), OscType::Int(slot as i32)];
+                if !self
+                    .query_flag("/live/clip_slot/get/has_clip", args.clone())
+                    .await?
+                {
+                    return Err(Error::InvalidParameter(format!(
+                        "Clip slot {slot} on track {source} is empty"
+                    )));
+                }
+                let length: f32 = self.osc.query("/live/clip/get/length", args).await?;
+                f64::from(length)
+            }
+            (None, None) => {
+                return Err(Error::InvalidParameter(
+                    "Give a source clip slot or a length in bars".to_string(),
+                ));
+            }
+        };
+
+        // Put the audio track right after the source, or after everything in
+        // it when the source is a group
+        let count: i32 = self.osc.query("/live/song/get/num_tracks", vec![]).await?;
+        let (is_group, parents) = self.track_groups().await?;
+        let track = if is_group.get(source as usize).copied().unwrap_or(false) {
+            after_group(source, &parents)
+        } else {
+            source + 1
+        };
+        self.osc
+            .send(
+                "/live/song/create_audio_track",
+                vec![OscType::Int(track as i32)],
+            )
+            .await?;
+        self.wait_for_track_count(count + 1).await?;
+
+        let target_slot = params.target_slot.or(params.slot).unwrap_or(0);
+        let prepared = self
+            .prepare_resample_track(track, source, &source_name, &params)
+            .await;
+        if let Err(e) = prepared {
+            self.osc
+                .send("/live/song/delete_track", vec![OscType::Int(track as i32)])
+                .await?;
+            return Err(e);
+        }
+
+        let with = params.slot.map(|slot| (source, slot));
+        let clip = match self.record_into_slot(track, target_slot, beats, with).await {
+            Ok(clip) => clip,
+            Err(e) => {
+                self.osc
+                    .send("/live/song/delete_track", vec![OscType::Int(track as i32)])
+                    .await?;
+                return Err(e);
+            }
+        };
+        self.set_track_switch(track, "arm", false).await?;
+
+        let mute = params.mute_source.unwrap_or(true);
+        if mute {
+            self.set_track_switch(source, "mute", true).await?;
+        }
+        for stop in [source, track] {
+            self.osc
+                .send(
+                    "/live/track/stop_all_clips",
+                    vec![OscType::Int(stop as i32)],
+                )
+                .await?;
+        }
+
+        let lines = [
+            format!(
+                "Resampled track {source} \"{source_name}\" to audio track {track}{}",
+                if mute { "; source muted" } else { "" }
+            ),
+            serde_json::to_string_pretty(&clip).unwrap_or_else(|_| format!("{clip:?}")),
+        ];
+        Ok(lines.join("\n"))
+    }
+
+    /// Go back to the source of a resampled track.
+    #[tool(
+        description = "Undo resample_track: unmute the source track and mute (or delete) the resampled audio track"
+    )]
+    pub async fn restore_resampled_track(
+        &self,
+        Parameters(params): Parameters<RestoreResampledTrackParams>,
+    ) -> Result<String, Error> {
+        let TrackId(source) = self.resolve_track(&params.track).await?;
+        let TrackId(resampled) = self.resolve_track(&params.resampled).await?;
+        if source == resampled {
+            return Err(Error::InvalidParameter(
+                "The source and resampled tracks must differ".to_string(),
+            ));
+        }
+        self.set_track_switch(source, "mute", false).await?;
+        if params.delete.unwrap_or(false) {
+            self.osc
+                .send(
+                    "/live/song/delete_track",
+                    vec![OscType::Int(resampled as i32)],
+                )
+                .await?;
+            Ok(format!(
+                "Unmuted track {source} and deleted resampled track {resampled}"
+            ))
+        } else {
+            self.set_track_switch(resampled, "mute", true).await?;
+            Ok(format!(
+                "Unmuted track {source} and muted resampled track {resampled}"
+            ))
+        }
+    }
+
+    /// Record one or more takes into clip slots.
+    #[tool(
+        description = "Record takes on a track: arms it, optionally counts in with the metronome, records N bars into a clip slot starting and ending on bar lines, and can record several takes back to back into the following empty slots. Takes are named '<name> Take N', continuing the numbering. Disarms afterwards; blocks while recording and returns the recorded clips"
+    )]
+    pub async fn record_take(
+        &self,
+        Parameters(params): Parameters<RecordTakeParams>,
+    ) -> Result<String, Error> {
+        if params.bars == 0 {
+            return Err(Error::InvalidParameter(
+                "bars must be at least 1".to_string(),
+            ));
+        }
+        let takes = params.takes.unwrap_or(1);
+        if takes == 0 {
+            return Err(Error::InvalidParameter(
+                "takes must be at least 1".to_string(),
+            ));
+        }
+        let TrackId(track) = self.resolve_track(&params.track).await?;
+        let track_args = vec![OscType::Int(track as i32)];
+        if !self
+            .query_flag("/live/track/get/can_be_armed", track_args.clone())
+            .await?
+        {
+            return Err(Error::InvalidParameter(format!(
+                "Track {track} cannot be armed"
+            )));
+        }
+        let slots = self.empty_slots(track, params.slot, takes).await?;
+        let base = match params.name {
+            Some(name) => name,
+            None => self.osc.query("/live/track/get/name", track_args).await?,
+        };
+        let clip_names = self
+            .query_names(
+                "/live/track/get/clips/name",
+                vec![OscType::Int(track as i32)],
+            )
+            .await?;
+        let first_take = next_take_number(&base, &clip_names);
+
+        // Read everything the restore needs before changing anything
+        let was_armed = self.track_switch(track, "arm").await?;
+        let count_in = params.count_in.unwrap_or(0);
+        let metronome: i32 = self.osc.query("/live/song/get/metronome", vec![]).await?;
+        let quantization = self.quantize_to_bars().await?;
+
+        let recorded = async {
+            self.set_track_switch(track, "arm", true).await?;
+            if count_in > 0 && metronome == 0 {
+                self.osc
+                    .send("/live/song/set/metronome", vec![OscType::Int(1)])
+                    .await?;
+            }
+            let (bar, _) = self.song_meter().await?;
+            let beats = f64::from(params.bars) * bar;
+            let at = if count_in > 0 {
+                let playing: bool = self.osc.query("/live/song/get/is_playing", vec![]).await?;
+                if !playing {
+                    self.osc.send("/live/song/start_playing", vec![]).await?;
+                }
+                let now: f32 = self
+                    .osc
+                    .query("/live/song/get/current_song_time", vec![])
+                    .await?;
+                Some(next_bar(f64::from(now), bar) + f64::from(count_in) * bar)
+            } else {
+                None
+            };
+
+            let mut clips = Vec::new();
+            let mut start = self.launch_recording(track, slots[0], None, at).await?;
+            for (i, &slot) in slots.iter().enumerate() {
+                if i > 0 {
+                    start = self.wait_for_recording_start(track, slot).await?;
+                }
+                let next = slots.get(i + 1).copied();
+                let mut clip = self
+                    .finish_recording(track, slot, start + beats, next)
+                    .await?;
+                clip.name = take_name(&base, first_take + i as u32);
+                self.osc
+                    .send(
+                        "/live/clip/set/name",
+                        vec![
+                            OscType::Int(track as i32),
+                            OscType::Int(slot as i32),
+                            OscType::String(clip.name.clone()),
+                        ],
+                    )
+                    .await?;
+                clips.push(clip);
+            }
+            Ok::<_, Error>(clips)
+        }
+        .await;
+
+        // Run every restore step even if one fails, then report the first error
+        let restored = [
+            self.restore_quantization(quantization).await,
+            if count_in > 0 && metronome == 0 {
+                self.osc
+                    .send("/live/song/set/metronome", vec![OscType::Int(0)])
+                    .await
+            } else {
+                Ok(())
+            },
+            self.set_track_switch(track, "arm", was_armed).await,
+            self.osc
+                .send(
+                    "/live/track/stop_all_clips",
+                    vec![OscType::Int(track as i32)],
+                )
+                .await,
+        ];
+        let clips = recorded?;
+        restored.into_iter().collect::<Result<(), Error>>()?;
+        let lines = [
+            format!("Recorded {} take(s) on track {track}", clips.len()),
+            serde_json::to_string_pretty(&clips).unwrap_or_else(|_| format!("{clips:?}")),
+        ];
+        Ok(lines.join("\n"))
+    }
+
+    // ========== Helper methods for internal use ==========
+
+    /// `count` empty clip slots on a track, from `first` (or the first empty
+    /// slot) on. A given `first` slot must itself be empty.
+    async fn empty_slots(
+        &self,
+        track: u32,
+        first: Option<u32>,
+        count: u32,
+    ) -> Result<Vec<u32>, Error> {
+        let scenes: i32 = self.osc.query("/live/song/get/num_scenes", vec![]).await?;
+        let scenes = u32::try_from(scenes).unwrap_or(0);
+        let mut slots = Vec::new();
+        for slot in first.unwrap_or(0)..scenes {
+            let has_clip = self
+                .query_flag(
+                    "/live/clip_slot/get/has_clip",
+                    vec![OscType::Int(track as i32), OscType::Int(slot as i32)],
+                )
+                .await?;
+            if has_clip && first == Some(slot) {
+                return Err(Error::InvalidParameter(format!(
+                    "Clip slot {slot} on track {track} already has a clip"
+                )));
+            }
+            if !has_clip {
+                slots.push(slot);
+                if slots.len() == count as usize {
+                    return Ok(slots);
+                }
+            }
+        }
+        Err(Error::InvalidParameter(format!(
+            "Track {track} has only {} empty clip slot(s) for {count} take(s); add scenes first",
+            slots.len()
+        )))
+    }
+
+    /// Route a new audio track from the source, silence its monitoring, name
+    /// it and arm it.
+    async fn prepare_resample_track(
+        &self,
+        track: u32,
+        source: u32,
+        source_name: &str,
+        params: &ResampleTrackParams,
+    ) -> Result<(), Error> {
+        let types = self.query_available_input_routing_types(track).await?;
+        let input =
+            find_track_input(&types, source, source_name).ok_or_else(|| Error::NotFound {
+                kind: "input routing type",
+                query: source_name.to_string(),
+                available: types.clone(),
+            })?;
+        let args = |value: OscType| vec![OscType::Int(track as i32), value];
+        self.osc
+            .send(
+                "/live/track/set/input_routing_type",
+                args(OscType::String(types[input].clone())),
+            )
+            .await?;
+        if let Some(tap) = &params.tap {
+            self.osc
+                .send(
+                    "/live/track/set/input_routing_channel",
+                    args(OscType::String(tap.clone())),
+                )
+                .await?;
+        }
+        self.osc
+            .send(
+                "/live/track/set/current_monitoring_state",
+                args(OscType::Int(MONITOR_OFF)),
+            )
+            .await?;
+        let name = params
+            .name
+            .clone()
+            .unwrap_or_else(|| format!("{source_name} (Resampled)"));
+        self.osc
+            .send("/live/track/set/name", args(OscType::String(name)))
+            .await?;
+        self.set_track_switch(track, "arm", true).await
+    }
+
+    /// Record `beats` into an empty clip slot on an armed track, starting and
+    /// ending on bar lines, optionally launching another clip slot with it.
+    pub(crate) async fn record_into_slot(
+        &self,
+        track: u32,
+        slot: u32,
+        beats: f64,
+        with: Option<(u32, u32)>,
+    ) -> Result<RecordedClip, Error> {
+        let slot_args = vec![OscType::Int(track as i32), OscType::Int(slot as i32)];
+        if self
+            .query_flag("/live/clip_slot/get/has_clip", slot_args)
+            .await?
+        {
+            return Err(Error::InvalidParameter(format!(
+                "Clip slot {slot} on track {track} already has a clip"
+            )));
+        }
+        let quantization = self.quantize_to_bars().await?;
+        let recorded = async {
+            let start = self.launch_recording(track, slot, with, None).await?;
+            self.finish_recording(track, slot, start + beats, None)
+                .await
+        }
+        .await;
+        let restored = self.restore_quantization(quantization).await;
+        let clip = recorded?;
+        restored?;
+        Ok(clip)
+    }
+
+    /// Set clip trigger quantization to one bar, returning the previous value.
+    async fn quantize_to_bars(&self) -> Result<i32, Error> {
+        let quantization: i32 = self
+            .osc
+            .query("/live/song/get/clip_trigger_quantization", vec![])
+            .await?;
+        self.osc
+            .send(
+                "/live/song/set/clip_trigger_quantization",
+                vec![OscType::Int(QUANTIZE_ONE_BAR)],
+            )
+            .await?;
+        Ok(quantization)
+    }
+
+    /// Put back the clip trigger quantization saved by [`Self::quantize_to_bars`].
+    async fn restore_quantization(&self, quantization: i32) -> Result<(), Error> {
+        self.osc
+            .send(
+                "/live/song/set/clip_trigger_quantization",
+                vec![OscType::Int(quantization)],
+            )
+            .await
+    }
+
+    /// Beats per bar and tempo.
+    pub(crate) async fn song_meter(&self) -> Result<(f64, f32), Error> {
+        let numerator: i32 = self
+            .osc
+            .query("/live/song/get/signature_numerator", vec![])
+            .await?;
+        let tempo: f32 = self.osc.query("/live/song/get/tempo", vec![]).await?;
+        Ok((f64::from(numerator), tempo))
+    }
+
+    /// Launch a recording into an empty slot, together with another clip slot
+    /// if given, and return the song beat it started at.
+    ///
+    /// Without `at`, the recording starts on the next bar line; with it, on
+    /// the bar line at that beat.
+    async fn launch_recording(
+        &self,
+        track: u32,
+        slot: u32,
+        with: Option<(u32, u32)>,
+        at: Option<f64>,
+    ) -> Result<f64, Error> {
+        let (bar, tempo) = self.song_meter().await?;
+        if let Some(at) = at {
+            self.wait_for_beat(fire_beat(at, bar), tempo).await?;
+        }
+        if let Some((other, other_slot)) = with {
+            self.osc
+                .send(
+                    "/live/clip_slot/fire",
+                    vec![OscType::Int(other as i32), OscType::Int(other_slot as i32)],
+                )
+                .await?;
+        }
+        self.osc
+            .send(
+                "/live/clip_slot/fire",
+                vec![OscType::Int(track as i32), OscType::Int(slot as i32)],
+            )
+            .await?;
+        self.wait_for_recording_start(track, slot).await
+    }
+
+    /// Wait for a launched recording to start and return the song beat it
+    /// started at. Stops the slot and fails if nothing records within two bars.
+    async fn wait_for_recording_start(&self, track: u32, slot: u32) -> Result<f64, Error> {
+        let slot_args = vec![OscType::Int(track as i32), OscType::Int(slot as i32)];
+        let (bar, tempo) = self.song_meter().await?;
+        let deadline = Instant::now() + beats_duration(2.0 * bar, tempo) + Duration::from_secs(1);
+        loop {
+            tokio::time::sleep(POLL_INTERVAL).await;
+            if self.is_recording(&slot_args).await? {
+                let song_time: f32 = self
+                    .osc
+                    .query("/live/song/get/current_song_time", vec![])
+                    .await?;
+                let position: f32 = self
+                    .osc
+                    .query("/live/clip/get/playing_position", slot_args)
+                    .await
+                    .unwrap_or(-1.0);
+                return Ok(recording_start(
+                    f64::from(song_time),
+                    f64::from(position),
+                    bar,
+                ));
+            }
+            if Instant::now() > deadline {
+                self.osc.send("/live/clip_slot/stop", slot_args).await?;
+                return Err(Error::InvalidResponse(format!(
+                    "Recording did not start on track {track}, slot {slot}; is the track armed?"
+                )));
+            }
+        }
+    }
+
+    /// End a recording on the bar line at `end` and describe the clip.
+    ///
+    /// With `next`, that slot is launched instead of re-firing the recording
+    /// one, so the next recording starts at `end` without a gap.
+    async fn finish_recording(
+        &self,
+        track: u32,
+        slot: u32,
+        end: f64,
+        next: Option<u32>,
+    ) -> Result<RecordedClip, Error> {
+        let slot_args = vec![OscType::Int(track as i32), OscType::Int(slot as i32)];
+        let (bar, tempo) = self.song_meter().await?;
+        self.wait_for_beat(fire_beat(end, bar), tempo).await?;
+        // Launching any slot on the track ends the recording on the next bar line
+        let fire = next.map_or_else(
+            || slot_args.clone(),
+            |next| vec![OscType::Int(track as i32), OscType::Int(next as i32)],
+        );
+        self.osc.send("/live/clip_slot/fire", fire).await?;
+        self.wait_for_beat(end, tempo).await?;
+        let deadline = Instant::now() + beats_duration(bar, tempo);
+        while self.is_recording(&slot_args).await? && Instant::now() < deadline {
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+
+        let name: String = self
+            .osc
+            .query("/live/clip/get/name", slot_args.clone())
+            .await
+            .unwrap_or_default();
+        let length: f32 = self
+            .osc
+            .query("/live/clip/get/length", slot_args.clone())
+            .await?;
+        let is_audio_clip = self
+            .query_flag("/live/clip/get/is_audio_clip", slot_args.clone())
+            .await
+            .unwrap_or(false);
+        let file_path = if is_audio_clip {
+            self.osc
+                .query::<String>("/live/clip/get/file_path", slot_args)
+                .await
+                .ok()
+                .filter(|path| !path.is_empty())
+        } else {
+            None
+        };
+        Ok(RecordedClip {
+            track,
+            slot,
+            name,
+            length,
+            is_audio_clip,
+            file_path,
+        })
+    }
+
+    /// Whether the clip in a slot exists and is recording.
+    async fn is_recording(&self, slot_args: &[OscType]) -> Result<bool, Error> {
+        if !self
+            .query_flag("/live/clip_slot/get/has_clip", slot_args.to_vec())
+            .await?
+        {
+            return Ok(false);
+        }
+        self.query_flag("/live/clip/get/is_recording", slot_args.to_vec())
+            .await
+    }
+
+    /// Query an on/off property whose reply echoes the request arguments.
+    pub(crate) async fn query_flag(&self, addr: &str, args: Vec<OscType>) -> Result<bool, Error> {
+        let reply: Vec<OscType> = self.osc.query(addr, args).await?;
+        reply_flag(addr, &reply)
+    }
+
+    /// Wait until the song position reaches `beat`.
+    ///
+    /// Fails if the song stops first.
+    pub(crate) async fn wait_for_beat(&self, beat: f64, tempo: f32) -> Result<(), Error> {
+        loop {
+            let now: f32 = self
+                .osc
+                .query("/live/song/get/current_song_time", vec![])
+                .await?;
+            let remaining = beat - f64::from(now);
+            if remaining <= 0.0 {
+                return Ok(());
+            }
+            let playing: bool = self.osc.query("/live/song/get/is_playing", vec![]).await?;
+            if !playing {
+                return Err(Error::InvalidResponse(format!(
+                    "The song stopped at beat {now:.2} before reaching beat {beat:.2}"
+                )));
+            }
+            let step = (remaining * 60.0 / f64::from(tempo)).clamp(0.005, MAX_WAIT_STEP);
+            tokio::time::sleep(Duration::from_secs_f64(step)).await;
+        }
+    }
+}
+
+/// Index just past the last track inside `group`, including nested groups.
+///
+/// Grouped tracks follow their group track, so this is where a track goes to
+/// sit after the group rather than inside it.
+fn after_group(group: u32, parents: &[Option<u32>]) -> u32 {
+    let mut track = group + 1;
+    while is_inside(track, group, parents) {
+        track += 1;
+    }
+    track
+}
+
+/// Wall-clock duration of `beats` at `tempo`.
+fn beats_duration(beats: f64, tempo: f32) -> Duration {
+    Duration::from_secs_f64((beats * 60.0 / f64::from(tempo)).max(0.0))
+}
+
+/// Read an on/off value from the last argument of a reply, after any echoed
+/// request arguments.
+pub(crate) fn reply_flag(addr: &str, reply: &[OscType]) -> Result<bool, Error> {
+    match reply.last() {
+        Some(OscType::Int(v)) => Ok(*v != 0),
+        Some(OscType::Bool(v)) => Ok(*v),
+        other => Err(Error::InvalidResponse(format!(
+            "Expected an on/off value from {addr}, got {other:?}"
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A group's resample track goes after its last track, nested or not.
+    #[test]
+    fn after_group_skips_grouped_tracks() {
+        // 0: Drums (group), 1: Kick, 2: Tops (group in Drums), 3: Hats, 4: Bass
+        let parents = [None, Some(0), Some(0), Some(2), None];
+        assert_eq!(after_group(0, &parents), 4);
+        assert_eq!(after_group(2, &parents), 4);
+        assert_eq!(after_group(4, &parents), 5);
+    }
+}
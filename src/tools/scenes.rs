@@ -7,7 +7,7 @@ use rosc::OscType;
 use crate::error::Error;
 use crate::server::AbletonServer;
 use crate::types::{
-    CreateSceneParams, SceneInfo, SceneParams, SetSceneColorParams, SetSceneNameParams,
+    CreateSceneParams, SceneId, SceneInfo, SceneParams, SetSceneColorParams, SetSceneNameParams,
     SetSceneTempoEnabledParams, SetSceneTempoParams, SetSceneTimeSigEnabledParams,
     SetSceneTimeSignatureParams,
 };
@@ -42,7 +42,7 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<SceneParams>,
     ) -> Result<String, Error> {
-        let scene = params.scene;
+        let SceneId(scene) = self.resolve_scene(&params.scene).await?;
         self.osc
             .send("/live/scene/fire", vec![OscType::Int(scene as i32)])
             .await?;
@@ -72,7 +72,7 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<SceneParams>,
     ) -> Result<String, Error> {
-        let scene = params.scene;
+        let SceneId(scene) = self.resolve_scene(&params.scene).await?;
         self.osc
             .send("/live/song/delete_scene", vec![OscType::Int(scene as i32)])
             .await?;
@@ -85,7 +85,7 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<SceneParams>,
     ) -> Result<String, Error> {
-        let scene = params.scene;
+        let SceneId(scene) = self.resolve_scene(&params.scene).await?;
         self.osc
             .send(
                 "/live/song/duplicate_scene",
@@ -101,7 +101,7 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<SetSceneNameParams>,
     ) -> Result<String, Error> {
-        let scene = params.scene;
+        let SceneId(scene) = self.resolve_scene(&params.scene).await?;
         let name = params.name.clone();
         self.osc
             .send(
@@ -118,7 +118,7 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<SceneParams>,
     ) -> Result<String, Error> {
-        let scene = params.scene;
+        let SceneId(scene) = self.resolve_scene(&params.scene).await?;
         let color: i32 = self
             .osc
             .query("/live/scene/get/color", vec![OscType::Int(scene as i32)])
@@ -132,7 +132,7 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<SetSceneColorParams>,
     ) -> Result<String, Error> {
-        let scene = params.scene;
+        let SceneId(scene) = self.resolve_scene(&params.scene).await?;
         let color = params.color;
         self.osc
             .send(
@@ -149,7 +149,7 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<SceneParams>,
     ) -> Result<String, Error> {
-        let scene = params.scene;
+        let SceneId(scene) = self.resolve_scene(&params.scene).await?;
         let tempo: f32 = self
             .osc
             .query("/live/scene/get/tempo", vec![OscType::Int(scene as i32)])
@@ -163,7 +163,7 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<SetSceneTempoParams>,
     ) -> Result<String, Error> {
-        let scene = params.scene;
+        let SceneId(scene) = self.resolve_scene(&params.scene).await?;
         let tempo = params.tempo;
         self.osc
             .send(
@@ -180,7 +180,7 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<SceneParams>,
     ) -> Result<String, Error> {
-        let scene = params.scene;
+        let SceneId(scene) = self.resolve_scene(&params.scene).await?;
         let result: i32 = self
             .osc
             .query(
@@ -201,7 +201,7 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<SetSceneTempoEnabledParams>,
     ) -> Result<String, Error> {
-        let scene = params.scene;
+        let SceneId(scene) = self.resolve_scene(&params.scene).await?;
         let enabled = params.enabled;
         self.osc
             .send(
@@ -224,7 +224,7 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<SceneParams>,
     ) -> Result<String, Error> {
-        let scene = params.scene;
+        let SceneId(scene) = self.resolve_scene(&params.scene).await?;
         let numerator: i32 = self
             .osc
             .query(
@@ -243,7 +243,7 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<SceneParams>,
     ) -> Result<String, Error> {
-        let scene = params.scene;
+        let SceneId(scene) = self.resolve_scene(&params.scene).await?;
         let denominator: i32 = self
             .osc
             .query(
@@ -262,7 +262,7 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<SetSceneTimeSignatureParams>,
     ) -> Result<String, Error> {
-        let scene = params.scene;
+        let SceneId(scene) = self.resolve_scene(&params.scene).await?;
         let numerator = params.numerator;
         let denominator = params.denominator;
         self.osc
@@ -288,7 +288,7 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<SceneParams>,
     ) -> Result<String, Error> {
-        let scene = params.scene;
+        let SceneId(scene) = self.resolve_scene(&params.scene).await?;
         let result: i32 = self
             .osc
             .query(
@@ -309,7 +309,7 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<SetSceneTimeSigEnabledParams>,
     ) -> Result<String, Error> {
-        let scene = params.scene;
+        let SceneId(scene) = self.resolve_scene(&params.scene).await?;
         let enabled = params.enabled;
         self.osc
             .send(
@@ -332,7 +332,7 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<SceneParams>,
     ) -> Result<String, Error> {
-        let scene = params.scene;
+        let SceneId(scene) = self.resolve_scene(&params.scene).await?;
         let result: i32 = self
             .osc
             .query(
@@ -0,0 +1,263 @@
+//! Scheduler tools: queue tool calls for a bar, beat or quantization
+//! boundary.
+//!
+//! A scheduled action follows the song position by polling
+//! `current_song_time`, more often as the target gets closer. While the song
+//! is stopped the action keeps waiting; if the song jumps past the target,
+//! the action fires as soon as the jump is seen. A few missed replies in a
+//! row are waited out before the action gives up.
+//!
+//! Clip and scene launches are quantized by Live, so with global
+//! quantization on they are sent ahead of the target (see
+//! [`launch_send_beat`]).
+
+use std::time::Duration;
+
+use rmcp::handler::server::wrapper::Parameters;
+use rmcp::model::CallToolResult;
+use rmcp::service::RequestContext;
+use rmcp::{RoleServer, tool, tool_router};
+use serde_json::json;
+
+use crate::error::Error;
+use crate::scheduler::{
+    ScheduledAction, bar_beat, format_position, launch_send_beat, next_boundary, parse_grid,
+    trigger_quantization_grid,
+};
+use crate::server::AbletonServer;
+use crate::types::{CancelScheduledActionParams, ScheduleActionParams};
+
+/// Longest sleep between song position checks while waiting for a target.
+const MAX_WAIT_STEP: f64 = 0.25;
+
+/// Song position checks in a row that may time out before an action gives up.
+const MAX_POLL_TIMEOUTS: u32 = 8;
+
+/// Tools whose launch Live holds until the next quantization boundary.
+const QUANTIZED_LAUNCHES: [&str; 3] = ["fire_clip", "fire_scene", "fire_selected_scene"];
+
+/// Tools that cannot be scheduled.
+const UNSCHEDULABLE: [&str; 3] = [
+    "schedule_action",
+    "list_scheduled_actions",
+    "cancel_scheduled_action",
+];
+
+#[tool_router(router = scheduler_router, vis = "pub")]
+impl AbletonServer {
+    /// Queue a tool call for a song position.
+    #[tool(
+        description = "Schedule any tool call to fire at a song position: a bar (and beat), an absolute song beat, or the next quantization boundary ('global' uses the song's clip trigger quantization). Pending actions wait while the song is stopped. Returns an action id"
+    )]
+    pub async fn schedule_action(
+        &self,
+        Parameters(params): Parameters<ScheduleActionParams>,
+        context: RequestContext<RoleServer>,
+    ) -> Result<String, Error> {
+        if UNSCHEDULABLE.contains(&params.tool.as_str()) {
+            return Err(Error::InvalidParameter(format!(
+                "{} cannot be scheduled",
+                params.tool
+            )));
+        }
+        if !self.has_tool(&params.tool) {
+            return Err(Error::InvalidParameter(format!(
+                "Unknown tool \"{}\"",
+                params.tool
+            )));
+        }
+        if params.beat.is_some() && params.bar.is_none() {
+            return Err(Error::InvalidParameter("beat requires bar".to_string()));
+        }
+
+        let (beats_per_bar, _) = self.song_meter().await?;
+        let now: f32 = self
+            .osc
+            .query("/live/song/get/current_song_time", vec![])
+            .await?;
+        let now = f64::from(now);
+        let beat = match (params.bar, params.song_beat, &params.quantize) {
+            (Some(bar), None, None) => bar_beat(bar, params.beat.unwrap_or(1.0), beats_per_bar)?,
+            (None, Some(song_beat), None) if song_beat >= 0.0 => song_beat,
+            (None, Some(song_beat), None) => {
+                return Err(Error::InvalidParameter(format!(
+                    "song_beat must be 0 or later, got {song_beat}"
+                )));
+            }
+            (None, None, Some(quantize)) => {
+                let grid = self.schedule_grid(quantize, beats_per_bar).await?;
+                next_boundary(now, grid)
+            }
+            _ => {
+                return Err(Error::InvalidParameter(
+                    "Give exactly one of bar, song_beat or quantize".to_string(),
+                ));
+            }
+        };
+        let position = format_position(beat, beats_per_bar);
+        if beat <= now {
+            return Err(Error::InvalidParameter(format!(
+                "{position} is already past; the song is at {}",
+                format_position(now, beats_per_bar)
+            )));
+        }
+
+        // Live holds a launch until the next boundary, so send it early
+        let mut send_at = beat;
+        if QUANTIZED_LAUNCHES.contains(&params.tool.as_str()) {
+            if let Some(grid) = self.global_grid(beats_per_bar).await? {
+                send_at = launch_send_beat(beat, grid);
+            }
+        }
+
+        let tool = params.tool.clone();
+        let action = ScheduledAction {
+            tool: tool.clone(),
+            arguments: params.arguments.clone(),
+            beat,
+            position: position.clone(),
+        };
+        let server = self.clone();
+        let peer = context.peer;
+        let id = self.scheduler.schedule(action, async move {
+            if let Err(e) = server.wait_for_song_beat(send_at).await {
+                return format!("Not fired: {e}");
+            }
+            let result = server
+                .call_tool_by_name(params.tool, params.arguments, peer)
+                .await;
+            match result {
+                Ok(result) => tool_result_text(&result),
+                Err(e) => format!("Error: {}", e.message),
+            }
+        });
+        let lead = if send_at < beat {
+            format!(", sent at beat {send_at} so launch quantization starts it on time")
+        } else {
+            String::new()
+        };
+        Ok(format!(
+            "Scheduled action {id}: {tool} at {position} (beat {beat}){lead}"
+        ))
+    }
+
+    /// List pending and recently fired actions.
+    #[tool(
+        description = "List pending scheduled actions (soonest first) and recently fired ones with the result of each tool call"
+    )]
+    pub async fn list_scheduled_actions(&self) -> Result<String, Error> {
+        let listing = json!({
+            "pending": self.scheduler.pending(),
+            "fired": self.scheduler.fired(),
+        });
+        Ok(serde_json::to_string_pretty(&listing).unwrap_or_else(|_| "{}".into()))
+    }
+
+    /// Cancel one pending action, or all of them.
+    #[tool(
+        description = "Cancel a pending scheduled action by id, or all pending actions if no id is given"
+    )]
+    pub async fn cancel_scheduled_action(
+        &self,
+        Parameters(params): Parameters<CancelScheduledActionParams>,
+    ) -> Result<String, Error> {
+        match params.id {
+            Some(id) => match self.scheduler.cancel(id) {
+                Some(status) => Ok(format!(
+                    "Cancelled action {id}: {} at {}",
+                    status.action.tool, status.action.position
+                )),
+                None => Err(Error::InvalidParameter(format!(
+                    "No pending action with id {id}"
+                ))),
+            },
+            None => {
+                let count = self.scheduler.cancel_all();
+                Ok(format!("Cancelled {count} action(s)"))
+            }
+        }
+    }
+
+    // ========== Helper methods for internal use ==========
+
+    /// Grid length in beats for a `quantize` value.
+    async fn schedule_grid(&self, quantize: &str, beats_per_bar: f64) -> Result<f64, Error> {
+        if !quantize.trim().eq_ignore_ascii_case("global") {
+            return parse_grid(quantize, beats_per_bar);
+        }
+        self.global_grid(beats_per_bar).await?.ok_or_else(|| {
+            Error::InvalidParameter(
+                "Global quantization is off; give a grid such as 'bar' or '1/4'".to_string(),
+            )
+        })
+    }
+
+    /// Grid length in beats of the song's clip trigger quantization, or
+    /// `None` when it is off.
+    async fn global_grid(&self, beats_per_bar: f64) -> Result<Option<f64>, Error> {
+        let value: i32 = self
+            .osc
+            .query("/live/song/get/clip_trigger_quantization", vec![])
+            .await?;
+        Ok(trigger_quantization_grid(value, beats_per_bar))
+    }
+
+    /// Wait until the song reaches `beat`, waiting through stops.
+    ///
+    /// Timeouts are retried; only `MAX_POLL_TIMEOUTS` in a row give up.
+    async fn wait_for_song_beat(&self, beat: f64) -> Result<(), Error> {
+        let mut timeouts = 0;
+        loop {
+            let step = match self.song_wait_step(beat).await {
+                Ok(Some(step)) => {
+                    timeouts = 0;
+                    step
+                }
+                Ok(None) => return Ok(()),
+                Err(Error::Timeout) if timeouts < MAX_POLL_TIMEOUTS => {
+                    timeouts += 1;
+                    MAX_WAIT_STEP
+                }
+                Err(e) => return Err(e),
+            };
+            tokio::time::sleep(Duration::from_secs_f64(step)).await;
+        }
+    }
+
+    /// Seconds to sleep before checking the song position again, or `None`
+    /// once the song has reached `beat`.
+    async fn song_wait_step(&self, beat: f64) -> Result<Option<f64>, Error> {
+        let now: f32 = self
+            .osc
+            .query("/live/song/get/current_song_time", vec![])
+            .await?;
+        let remaining = beat - f64::from(now);
+        if remaining <= 0.0 {
+            return Ok(None);
+        }
+        let playing: bool = self.osc.query("/live/song/get/is_playing", vec![]).await?;
+        if !playing {
+            return Ok(Some(MAX_WAIT_STEP));
+        }
+        let tempo: f32 = self.osc.query("/live/song/get/tempo", vec![]).await?;
+        Ok(Some(
+            (remaining * 60.0 / f64::from(tempo)).clamp(0.005, MAX_WAIT_STEP),
+        ))
+    }
+}
+
+/// Text of a tool result, marked when the tool failed.
+fn tool_result_text(result: &CallToolResult) -> String {
+    let text: Vec<&str> = result
+        .content
+        .iter()
+        .filter_map(|content| content.as_text())
+        .map(|content| content.text.as_str())
+        .collect();
+    let text = text.join("\n");
+    if result.is_error.unwrap_or(false) {
+        format!("Error: {text}")
+    } else {
+        text
+    }
+}
@@ -10,7 +10,7 @@ use crate::types::{
     DeleteReturnTrackParams, JumpByParams, SetCurrentTimeParams, SetEnabledParams,
     SetGrooveAmountParams, SetLoopBeatsParams, SetLoopEnabledParams, SetQuantizationParams,
     SetRootNoteParams, SetScaleNameParams, SetSignatureDenominatorParams,
-    SetSignatureNumeratorParams, SongDetailedInfo, SongInfo, TrackParams,
+    SetSignatureNumeratorParams, SongDetailedInfo, SongInfo, TrackId, TrackParams,
 };
 
 #[tool_router(router = song_router, vis = "pub")]
@@ -573,7 +573,7 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<TrackParams>,
     ) -> Result<String, Error> {
-        let track = params.track;
+        let TrackId(track) = self.resolve_track(&params.track).await?;
         self.osc
             .send(
                 "/live/song/duplicate_track",
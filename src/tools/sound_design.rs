@@ -0,0 +1,182 @@
+//! Sound design tools: seeded device mutation and state morphing.
+
+use std::sync::Arc;
+
+use rmcp::handler::server::wrapper::Parameters;
+use rmcp::{tool, tool_router};
+use rosc::OscType;
+
+use crate::error::Error;
+use crate::modulation::{Modulation, Target};
+use crate::presets::{PresetLibrary, map_parameters};
+use crate::rng::Rng;
+use crate::server::AbletonServer;
+use crate::sound_design::{DeviceMutation, MorphStates, ParameterFilter, mutate_variations};
+use crate::types::{
+    DeviceId, DeviceState, MorphDeviceParams, MutateDeviceParams, ParameterStructure, Shape,
+    TrackId,
+};
+
+#[tool_router(router = sound_design_router, vis = "pub")]
+impl AbletonServer {
+    /// Randomly perturb a device's parameters.
+    #[tool(
+        description = "Mutate a device's parameters by a random amount (0-1) with a reproducible seed. Quantized parameters jump to valid steps; 'Device On' is left alone unless included. With count 1 the variation is applied; with a higher count (max 16) the device is left unchanged and every variation is returned. Returns the original values and each variation's seed, full values and changes"
+    )]
+    pub async fn mutate_device(
+        &self,
+        Parameters(params): Parameters<MutateDeviceParams>,
+    ) -> Result<String, Error> {
+        let TrackId(track) = self.resolve_track(&params.track).await?;
+        let DeviceId(device) = self.resolve_device(TrackId(track), &params.device).await?;
+        let parameters = self.query_device_parameters(track, device).await?;
+
+        let amount = params.amount.unwrap_or(0.2);
+        let filter = ParameterFilter {
+            include: params.include.unwrap_or_default(),
+            exclude: params.exclude.unwrap_or_default(),
+        };
+        let seed = params.seed.unwrap_or_else(Rng::seed_from_time);
+        let variations = mutate_variations(
+            &parameters,
+            amount,
+            &filter,
+            seed,
+            params.count.unwrap_or(1),
+        )?;
+
+        // Several variations are for auditioning: leave the device as it was
+        let applied = match variations.as_slice() {
+            [variation] => {
+                if !variation.changes.is_empty() {
+                    let mut args = vec![OscType::Int(track as i32), OscType::Int(device as i32)];
+                    args.extend(variation.values.iter().map(|v| OscType::Float(*v)));
+                    self.osc
+                        .send("/live/device/set/parameters/value", args)
+                        .await?;
+                }
+                Some(variation.seed)
+            }
+            _ => None,
+        };
+
+        let result = DeviceMutation {
+            amount,
+            original: parameters.iter().map(|p| p.value).collect(),
+            applied,
+            variations,
+        };
+        Ok(serde_json::to_string_pretty(&result).unwrap_or_else(|_| "{}".into()))
+    }
+
+    /// Morph a device between two parameter states.
+    #[tool(
+        description = "Morph every parameter of a device from state A to state B (preset names or value arrays), either immediately at a position (0-1) or over a duration in beats. Continuous parameters interpolate; quantized parameters switch at the midpoint"
+    )]
+    pub async fn morph_device(
+        &self,
+        Parameters(params): Parameters<MorphDeviceParams>,
+    ) -> Result<String, Error> {
+        match (params.position, params.duration_beats) {
+            (Some(position), None) if !(0.0..=1.0).contains(&position) => {
+                return Err(Error::InvalidParameter(format!(
+                    "position must be between 0 and 1, got {position}"
+                )));
+            }
+            (None, Some(duration)) if duration <= 0.0 => {
+                return Err(Error::InvalidParameter(
+                    "duration_beats must be greater than 0".to_string(),
+                ));
+            }
+            (Some(_), None) | (None, Some(_)) => {}
+            _ => {
+                return Err(Error::InvalidParameter(
+                    "Specify exactly one of position or duration_beats".to_string(),
+                ));
+            }
+        }
+
+        let TrackId(track) = self.resolve_track(&params.track).await?;
+        let DeviceId(device) = self.resolve_device(TrackId(track), &params.device).await?;
+        let parameters = self.query_device_parameters(track, device).await?;
+
+        let from = match &params.from {
+            Some(state) => {
+                self.device_state_values(track, device, &parameters, state)
+                    .await?
+            }
+            None => parameters.iter().map(|p| p.value).collect(),
+        };
+        let to = self
+            .device_state_values(track, device, &parameters, &params.to)
+            .await?;
+        let states = MorphStates::new(&parameters, &from, &to)?;
+        let changed = states.changed_count();
+
+        if let Some(position) = params.position {
+            let mut args = vec![OscType::Int(track as i32), OscType::Int(device as i32)];
+            args.extend(states.at(position).into_iter().map(OscType::Float));
+            self.osc
+                .send("/live/device/set/parameters/value", args)
+                .await?;
+            return Ok(format!(
+                "Morphed device {device} (track {track}) to position {position} ({changed} parameters differ between A and B)"
+            ));
+        }
+
+        let tempo: f32 = self.osc.query("/live/song/get/tempo", vec![]).await?;
+        let song_time: f32 = self
+            .osc
+            .query("/live/song/get/current_song_time", vec![])
+            .await?;
+        let modulation = Modulation {
+            target: Target::Morph {
+                track,
+                device,
+                states: Arc::new(states),
+            },
+            shape: Shape::Linear,
+            from: 0.0,
+            to: 1.0,
+            rate_beats: 1.0,
+            duration_beats: params.duration_beats,
+        };
+        let beats = modulation.duration_beats.unwrap_or_default();
+        let id = self
+            .modulations
+            .start(self.osc.clone(), modulation, tempo, f64::from(song_time));
+
+        Ok(format!(
+            "Started morph {id} on device {device} (track {track}): {changed} parameters over {beats} beats at {tempo} BPM"
+        ))
+    }
+
+    // ========== Helper methods for internal use ==========
+
+    /// Resolve a device state to one value per parameter.
+    ///
+    /// Presets must have been saved from the same device class.
+    async fn device_state_values(
+        &self,
+        track: u32,
+        device: u32,
+        parameters: &[ParameterStructure],
+        state: &DeviceState,
+    ) -> Result<Vec<f32>, Error> {
+        match state {
+            DeviceState::Values(values) => Ok(values.clone()),
+            DeviceState::Preset(name) => {
+                let (_, class_name) = self.query_device_identity(track, device).await?;
+                let library = PresetLibrary::open_default()?;
+                let preset = library.load(&library.find(&class_name, name)?.path)?;
+                if preset.class_name != class_name {
+                    return Err(Error::InvalidParameter(format!(
+                        "Preset \"{}\" was saved from a {} device, but device {device} on track {track} is a {class_name}",
+                        preset.name, preset.class_name
+                    )));
+                }
+                Ok(map_parameters(&preset, parameters).values)
+            }
+        }
+    }
+}
@@ -0,0 +1,206 @@
+//! Track template tools: create fully configured tracks from a recipe.
+//!
+//! A recipe runs as one operation on the new track: create it, load the
+//! instrument and effects by browser path (selecting the new track before
+//! each load, so nothing lands on another track), apply device presets, then
+//! set name, color, volume, pan, sends and routing. Later steps still run
+//! when a device fails to load; the report lists what failed.
+
+use rmcp::handler::server::wrapper::Parameters;
+use rmcp::{tool, tool_router};
+use rosc::OscType;
+
+use crate::error::Error;
+use crate::server::AbletonServer;
+use crate::templates::TemplateLibrary;
+use crate::types::{
+    CreateTrackFromTemplateParams, MixerChange, SendChange, TemplateRouting, TrackKind, TrackRef,
+    TrackType,
+};
+
+#[tool_router(router = templates_router, vis = "pub")]
+impl AbletonServer {
+    /// Create a configured track from a saved template or inline recipe.
+    #[tool(
+        description = "Create a fully configured track in one step from a saved template or an inline recipe: track type, name, color, instrument and effect chain by browser path, device presets, volume, pan, sends and input/output routing. Reports the new track and device indices, and any step that failed"
+    )]
+    pub async fn create_track_from_template(
+        &self,
+        Parameters(params): Parameters<CreateTrackFromTemplateParams>,
+    ) -> Result<String, Error> {
+        let library = TemplateLibrary::open_default()?;
+        let (label, template) = match (params.template, params.recipe) {
+            (Some(name), None) => {
+                let (summary, template) = library.find(&name)?;
+                (format!(" from template \"{}\"", summary.name), template)
+            }
+            (None, Some(recipe)) => (String::new(), recipe),
+            _ => {
+                return Err(Error::InvalidParameter(
+                    "Give either a template name or a recipe".to_string(),
+                ));
+            }
+        };
+        template.validate()?;
+        // Check browser paths before creating anything
+        let mut devices = Vec::new();
+        for device in template.instrument.iter().chain(&template.effects) {
+            let (_, addr, args) = self.item_loader(&device.path)?;
+            devices.push((device, addr, args));
+        }
+
+        let mut lines = Vec::new();
+        if let Some(name) = &params.save_as {
+            let path = library.save(name, &template, params.overwrite.unwrap_or(false))?;
+            lines.push(format!("Saved template \"{name}\" to {}", path.display()));
+        }
+
+        let count: i32 = self.osc.query("/live/song/get/num_tracks", vec![]).await?;
+        let track = match params.index {
+            Some(index) if !(0..=count).contains(&index) => {
+                return Err(Error::InvalidParameter(format!(
+                    "Track index {index} is out of range (the set has {count} tracks)"
+                )));
+            }
+            Some(index) => index,
+            None => count,
+        };
+        let (addr, kind) = match template.track_type {
+            TrackType::Midi => ("/live/song/create_midi_track", "MIDI"),
+            TrackType::Audio => ("/live/song/create_audio_track", "audio"),
+        };
+        self.osc.send(addr, vec![OscType::Int(track)]).await?;
+        self.wait_for_track_count(count + 1).await?;
+        let track = track as u32;
+
+        let name = params.name.or_else(|| template.name.clone());
+        lines.insert(
+            0,
+            match &name {
+                Some(name) => format!("Created {kind} track {track} \"{name}\"{label}"),
+                None => format!("Created {kind} track {track}{label}"),
+            },
+        );
+
+        let mut failed = 0;
+        for (device, addr, args) in devices {
+            let loaded = self
+                .load_onto_track(addr, args, &device.path, track, None)
+                .await;
+            let (index, device_name) = match loaded {
+                Ok(loaded) => loaded,
+                Err(e) => {
+                    failed += 1;
+                    lines.push(format!("- failed to load {}: {e}", device.path));
+                    continue;
+                }
+            };
+            lines.push(format!("- device {index}: {device_name}"));
+            let Some(preset) = &device.preset else {
+                continue;
+            };
+            match self.apply_device_preset(track, index, preset, false).await {
+                Ok(report) => lines.extend(report.lines().map(|line| format!("  {line}"))),
+                Err(e) => {
+                    failed += 1;
+                    lines.push(format!("  failed to apply preset \"{preset}\": {e}"));
+                }
+            }
+        }
+
+        let change = MixerChange {
+            track: TrackRef::Index(track),
+            kind: TrackKind::Regular,
+            volume: template.volume.clone(),
+            pan: template.pan.clone(),
+            sends: Some(
+                template
+                    .sends
+                    .iter()
+                    .map(|s| SendChange {
+                        send: s.send.clone(),
+                        level: s.level,
+                    })
+                    .collect(),
+            ),
+            mute: None,
+            solo: None,
+            arm: None,
+            color: template.color,
+            name,
+        };
+        let has_mixer = change.volume.is_some()
+            || change.pan.is_some()
+            || !template.sends.is_empty()
+            || change.color.is_some()
+            || change.name.is_some();
+        if has_mixer {
+            match self.apply_mixer_change(&change).await {
+                Ok(applied) => lines.extend(applied.into_iter().map(|a| format!("- {a}"))),
+                Err(e) => {
+                    failed += 1;
+                    lines.push(format!("- failed to apply mixer settings: {e}"));
+                }
+            }
+        }
+
+        for (direction, routing) in [("input", &template.input), ("output", &template.output)] {
+            let Some(routing) = routing else {
+                continue;
+            };
+            match self.set_template_routing(track, direction, routing).await {
+                Ok(set) => lines.extend(set),
+                Err(e) => {
+                    failed += 1;
+                    lines.push(format!("- failed to set {direction} routing: {e}"));
+                }
+            }
+        }
+
+        if failed > 0 {
+            lines.push(format!("{failed} step(s) failed; the track was kept"));
+        }
+        Ok(lines.join("\n"))
+    }
+
+    /// List saved track templates.
+    #[tool(
+        description = "List saved track templates (TOML or JSON files in the track_templates config directory) with their type, instrument and effects"
+    )]
+    pub async fn list_track_templates(&self) -> Result<String, Error> {
+        let library = TemplateLibrary::open_default()?;
+        let templates = library.list()?;
+        Ok(serde_json::to_string_pretty(&templates).unwrap_or_else(|_| "[]".into()))
+    }
+
+    // ========== Helper methods for internal use ==========
+
+    /// Set a track's input or output routing type and channel.
+    async fn set_template_routing(
+        &self,
+        track: u32,
+        direction: &str,
+        routing: &TemplateRouting,
+    ) -> Result<Vec<String>, Error> {
+        let mut lines = Vec::new();
+        for (property, value) in [
+            ("routing_type", &routing.routing_type),
+            ("routing_channel", &routing.channel),
+        ] {
+            let Some(value) = value else {
+                continue;
+            };
+            self.osc
+                .send(
+                    &format!("/live/track/set/{direction}_{property}"),
+                    vec![OscType::Int(track as i32), OscType::String(value.clone())],
+                )
+                .await?;
+            lines.push(format!(
+                "- {direction} {} {value}",
+                property.replace('_', " ")
+            ));
+        }
+        Ok(lines)
+    }
+}
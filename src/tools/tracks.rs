@@ -1,4 +1,11 @@
 //! Track control tools.
+//!
+//! The group hierarchy comes from each track's `is_foldable` flag and, for
+//! grouped tracks, `/live/track/get/group_track`, which names the enclosing
+//! group track directly, so nested groups need no guessing from track order.
+//!
+//! `AbletonOSC` has no endpoint for grouping or ungrouping tracks, so groups
+//! can only be read here; create them in Live.
 
 use rmcp::handler::server::wrapper::Parameters;
 use rmcp::{tool, tool_router};
@@ -6,15 +13,55 @@ use rosc::OscPacket;
 use rosc::OscType;
 
 use crate::error::Error;
+use crate::fader::{format_db, format_pan, volume_db, volume_to_db};
 use crate::server::AbletonServer;
+use crate::tools::devices::{POLL_ATTEMPTS, POLL_INTERVAL};
 use crate::types::{
     ArmTrackParams, ArrangementClipInfo, ClipSlotParams, CreateTrackParams, GetTrackSendParams,
     MuteTrackParams, RoutingOptions, SetTrackColorParams, SetTrackFoldStateParams,
     SetTrackMonitoringParams, SetTrackNameParams, SetTrackPanParams, SetTrackRoutingChannelParams,
     SetTrackRoutingTypeParams, SetTrackSendParams, SetTrackVolumeParams, SoloTrackParams,
-    TrackCapabilities, TrackInfo, TrackParams,
+    TrackCapabilities, TrackId, TrackInfo, TrackNode, TrackParams,
 };
 
+/// A track's place in the group hierarchy.
+struct GroupEntry {
+    name: String,
+    is_group: bool,
+    /// Index of the enclosing group track.
+    parent: Option<u32>,
+}
+
+/// Nest tracks under their groups; `entries` are in track order.
+fn track_tree(entries: &[GroupEntry], parent: Option<u32>) -> Vec<TrackNode> {
+    entries
+        .iter()
+        .enumerate()
+        .filter(|(_, entry)| entry.parent == parent)
+        .map(|(index, entry)| TrackNode {
+            index: index as u32,
+            name: entry.name.clone(),
+            is_group: entry.is_group,
+            children: if entry.is_group {
+                track_tree(entries, Some(index as u32))
+            } else {
+                Vec::new()
+            },
+        })
+        .collect()
+}
+
+/// Group track index from a `/live/track/get/group_track` reply.
+///
+/// The reply echoes the track index first; an ungrouped track has no group
+/// index after it, or a negative one.
+fn group_track_index(reply: &[OscType]) -> Option<u32> {
+    match reply.get(1) {
+        Some(OscType::Int(group)) => u32::try_from(*group).ok(),
+        _ => None,
+    }
+}
+
 #[tool_router(router = tracks_router, vis = "pub")]
 impl AbletonServer {
     /// Get list of all tracks.
@@ -71,7 +118,9 @@ impl AbletonServer {
                 muted,
                 soloed,
                 volume,
+                volume_db: volume_db(volume),
                 pan,
+                pan_label: format_pan(pan),
             });
         }
 
@@ -84,13 +133,14 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<TrackParams>,
     ) -> Result<String, Error> {
-        let args = vec![OscType::Int(params.track as i32)];
+        let TrackId(track) = self.resolve_track(&params.track).await?;
+        let args = vec![OscType::Int(track as i32)];
 
         let name: String = self
             .osc
             .query("/live/track/get/name", args.clone())
             .await
-            .unwrap_or_else(|_| format!("Track {}", params.track + 1));
+            .unwrap_or_else(|_| format!("Track {}", track + 1));
 
         let armed: bool = self
             .osc
@@ -123,65 +173,53 @@ impl AbletonServer {
             .unwrap_or(0.0);
 
         let track = TrackInfo {
-            index: params.track,
+            index: track,
             name,
             armed,
             muted,
             soloed,
             volume,
+            volume_db: volume_db(volume),
             pan,
+            pan_label: format_pan(pan),
         };
 
         Ok(serde_json::to_string_pretty(&track).unwrap_or_else(|_| format!("{track:?}")))
     }
 
     /// Set track volume.
-    #[tool(description = "Set track volume (0.0 to 1.0)")]
+    #[tool(
+        description = "Set track volume as a fader position (0.0 to 1.0, 0.85 = 0 dB) or in dB, e.g. \"-6 dB\""
+    )]
     pub async fn set_track_volume(
         &self,
         Parameters(params): Parameters<SetTrackVolumeParams>,
     ) -> Result<String, Error> {
-        if !(0.0..=1.0).contains(&params.volume) {
-            return Err(Error::InvalidParameter(
-                "Volume must be between 0.0 and 1.0".to_string(),
-            ));
-        }
-        self.osc
-            .send(
-                "/live/track/set/volume",
-                vec![
-                    OscType::Int(params.track as i32),
-                    OscType::Float(params.volume),
-                ],
-            )
-            .await?;
+        let volume = params.volume.to_fader()?;
+        let track = self.resolve_track_of(params.kind, &params.track).await?;
+        let (addr, mut args) = track.track_message("set/volume");
+        args.push(OscType::Float(volume));
+        self.osc.send(&addr, args).await?;
         Ok(format!(
-            "Track {} volume set to {}",
-            params.track, params.volume
+            "Set {track} volume to {volume:.3} ({})",
+            format_db(volume_to_db(volume))
         ))
     }
 
     /// Set track panning.
-    #[tool(description = "Set track pan position (-1.0 left to 1.0 right)")]
+    #[tool(
+        description = "Set track pan position (-1.0 left to 1.0 right) or as Live shows it, e.g. \"25L\", \"C\", \"50R\""
+    )]
     pub async fn set_track_pan(
         &self,
         Parameters(params): Parameters<SetTrackPanParams>,
     ) -> Result<String, Error> {
-        if !(-1.0..=1.0).contains(&params.pan) {
-            return Err(Error::InvalidParameter(
-                "Pan must be between -1.0 and 1.0".to_string(),
-            ));
-        }
-        self.osc
-            .send(
-                "/live/track/set/panning",
-                vec![
-                    OscType::Int(params.track as i32),
-                    OscType::Float(params.pan),
-                ],
-            )
-            .await?;
-        Ok(format!("Track {} pan set to {}", params.track, params.pan))
+        let pan = params.pan.to_value()?;
+        let track = self.resolve_track_of(params.kind, &params.track).await?;
+        let (addr, mut args) = track.track_message("set/panning");
+        args.push(OscType::Float(pan));
+        self.osc.send(&addr, args).await?;
+        Ok(format!("Set {track} pan to {pan} ({})", format_pan(pan)))
     }
 
     /// Mute or unmute a track.
@@ -190,19 +228,13 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<MuteTrackParams>,
     ) -> Result<String, Error> {
-        self.osc
-            .send(
-                "/live/track/set/mute",
-                vec![
-                    OscType::Int(params.track as i32),
-                    OscType::Int(if params.mute { 1 } else { 0 }),
-                ],
-            )
-            .await?;
+        let track = self.resolve_track_of(params.kind, &params.track).await?;
+        let (addr, mut args) = track.track_message("set/mute");
+        args.push(OscType::Int(i32::from(params.mute)));
+        self.osc.send(&addr, args).await?;
         Ok(format!(
-            "Track {} {}",
-            params.track,
-            if params.mute { "muted" } else { "unmuted" }
+            "{} {track}",
+            if params.mute { "Muted" } else { "Unmuted" }
         ))
     }
 
@@ -212,19 +244,13 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<SoloTrackParams>,
     ) -> Result<String, Error> {
-        self.osc
-            .send(
-                "/live/track/set/solo",
-                vec![
-                    OscType::Int(params.track as i32),
-                    OscType::Int(if params.solo { 1 } else { 0 }),
-                ],
-            )
-            .await?;
+        let track = self.resolve_track_of(params.kind, &params.track).await?;
+        let (addr, mut args) = track.track_message("set/solo");
+        args.push(OscType::Int(i32::from(params.solo)));
+        self.osc.send(&addr, args).await?;
         Ok(format!(
-            "Track {} {}",
-            params.track,
-            if params.solo { "soloed" } else { "unsoloed" }
+            "{} {track}",
+            if params.solo { "Soloed" } else { "Unsoloed" }
         ))
     }
 
@@ -234,18 +260,19 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<ArmTrackParams>,
     ) -> Result<String, Error> {
+        let TrackId(track) = self.resolve_track(&params.track).await?;
         self.osc
             .send(
                 "/live/track/set/arm",
                 vec![
-                    OscType::Int(params.track as i32),
+                    OscType::Int(track as i32),
                     OscType::Int(if params.arm { 1 } else { 0 }),
                 ],
             )
             .await?;
         Ok(format!(
             "Track {} {}",
-            params.track,
+            track,
             if params.arm { "armed" } else { "disarmed" }
         ))
     }
@@ -290,13 +317,11 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<TrackParams>,
     ) -> Result<String, Error> {
+        let TrackId(track) = self.resolve_track(&params.track).await?;
         self.osc
-            .send(
-                "/live/song/delete_track",
-                vec![OscType::Int(params.track as i32)],
-            )
+            .send("/live/song/delete_track", vec![OscType::Int(track as i32)])
             .await?;
-        Ok(format!("Deleted track {}", params.track))
+        Ok(format!("Deleted track {track}"))
     }
 
     /// Set track name.
@@ -305,19 +330,11 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<SetTrackNameParams>,
     ) -> Result<String, Error> {
-        self.osc
-            .send(
-                "/live/track/set/name",
-                vec![
-                    OscType::Int(params.track as i32),
-                    OscType::String(params.name.clone()),
-                ],
-            )
-            .await?;
-        Ok(format!(
-            "Track {} renamed to \"{}\"",
-            params.track, params.name
-        ))
+        let track = self.resolve_track_of(params.kind, &params.track).await?;
+        let (addr, mut args) = track.track_message("set/name");
+        args.push(OscType::String(params.name.clone()));
+        self.osc.send(&addr, args).await?;
+        Ok(format!("Renamed {track} to \"{}\"", params.name))
     }
 
     /// Get track send level.
@@ -326,19 +343,17 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<GetTrackSendParams>,
     ) -> Result<String, Error> {
+        let TrackId(track) = self.resolve_track(&params.track).await?;
         let level: f32 = self
             .osc
             .query(
                 "/live/track/get/send",
-                vec![
-                    OscType::Int(params.track as i32),
-                    OscType::Int(params.send as i32),
-                ],
+                vec![OscType::Int(track as i32), OscType::Int(params.send as i32)],
             )
             .await?;
         Ok(format!(
             "Track {} send {} level: {level}",
-            params.track, params.send
+            track, params.send
         ))
     }
 
@@ -353,11 +368,12 @@ impl AbletonServer {
                 "Send level must be between 0.0 and 1.0".to_string(),
             ));
         }
+        let TrackId(track) = self.resolve_track(&params.track).await?;
         self.osc
             .send(
                 "/live/track/set/send",
                 vec![
-                    OscType::Int(params.track as i32),
+                    OscType::Int(track as i32),
                     OscType::Int(params.send as i32),
                     OscType::Float(params.level),
                 ],
@@ -365,7 +381,7 @@ impl AbletonServer {
             .await?;
         Ok(format!(
             "Track {} send {} set to {}",
-            params.track, params.send, params.level
+            track, params.send, params.level
         ))
     }
 
@@ -375,14 +391,12 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<TrackParams>,
     ) -> Result<String, Error> {
+        let TrackId(track) = self.resolve_track(&params.track).await?;
         let color: i32 = self
             .osc
-            .query(
-                "/live/track/get/color",
-                vec![OscType::Int(params.track as i32)],
-            )
+            .query("/live/track/get/color", vec![OscType::Int(track as i32)])
             .await?;
-        Ok(format!("Track {} color: {color}", params.track))
+        Ok(format!("Track {track} color: {color}"))
     }
 
     /// Set track color.
@@ -391,19 +405,14 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<SetTrackColorParams>,
     ) -> Result<String, Error> {
+        let TrackId(track) = self.resolve_track(&params.track).await?;
         self.osc
             .send(
                 "/live/track/set/color",
-                vec![
-                    OscType::Int(params.track as i32),
-                    OscType::Int(params.color),
-                ],
+                vec![OscType::Int(track as i32), OscType::Int(params.color)],
             )
             .await?;
-        Ok(format!(
-            "Track {} color set to {}",
-            params.track, params.color
-        ))
+        Ok(format!("Track {} color set to {}", track, params.color))
     }
 
     /// Get track monitoring state.
@@ -412,11 +421,12 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<TrackParams>,
     ) -> Result<String, Error> {
+        let TrackId(track) = self.resolve_track(&params.track).await?;
         let state: i32 = self
             .osc
             .query(
                 "/live/track/get/current_monitoring_state",
-                vec![OscType::Int(params.track as i32)],
+                vec![OscType::Int(track as i32)],
             )
             .await?;
         let state_name = match state {
@@ -425,10 +435,7 @@ impl AbletonServer {
             2 => "Off",
             _ => "Unknown",
         };
-        Ok(format!(
-            "Track {} monitoring: {state_name} ({state})",
-            params.track
-        ))
+        Ok(format!("Track {track} monitoring: {state_name} ({state})"))
     }
 
     /// Set track monitoring state.
@@ -442,13 +449,11 @@ impl AbletonServer {
                 "Monitoring state must be 0 (In), 1 (Auto), or 2 (Off)".to_string(),
             ));
         }
+        let TrackId(track) = self.resolve_track(&params.track).await?;
         self.osc
             .send(
                 "/live/track/set/current_monitoring_state",
-                vec![
-                    OscType::Int(params.track as i32),
-                    OscType::Int(params.state),
-                ],
+                vec![OscType::Int(track as i32), OscType::Int(params.state)],
             )
             .await?;
         let state_name = match params.state {
@@ -457,10 +462,7 @@ impl AbletonServer {
             2 => "Off",
             _ => "Unknown",
         };
-        Ok(format!(
-            "Track {} monitoring set to {state_name}",
-            params.track
-        ))
+        Ok(format!("Track {track} monitoring set to {state_name}"))
     }
 
     /// Get track output meter level.
@@ -469,14 +471,15 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<TrackParams>,
     ) -> Result<String, Error> {
+        let TrackId(track) = self.resolve_track(&params.track).await?;
         let level: f32 = self
             .osc
             .query(
                 "/live/track/get/output_meter_level",
-                vec![OscType::Int(params.track as i32)],
+                vec![OscType::Int(track as i32)],
             )
             .await?;
-        Ok(format!("Track {} output meter: {level}", params.track))
+        Ok(format!("Track {track} output meter: {level}"))
     }
 
     /// Get track output meter left channel.
@@ -485,17 +488,15 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<TrackParams>,
     ) -> Result<String, Error> {
+        let TrackId(track) = self.resolve_track(&params.track).await?;
         let level: f32 = self
             .osc
             .query(
                 "/live/track/get/output_meter_left",
-                vec![OscType::Int(params.track as i32)],
+                vec![OscType::Int(track as i32)],
             )
             .await?;
-        Ok(format!(
-            "Track {} output meter (left): {level}",
-            params.track
-        ))
+        Ok(format!("Track {track} output meter (left): {level}"))
     }
 
     /// Get track output meter right channel.
@@ -504,17 +505,15 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<TrackParams>,
     ) -> Result<String, Error> {
+        let TrackId(track) = self.resolve_track(&params.track).await?;
         let level: f32 = self
             .osc
             .query(
                 "/live/track/get/output_meter_right",
-                vec![OscType::Int(params.track as i32)],
+                vec![OscType::Int(track as i32)],
             )
             .await?;
-        Ok(format!(
-            "Track {} output meter (right): {level}",
-            params.track
-        ))
+        Ok(format!("Track {track} output meter (right): {level}"))
     }
 
     /// Get track fold state.
@@ -523,17 +522,18 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<TrackParams>,
     ) -> Result<String, Error> {
+        let TrackId(track) = self.resolve_track(&params.track).await?;
         let result: i32 = self
             .osc
             .query(
                 "/live/track/get/fold_state",
-                vec![OscType::Int(params.track as i32)],
+                vec![OscType::Int(track as i32)],
             )
             .await?;
         let folded = result != 0;
         Ok(format!(
             "Track {} is {}",
-            params.track,
+            track,
             if folded { "folded" } else { "unfolded" }
         ))
     }
@@ -544,18 +544,19 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<SetTrackFoldStateParams>,
     ) -> Result<String, Error> {
+        let TrackId(track) = self.resolve_track(&params.track).await?;
         self.osc
             .send(
                 "/live/track/set/fold_state",
                 vec![
-                    OscType::Int(params.track as i32),
+                    OscType::Int(track as i32),
                     OscType::Int(if params.folded { 1 } else { 0 }),
                 ],
             )
             .await?;
         Ok(format!(
             "Track {} {}",
-            params.track,
+            track,
             if params.folded { "folded" } else { "unfolded" }
         ))
     }
@@ -566,17 +567,18 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<TrackParams>,
     ) -> Result<String, Error> {
+        let TrackId(track) = self.resolve_track(&params.track).await?;
         let result: i32 = self
             .osc
             .query(
                 "/live/track/get/is_foldable",
-                vec![OscType::Int(params.track as i32)],
+                vec![OscType::Int(track as i32)],
             )
             .await?;
         let foldable = result != 0;
         Ok(format!(
             "Track {} is {}foldable",
-            params.track,
+            track,
             if foldable { "" } else { "not " }
         ))
     }
@@ -587,34 +589,60 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<TrackParams>,
     ) -> Result<String, Error> {
+        let TrackId(track) = self.resolve_track(&params.track).await?;
         let result: i32 = self
             .osc
             .query(
                 "/live/track/get/is_grouped",
-                vec![OscType::Int(params.track as i32)],
+                vec![OscType::Int(track as i32)],
             )
             .await?;
         let grouped = result != 0;
         Ok(format!(
             "Track {} is {}grouped",
-            params.track,
+            track,
             if grouped { "" } else { "not " }
         ))
     }
 
+    /// Get the nested group structure.
+    #[tool(
+        description = "Get all tracks as a tree: group tracks list the tracks inside them, with track indices"
+    )]
+    pub async fn get_track_tree(&self) -> Result<String, Error> {
+        let (is_group, parents) = self.track_groups().await?;
+        let mut entries = Vec::with_capacity(parents.len());
+        for (i, (is_group, parent)) in is_group.into_iter().zip(parents).enumerate() {
+            let name: String = self
+                .osc
+                .query("/live/track/get/name", vec![OscType::Int(i as i32)])
+                .await
+                .unwrap_or_else(|_| format!("Track {}", i + 1));
+            entries.push(GroupEntry {
+                name,
+                is_group,
+                parent,
+            });
+        }
+
+        let tree = track_tree(&entries, None);
+        Ok(serde_json::to_string_pretty(&tree).unwrap_or_else(|_| "[]".into()))
+    }
+
     /// Stop all clips on a track.
     #[tool(description = "Stop all clips on a track")]
     pub async fn stop_track_clips(
         &self,
         Parameters(params): Parameters<TrackParams>,
     ) -> Result<String, Error> {
+        let TrackId(track) = self.resolve_track(&params.track).await?;
         self.osc
             .send(
                 "/live/track/stop_all_clips",
-                vec![OscType::Int(params.track as i32)],
+                vec![OscType::Int(track as i32)],
             )
             .await?;
-        Ok(format!("Stopped all clips on track {}", params.track))
+        Ok(format!("Stopped all clips on track {track}"))
     }
 
     /// Get the currently playing clip slot index.
@@ -623,17 +651,18 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<TrackParams>,
     ) -> Result<String, Error> {
+        let TrackId(track) = self.resolve_track(&params.track).await?;
         let slot: i32 = self
             .osc
             .query(
                 "/live/track/get/playing_slot_index",
-                vec![OscType::Int(params.track as i32)],
+                vec![OscType::Int(track as i32)],
             )
             .await?;
         if slot < 0 {
-            Ok(format!("Track {} has no playing clip", params.track))
+            Ok(format!("Track {track} has no playing clip"))
         } else {
-            Ok(format!("Track {} playing slot: {slot}", params.track))
+            Ok(format!("Track {track} playing slot: {slot}"))
         }
     }
 
@@ -643,17 +672,18 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<TrackParams>,
     ) -> Result<String, Error> {
+        let TrackId(track) = self.resolve_track(&params.track).await?;
         let slot: i32 = self
             .osc
             .query(
                 "/live/track/get/fired_slot_index",
-                vec![OscType::Int(params.track as i32)],
+                vec![OscType::Int(track as i32)],
             )
             .await?;
         if slot < 0 {
-            Ok(format!("Track {} has no fired clip", params.track))
+            Ok(format!("Track {track} has no fired clip"))
         } else {
-            Ok(format!("Track {} fired slot: {slot}", params.track))
+            Ok(format!("Track {track} fired slot: {slot}"))
         }
     }
 
@@ -663,11 +693,9 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<TrackParams>,
     ) -> Result<String, Error> {
-        let routing = self.query_track_input_routing_type(params.track).await?;
-        Ok(format!(
-            "Track {} input routing type: {routing}",
-            params.track
-        ))
+        let TrackId(track) = self.resolve_track(&params.track).await?;
+        let routing = self.query_track_input_routing_type(track).await?;
+        Ok(format!("Track {track} input routing type: {routing}"))
     }
 
     /// Set track input routing type.
@@ -676,18 +704,19 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<SetTrackRoutingTypeParams>,
     ) -> Result<String, Error> {
+        let TrackId(track) = self.resolve_track(&params.track).await?;
         self.osc
             .send(
                 "/live/track/set/input_routing_type",
                 vec![
-                    OscType::Int(params.track as i32),
+                    OscType::Int(track as i32),
                     OscType::String(params.routing_type.clone()),
                 ],
             )
             .await?;
         Ok(format!(
             "Track {} input routing type set to {}",
-            params.track, params.routing_type
+            track, params.routing_type
         ))
     }
 
@@ -697,11 +726,9 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<TrackParams>,
     ) -> Result<String, Error> {
-        let channel = self.query_track_input_routing_channel(params.track).await?;
-        Ok(format!(
-            "Track {} input routing channel: {channel}",
-            params.track
-        ))
+        let TrackId(track) = self.resolve_track(&params.track).await?;
+        let channel = self.query_track_input_routing_channel(track).await?;
+        Ok(format!("Track {track} input routing channel: {channel}"))
     }
 
     /// Set track input routing channel.
@@ -710,18 +737,19 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<SetTrackRoutingChannelParams>,
     ) -> Result<String, Error> {
+        let TrackId(track) = self.resolve_track(&params.track).await?;
         self.osc
             .send(
                 "/live/track/set/input_routing_channel",
                 vec![
-                    OscType::Int(params.track as i32),
+                    OscType::Int(track as i32),
                     OscType::String(params.channel.clone()),
                 ],
             )
             .await?;
         Ok(format!(
             "Track {} input routing channel set to {}",
-            params.track, params.channel
+            track, params.channel
         ))
     }
 
@@ -731,11 +759,9 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<TrackParams>,
     ) -> Result<String, Error> {
-        let routing = self.query_track_output_routing_type(params.track).await?;
-        Ok(format!(
-            "Track {} output routing type: {routing}",
-            params.track
-        ))
+        let TrackId(track) = self.resolve_track(&params.track).await?;
+        let routing = self.query_track_output_routing_type(track).await?;
+        Ok(format!("Track {track} output routing type: {routing}"))
     }
 
     /// Set track output routing type.
@@ -744,18 +770,19 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<SetTrackRoutingTypeParams>,
     ) -> Result<String, Error> {
+        let TrackId(track) = self.resolve_track(&params.track).await?;
         self.osc
             .send(
                 "/live/track/set/output_routing_type",
                 vec![
-                    OscType::Int(params.track as i32),
+                    OscType::Int(track as i32),
                     OscType::String(params.routing_type.clone()),
                 ],
             )
             .await?;
         Ok(format!(
             "Track {} output routing type set to {}",
-            params.track, params.routing_type
+            track, params.routing_type
         ))
     }
 
@@ -765,13 +792,9 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<TrackParams>,
     ) -> Result<String, Error> {
-        let channel = self
-            .query_track_output_routing_channel(params.track)
-            .await?;
-        Ok(format!(
-            "Track {} output routing channel: {channel}",
-            params.track
-        ))
+        let TrackId(track) = self.resolve_track(&params.track).await?;
+        let channel = self.query_track_output_routing_channel(track).await?;
+        Ok(format!("Track {track} output routing channel: {channel}"))
     }
 
     /// Set track output routing channel.
@@ -780,18 +803,19 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<SetTrackRoutingChannelParams>,
     ) -> Result<String, Error> {
+        let TrackId(track) = self.resolve_track(&params.track).await?;
         self.osc
             .send(
                 "/live/track/set/output_routing_channel",
                 vec![
-                    OscType::Int(params.track as i32),
+                    OscType::Int(track as i32),
                     OscType::String(params.channel.clone()),
                 ],
             )
             .await?;
         Ok(format!(
             "Track {} output routing channel set to {}",
-            params.track, params.channel
+            track, params.channel
         ))
     }
 
@@ -801,7 +825,8 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<TrackParams>,
     ) -> Result<String, Error> {
-        let args = vec![OscType::Int(params.track as i32)];
+        let TrackId(track) = self.resolve_track(&params.track).await?;
+        let args = vec![OscType::Int(track as i32)];
 
         let can_be_armed: bool = self
             .osc
@@ -879,17 +904,18 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<TrackParams>,
     ) -> Result<String, Error> {
+        let TrackId(track) = self.resolve_track(&params.track).await?;
         let result: i32 = self
             .osc
             .query(
                 "/live/track/get/can_be_armed",
-                vec![OscType::Int(params.track as i32)],
+                vec![OscType::Int(track as i32)],
             )
             .await?;
         let can_arm = result != 0;
         Ok(format!(
             "Track {} {}be armed",
-            params.track,
+            track,
             if can_arm { "can " } else { "cannot " }
         ))
     }
@@ -900,17 +926,18 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<TrackParams>,
     ) -> Result<String, Error> {
+        let TrackId(track) = self.resolve_track(&params.track).await?;
         let result: i32 = self
             .osc
             .query(
                 "/live/track/get/has_audio_input",
-                vec![OscType::Int(params.track as i32)],
+                vec![OscType::Int(track as i32)],
             )
             .await?;
         let has = result != 0;
         Ok(format!(
             "Track {} {}have audio input",
-            params.track,
+            track,
             if has { "does " } else { "does not " }
         ))
     }
@@ -921,17 +948,18 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<TrackParams>,
     ) -> Result<String, Error> {
+        let TrackId(track) = self.resolve_track(&params.track).await?;
         let result: i32 = self
             .osc
             .query(
                 "/live/track/get/has_audio_output",
-                vec![OscType::Int(params.track as i32)],
+                vec![OscType::Int(track as i32)],
             )
             .await?;
         let has = result != 0;
         Ok(format!(
             "Track {} {}have audio output",
-            params.track,
+            track,
             if has { "does " } else { "does not " }
         ))
     }
@@ -942,17 +970,18 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<TrackParams>,
     ) -> Result<String, Error> {
+        let TrackId(track) = self.resolve_track(&params.track).await?;
         let result: i32 = self
             .osc
             .query(
                 "/live/track/get/has_midi_input",
-                vec![OscType::Int(params.track as i32)],
+                vec![OscType::Int(track as i32)],
             )
             .await?;
         let has = result != 0;
         Ok(format!(
             "Track {} {}have MIDI input",
-            params.track,
+            track,
             if has { "does " } else { "does not " }
         ))
     }
@@ -963,17 +992,18 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<TrackParams>,
     ) -> Result<String, Error> {
+        let TrackId(track) = self.resolve_track(&params.track).await?;
         let result: i32 = self
             .osc
             .query(
                 "/live/track/get/has_midi_output",
-                vec![OscType::Int(params.track as i32)],
+                vec![OscType::Int(track as i32)],
             )
             .await?;
         let has = result != 0;
         Ok(format!(
             "Track {} {}have MIDI output",
-            params.track,
+            track,
             if has { "does " } else { "does not " }
         ))
     }
@@ -984,17 +1014,18 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<TrackParams>,
     ) -> Result<String, Error> {
+        let TrackId(track) = self.resolve_track(&params.track).await?;
         let result: i32 = self
             .osc
             .query(
                 "/live/track/get/is_visible",
-                vec![OscType::Int(params.track as i32)],
+                vec![OscType::Int(track as i32)],
             )
             .await?;
         let visible = result != 0;
         Ok(format!(
             "Track {} is {}visible",
-            params.track,
+            track,
             if visible { "" } else { "not " }
         ))
     }
@@ -1005,9 +1036,8 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<TrackParams>,
     ) -> Result<String, Error> {
-        let types = self
-            .query_available_input_routing_types(params.track)
-            .await?;
+        let TrackId(track) = self.resolve_track(&params.track).await?;
+        let types = self.query_available_input_routing_types(track).await?;
         Ok(serde_json::to_string_pretty(&types).unwrap_or_else(|_| format!("{types:?}")))
     }
 
@@ -1017,9 +1047,8 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<TrackParams>,
     ) -> Result<String, Error> {
-        let channels = self
-            .query_available_input_routing_channels(params.track)
-            .await?;
+        let TrackId(track) = self.resolve_track(&params.track).await?;
+        let channels = self.query_available_input_routing_channels(track).await?;
         Ok(serde_json::to_string_pretty(&channels).unwrap_or_else(|_| format!("{channels:?}")))
     }
 
@@ -1029,9 +1058,8 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<TrackParams>,
     ) -> Result<String, Error> {
-        let types = self
-            .query_available_output_routing_types(params.track)
-            .await?;
+        let TrackId(track) = self.resolve_track(&params.track).await?;
+        let types = self.query_available_output_routing_types(track).await?;
         Ok(serde_json::to_string_pretty(&types).unwrap_or_else(|_| format!("{types:?}")))
     }
 
@@ -1041,9 +1069,8 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<TrackParams>,
     ) -> Result<String, Error> {
-        let channels = self
-            .query_available_output_routing_channels(params.track)
-            .await?;
+        let TrackId(track) = self.resolve_track(&params.track).await?;
+        let channels = self.query_available_output_routing_channels(track).await?;
         Ok(serde_json::to_string_pretty(&channels).unwrap_or_else(|_| format!("{channels:?}")))
     }
 
@@ -1055,20 +1082,21 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<TrackParams>,
     ) -> Result<String, Error> {
+        let TrackId(track) = self.resolve_track(&params.track).await?;
         let available_types = self
-            .query_available_input_routing_types(params.track)
+            .query_available_input_routing_types(track)
             .await
             .unwrap_or_default();
         let available_channels = self
-            .query_available_input_routing_channels(params.track)
+            .query_available_input_routing_channels(track)
             .await
             .unwrap_or_default();
         let current_type = self
-            .query_track_input_routing_type(params.track)
+            .query_track_input_routing_type(track)
             .await
             .unwrap_or_default();
         let current_channel = self
-            .query_track_input_routing_channel(params.track)
+            .query_track_input_routing_channel(track)
             .await
             .unwrap_or_default();
 
@@ -1089,20 +1117,21 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<TrackParams>,
     ) -> Result<String, Error> {
+        let TrackId(track) = self.resolve_track(&params.track).await?;
         let available_types = self
-            .query_available_output_routing_types(params.track)
+            .query_available_output_routing_types(track)
             .await
             .unwrap_or_default();
         let available_channels = self
-            .query_available_output_routing_channels(params.track)
+            .query_available_output_routing_channels(track)
             .await
             .unwrap_or_default();
         let current_type = self
-            .query_track_output_routing_type(params.track)
+            .query_track_output_routing_type(track)
             .await
             .unwrap_or_default();
         let current_channel = self
-            .query_track_output_routing_channel(params.track)
+            .query_track_output_routing_channel(track)
             .await
             .unwrap_or_default();
 
@@ -1121,11 +1150,12 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<TrackParams>,
     ) -> Result<String, Error> {
+        let TrackId(track) = self.resolve_track(&params.track).await?;
         let packets = self
             .osc
             .query_all(
                 "/live/track/get/clips/name",
-                vec![OscType::Int(params.track as i32)],
+                vec![OscType::Int(track as i32)],
             )
             .await
             .unwrap_or_default();
@@ -1151,11 +1181,12 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<TrackParams>,
     ) -> Result<String, Error> {
+        let TrackId(track) = self.resolve_track(&params.track).await?;
         let packets = self
             .osc
             .query_all(
                 "/live/track/get/clips/length",
-                vec![OscType::Int(params.track as i32)],
+                vec![OscType::Int(track as i32)],
             )
             .await
             .unwrap_or_default();
@@ -1181,11 +1212,12 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<TrackParams>,
     ) -> Result<String, Error> {
+        let TrackId(track) = self.resolve_track(&params.track).await?;
         let packets = self
             .osc
             .query_all(
                 "/live/track/get/clips/color",
-                vec![OscType::Int(params.track as i32)],
+                vec![OscType::Int(track as i32)],
             )
             .await
             .unwrap_or_default();
@@ -1211,7 +1243,8 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<TrackParams>,
     ) -> Result<String, Error> {
-        let names = self.query_arrangement_clip_names(params.track).await?;
+        let TrackId(track) = self.resolve_track(&params.track).await?;
+        let names = self.query_arrangement_clip_names(track).await?;
         Ok(serde_json::to_string_pretty(&names).unwrap_or_else(|_| format!("{names:?}")))
     }
 
@@ -1221,7 +1254,8 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<TrackParams>,
     ) -> Result<String, Error> {
-        let lengths = self.query_arrangement_clip_lengths(params.track).await?;
+        let TrackId(track) = self.resolve_track(&params.track).await?;
+        let lengths = self.query_arrangement_clip_lengths(track).await?;
         Ok(serde_json::to_string_pretty(&lengths).unwrap_or_else(|_| format!("{lengths:?}")))
     }
 
@@ -1231,9 +1265,8 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<TrackParams>,
     ) -> Result<String, Error> {
-        let times = self
-            .query_arrangement_clip_start_times(params.track)
-            .await?;
+        let TrackId(track) = self.resolve_track(&params.track).await?;
+        let times = self.query_arrangement_clip_start_times(track).await?;
         Ok(serde_json::to_string_pretty(&times).unwrap_or_else(|_| format!("{times:?}")))
     }
 
@@ -1243,11 +1276,10 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<TrackParams>,
     ) -> Result<String, Error> {
-        let names = self.query_arrangement_clip_names(params.track).await?;
-        let lengths = self.query_arrangement_clip_lengths(params.track).await?;
-        let start_times = self
-            .query_arrangement_clip_start_times(params.track)
-            .await?;
+        let TrackId(track) = self.resolve_track(&params.track).await?;
+        let names = self.query_arrangement_clip_names(track).await?;
+        let lengths = self.query_arrangement_clip_lengths(track).await?;
+        let start_times = self.query_arrangement_clip_start_times(track).await?;
 
         let mut clips = Vec::new();
         for i in 0..names.len().min(lengths.len()).min(start_times.len()) {
@@ -1266,11 +1298,12 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<TrackParams>,
     ) -> Result<String, Error> {
+        let TrackId(track) = self.resolve_track(&params.track).await?;
         let packets = self
             .osc
             .query_all(
                 "/live/track/get/devices/name",
-                vec![OscType::Int(params.track as i32)],
+                vec![OscType::Int(track as i32)],
             )
             .await
             .unwrap_or_default();
@@ -1296,11 +1329,12 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<TrackParams>,
     ) -> Result<String, Error> {
+        let TrackId(track) = self.resolve_track(&params.track).await?;
         let packets = self
             .osc
             .query_all(
                 "/live/track/get/devices/type",
-                vec![OscType::Int(params.track as i32)],
+                vec![OscType::Int(track as i32)],
             )
             .await
             .unwrap_or_default();
@@ -1324,11 +1358,12 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<TrackParams>,
     ) -> Result<String, Error> {
+        let TrackId(track) = self.resolve_track(&params.track).await?;
         let packets = self
             .osc
             .query_all(
                 "/live/track/get/devices/class_name",
-                vec![OscType::Int(params.track as i32)],
+                vec![OscType::Int(track as i32)],
             )
             .await
             .unwrap_or_default();
@@ -1352,23 +1387,63 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<ClipSlotParams>,
     ) -> Result<String, Error> {
+        let TrackId(track) = self.resolve_track(&params.track).await?;
         self.osc
             .send(
                 "/live/track/delete_clip",
-                vec![
-                    OscType::Int(params.track as i32),
-                    OscType::Int(params.slot as i32),
-                ],
+                vec![OscType::Int(track as i32), OscType::Int(params.slot as i32)],
             )
             .await?;
         Ok(format!(
             "Deleted clip at track {}, slot {}",
-            params.track, params.slot
+            track, params.slot
         ))
     }
 
     // ========== Helper methods for internal use ==========
 
+    /// Whether each track is a group track, and the group each one is in.
+    pub(crate) async fn track_groups(&self) -> Result<(Vec<bool>, Vec<Option<u32>>), Error> {
+        let count: i32 = self.osc.query("/live/song/get/num_tracks", vec![]).await?;
+        let mut is_group = Vec::new();
+        let mut parents = Vec::new();
+        for i in 0..count {
+            let args = vec![OscType::Int(i)];
+            let foldable: i32 = self
+                .osc
+                .query("/live/track/get/is_foldable", args.clone())
+                .await?;
+            let grouped: i32 = self
+                .osc
+                .query("/live/track/get/is_grouped", args.clone())
+                .await?;
+            let parent = if grouped != 0 {
+                let reply: Vec<OscType> =
+                    self.osc.query("/live/track/get/group_track", args).await?;
+                group_track_index(&reply)
+            } else {
+                None
+            };
+            is_group.push(foldable != 0);
+            parents.push(parent);
+        }
+        Ok((is_group, parents))
+    }
+
+    /// Wait until the song has `count` tracks.
+    pub(crate) async fn wait_for_track_count(&self, count: i32) -> Result<(), Error> {
+        for _ in 0..POLL_ATTEMPTS {
+            tokio::time::sleep(POLL_INTERVAL).await;
+            let current: i32 = self.osc.query("/live/song/get/num_tracks", vec![]).await?;
+            if current == count {
+                return Ok(());
+            }
+        }
+        Err(Error::InvalidResponse(format!(
+            "Live did not report {count} tracks after the change"
+        )))
+    }
+
     /// Query track input routing type.
     async fn query_track_input_routing_type(&self, track: u32) -> Result<String, Error> {
         self.osc
@@ -1410,7 +1485,10 @@ impl AbletonServer {
     }
 
     /// Query available input routing types for a track.
-    async fn query_available_input_routing_types(&self, track: u32) -> Result<Vec<String>, Error> {
+    pub(crate) async fn query_available_input_routing_types(
+        &self,
+        track: u32,
+    ) -> Result<Vec<String>, Error> {
         let packets = self
             .osc
             .query_all(
@@ -1583,3 +1661,51 @@ impl AbletonServer {
         Ok(times)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(name: &str, is_group: bool, parent: Option<u32>) -> GroupEntry {
+        GroupEntry {
+            name: name.to_string(),
+            is_group,
+            parent,
+        }
+    }
+
+    /// Tracks nest under their group, including groups inside groups.
+    #[test]
+    fn nests_tracks_under_groups() {
+        let entries = vec![
+            entry("Drums", true, None),
+            entry("Kick", false, Some(0)),
+            entry("Tops", true, Some(0)),
+            entry("Hats", false, Some(2)),
+            entry("Snare", false, Some(0)),
+            entry("Bass", false, None),
+        ];
+        let tree = track_tree(&entries, None);
+        assert_eq!(tree.len(), 2);
+        let drums: Vec<&str> = tree[0].children.iter().map(|n| n.name.as_str()).collect();
+        assert_eq!(drums, vec!["Kick", "Tops", "Snare"]);
+        assert_eq!(tree[0].children[1].children[0].index, 3);
+        assert!(tree[1].children.is_empty());
+    }
+
+    /// The group index follows the echoed track index; ungrouped replies
+    /// have none or a negative one.
+    #[test]
+    fn reads_group_track_index() {
+        assert_eq!(
+            group_track_index(&[OscType::Int(4), OscType::Int(0)]),
+            Some(0)
+        );
+        assert_eq!(group_track_index(&[OscType::Int(4)]), None);
+        assert_eq!(
+            group_track_index(&[OscType::Int(4), OscType::Int(-1)]),
+            None
+        );
+        assert_eq!(group_track_index(&[OscType::Int(4), OscType::Nil]), None);
+    }
+}
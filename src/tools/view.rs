@@ -6,7 +6,10 @@ use rosc::{OscPacket, OscType};
 
 use crate::error::Error;
 use crate::server::AbletonServer;
-use crate::types::{SceneParams, SetSelectedClipParams, SetSelectedDeviceParams, TrackParams};
+use crate::types::{
+    DeviceId, SceneId, SceneParams, SetSelectedClipParams, SetSelectedDeviceParams, TrackId,
+    TrackParams,
+};
 
 #[tool_router(router = view_router, vis = "pub")]
 impl AbletonServer {
@@ -28,7 +31,7 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<TrackParams>,
     ) -> Result<String, Error> {
-        let track = params.track;
+        let TrackId(track) = self.resolve_track(&params.track).await?;
         self.osc
             .send(
                 "/live/view/set/selected_track",
@@ -56,7 +59,7 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<SceneParams>,
     ) -> Result<String, Error> {
-        let scene = params.scene;
+        let SceneId(scene) = self.resolve_scene(&params.scene).await?;
         self.osc
             .send(
                 "/live/view/set/selected_scene",
@@ -104,7 +107,7 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<SetSelectedClipParams>,
     ) -> Result<String, Error> {
-        let track = params.track;
+        let TrackId(track) = self.resolve_track(&params.track).await?;
         let slot = params.slot;
         self.osc
             .send(
@@ -153,8 +156,8 @@ impl AbletonServer {
         &self,
         Parameters(params): Parameters<SetSelectedDeviceParams>,
     ) -> Result<String, Error> {
-        let track = params.track;
-        let device = params.device;
+        let TrackId(track) = self.resolve_track(&params.track).await?;
+        let DeviceId(device) = self.resolve_device(TrackId(track), &params.device).await?;
         self.osc
             .send(
                 "/live/view/set/selected_device",
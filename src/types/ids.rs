@@ -1,5 +1,8 @@
-//! Newtype wrappers for various IDs in Ableton Live.
+//! Newtype wrappers for various IDs in Ableton Live, and name-or-index references
+//! that resolve to them.
 
+use rosc::OscType;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 /// Track index (0-based).
@@ -81,3 +84,148 @@ impl From<u32> for ParameterId {
         ParameterId(v)
     }
 }
+
+/// Reference to a track, either by index or by name.
+///
+/// Names are matched exactly first, then case-insensitively, then fuzzily.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum TrackRef {
+    /// Track index (0-based).
+    Index(u32),
+    /// Track name (exact, case-insensitive, or fuzzy match).
+    Name(String),
+}
+
+impl From<u32> for TrackRef {
+    fn from(v: u32) -> Self {
+        TrackRef::Index(v)
+    }
+}
+
+impl From<TrackId> for TrackRef {
+    fn from(id: TrackId) -> Self {
+        TrackRef::Index(id.0)
+    }
+}
+
+/// Kind of track. Live keeps regular tracks and return tracks in separate
+/// lists, so a track index only means something together with its kind.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TrackKind {
+    /// An audio, MIDI or group track.
+    #[default]
+    Regular,
+    /// A return track (A, B, ...).
+    Return,
+}
+
+/// A resolved regular or return track.
+///
+/// Both kinds use the `/live/track/...` and `/live/device/...` endpoints.
+/// Regular tracks pass their index; return tracks pass `"return_track"`
+/// followed by the return index, the form `AbletonOSC` takes for its
+/// `return_track` index.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TrackAddr {
+    pub kind: TrackKind,
+    /// Index within the kind's list.
+    pub index: u32,
+}
+
+impl TrackAddr {
+    /// A regular track.
+    pub const fn regular(index: u32) -> Self {
+        Self {
+            kind: TrackKind::Regular,
+            index,
+        }
+    }
+
+    /// A return track.
+    pub const fn return_track(index: u32) -> Self {
+        Self {
+            kind: TrackKind::Return,
+            index,
+        }
+    }
+
+    /// Leading arguments that identify the track.
+    fn args(self) -> Vec<OscType> {
+        let index = OscType::Int(self.index as i32);
+        match self.kind {
+            TrackKind::Regular => vec![index],
+            TrackKind::Return => vec![OscType::String("return_track".to_string()), index],
+        }
+    }
+
+    /// Address and leading arguments for a track endpoint.
+    ///
+    /// `action` is the part after `/live/track/`, e.g. `"get/volume"`.
+    pub fn track_message(self, action: &str) -> (String, Vec<OscType>) {
+        (format!("/live/track/{action}"), self.args())
+    }
+
+    /// Address and leading arguments for an endpoint of a device on the track.
+    ///
+    /// `action` is the part after `/live/device/`, e.g. `"get/name"`.
+    pub fn device_message(self, device: u32, action: &str) -> (String, Vec<OscType>) {
+        let mut args = self.args();
+        args.push(OscType::Int(device as i32));
+        (format!("/live/device/{action}"), args)
+    }
+}
+
+impl std::fmt::Display for TrackAddr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.kind {
+            TrackKind::Regular => write!(f, "track {}", self.index),
+            TrackKind::Return => write!(f, "return track {}", self.index),
+        }
+    }
+}
+
+/// Reference to a scene, either by index or by name.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum SceneRef {
+    /// Scene index (0-based).
+    Index(u32),
+    /// Scene name (exact, case-insensitive, or fuzzy match).
+    Name(String),
+}
+
+impl From<u32> for SceneRef {
+    fn from(v: u32) -> Self {
+        SceneRef::Index(v)
+    }
+}
+
+impl From<SceneId> for SceneRef {
+    fn from(id: SceneId) -> Self {
+        SceneRef::Index(id.0)
+    }
+}
+
+/// Reference to a device on a track, either by index or by name.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum DeviceRef {
+    /// Device index (0-based).
+    Index(u32),
+    /// Device name (exact, case-insensitive, or fuzzy match).
+    Name(String),
+}
+
+impl From<u32> for DeviceRef {
+    fn from(v: u32) -> Self {
+        DeviceRef::Index(v)
+    }
+}
+
+impl From<DeviceId> for DeviceRef {
+    fn from(id: DeviceId) -> Self {
+        DeviceRef::Index(id.0)
+    }
+}
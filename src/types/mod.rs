@@ -3,7 +3,9 @@
 mod ids;
 mod params;
 mod tool_params;
+mod values;
 
 pub use ids::*;
 pub use params::*;
 pub use tool_params::*;
+pub use values::*;
@@ -3,6 +3,8 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+use crate::types::{Pan, TrackRef, Volume};
+
 /// Track information returned from `list_tracks`.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TrackInfo {
@@ -11,8 +13,25 @@ pub struct TrackInfo {
     pub armed: bool,
     pub muted: bool,
     pub soloed: bool,
+    /// Fader position (0.0 to 1.0).
     pub volume: f32,
+    /// Volume in dB; `None` when the fader is at -inf.
+    pub volume_db: Option<f32>,
     pub pan: f32,
+    /// Pan as Live shows it, e.g. `25L`, `C` or `50R`.
+    pub pan_label: String,
+}
+
+/// A track in the group hierarchy, returned from `get_track_tree`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TrackNode {
+    pub index: u32,
+    pub name: String,
+    /// Whether this is a group track.
+    pub is_group: bool,
+    /// Tracks inside the group, in track order.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<TrackNode>,
 }
 
 /// Scene information returned from `list_scenes`.
@@ -52,6 +71,36 @@ pub struct ParameterInfo {
     pub max: f32,
 }
 
+/// Rack macro information returned from `list_rack_macros`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MacroInfo {
+    /// Macro number as shown in Live (1-16).
+    pub number: u32,
+    /// Index of the macro in the rack's parameter list.
+    pub parameter: u32,
+    pub name: String,
+    pub value: f32,
+    pub min: f32,
+    pub max: f32,
+}
+
+/// A macro value change, returned by macro tools that write several macros.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MacroChange {
+    pub number: u32,
+    pub name: String,
+    pub before: f32,
+    pub after: f32,
+}
+
+/// Result of `randomize_rack_macros`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MacroRandomization {
+    /// Seed that reproduces this result.
+    pub seed: u64,
+    pub changes: Vec<MacroChange>,
+}
+
 /// Song information returned from `get_song_info`.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SongInfo {
@@ -222,3 +271,101 @@ pub struct SongDetailedInfo {
     pub root_note: i32,
     pub scale_name: String,
 }
+
+/// Kind of track a template creates.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TrackType {
+    #[default]
+    Midi,
+    Audio,
+}
+
+/// A device to load, by browser path, with an optional saved preset.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct TemplateDevice {
+    /// Full browser path, e.g. `"instruments/Operator"`.
+    #[schemars(
+        description = "Full browser path, e.g. 'instruments/Operator' or 'audio_effects/EQ Eight'"
+    )]
+    pub path: String,
+    /// Device preset to apply after loading (see `save_device_preset`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(description = "Saved device preset to apply after loading (fuzzy-matched)")]
+    pub preset: Option<String>,
+}
+
+/// A send level on the new track.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct TemplateSend {
+    /// Return track index (0-based) or name.
+    #[schemars(description = "Return track index (0-based) or name, e.g. 'A-Reverb'")]
+    pub send: TrackRef,
+    /// Send level (0.0-1.0).
+    #[schemars(description = "Send level (0.0-1.0)")]
+    pub level: f32,
+}
+
+/// Input or output routing of the new track.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct TemplateRouting {
+    /// Routing type, e.g. `"Ext. In"` or `"Master"`.
+    #[serde(rename = "type", default, skip_serializing_if = "Option::is_none")]
+    #[schemars(
+        description = "Routing type as Live shows it, e.g. 'Ext. In', 'All Ins' or 'Master'"
+    )]
+    pub routing_type: Option<String>,
+    /// Routing channel, e.g. `"1/2"`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(description = "Routing channel as Live shows it, e.g. '1/2' or 'Post FX'")]
+    pub channel: Option<String>,
+}
+
+/// A recipe for a configured track.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct TrackTemplate {
+    /// What the template is for.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(description = "What the template is for")]
+    pub description: Option<String>,
+    /// MIDI or audio track.
+    #[serde(default)]
+    #[schemars(description = "Track type: 'midi' (default) or 'audio'")]
+    pub track_type: TrackType,
+    /// Name for the new track.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(description = "Name for the new track")]
+    pub name: Option<String>,
+    /// Track color (0xRRGGBB).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(description = "Track color as 0xRRGGBB")]
+    pub color: Option<i32>,
+    /// Instrument to load first (MIDI tracks only).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(description = "Instrument to load (MIDI tracks only)")]
+    pub instrument: Option<TemplateDevice>,
+    /// Effects to load after the instrument, in order.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[schemars(description = "Effects to load after the instrument, in chain order")]
+    pub effects: Vec<TemplateDevice>,
+    /// Track volume.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(description = "Track volume: fader position (0.0-1.0) or a level like '-6 dB'")]
+    pub volume: Option<Volume>,
+    /// Track pan.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(description = "Track pan: -1.0 to 1.0 or a label like '25L', 'C' or '50R'")]
+    pub pan: Option<Pan>,
+    /// Send levels.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[schemars(description = "Send levels to return tracks")]
+    pub sends: Vec<TemplateSend>,
+    /// Input routing.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(description = "Input routing type and channel")]
+    pub input: Option<TemplateRouting>,
+    /// Output routing.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(description = "Output routing type and channel")]
+    pub output: Option<TemplateRouting>,
+}
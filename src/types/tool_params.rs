@@ -7,7 +7,10 @@
 use schemars::JsonSchema;
 use serde::Deserialize;
 
-use crate::types::MidiNote;
+use crate::types::{
+    DeviceRef, GraphFormat, MidiNote, Pan, SceneRef, Shape, TargetKind, TrackKind, TrackRef,
+    TrackTemplate, Volume,
+};
 
 // =============================================================================
 // Transport Parameters
@@ -41,42 +44,70 @@ pub struct SetMetronomeParams {
 // Track Parameters
 // =============================================================================
 
-/// Parameters for tools that only require a track index.
+/// Parameters for tools that only require a track.
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct TrackParams {
-    /// Track index (0-based).
-    #[schemars(description = "Track index (0-based)")]
-    pub track: u32,
+    /// Track index (0-based) or name.
+    #[schemars(description = "Track index (0-based) or name")]
+    pub track: TrackRef,
 }
 
 /// Parameters for `set_track_volume` tool.
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct SetTrackVolumeParams {
-    /// Track index (0-based).
-    #[schemars(description = "Track index (0-based)")]
-    pub track: u32,
-    /// Volume level (0.0 to 1.0).
-    #[schemars(description = "Volume level (0.0 to 1.0)")]
-    pub volume: f32,
+    /// Track index (0-based) or name.
+    #[schemars(description = "Track index (0-based) or name")]
+    pub track: TrackRef,
+    /// Kind of track the reference points to.
+    #[serde(default)]
+    #[schemars(description = "Track kind: 'regular' (default) or 'return'")]
+    pub kind: TrackKind,
+    /// Volume as a fader position or in dB.
+    #[schemars(
+        description = "Volume: fader position (0.0 to 1.0, 0.85 = 0 dB) or a level in dB such as \"-6 dB\" or \"-inf\""
+    )]
+    pub volume: Volume,
+}
+
+/// Parameters for `list_devices` tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ListDevicesParams {
+    /// Track index (0-based) or name.
+    #[schemars(description = "Track index (0-based) or name")]
+    pub track: TrackRef,
+    /// Kind of track the reference points to.
+    #[serde(default)]
+    #[schemars(description = "Track kind: 'regular' (default) or 'return'")]
+    pub kind: TrackKind,
 }
 
 /// Parameters for `set_track_pan` tool.
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct SetTrackPanParams {
-    /// Track index (0-based).
-    #[schemars(description = "Track index (0-based)")]
-    pub track: u32,
-    /// Pan position (-1.0 left to 1.0 right).
-    #[schemars(description = "Pan position (-1.0 left to 1.0 right)")]
-    pub pan: f32,
+    /// Track index (0-based) or name.
+    #[schemars(description = "Track index (0-based) or name")]
+    pub track: TrackRef,
+    /// Kind of track the reference points to.
+    #[serde(default)]
+    #[schemars(description = "Track kind: 'regular' (default) or 'return'")]
+    pub kind: TrackKind,
+    /// Pan as a number or as Live shows it.
+    #[schemars(
+        description = "Pan: -1.0 (left) to 1.0 (right), or as Live shows it: \"25L\", \"C\", \"50R\""
+    )]
+    pub pan: Pan,
 }
 
 /// Parameters for `mute_track` tool.
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct MuteTrackParams {
-    /// Track index (0-based).
-    #[schemars(description = "Track index (0-based)")]
-    pub track: u32,
+    /// Track index (0-based) or name.
+    #[schemars(description = "Track index (0-based) or name")]
+    pub track: TrackRef,
+    /// Kind of track the reference points to.
+    #[serde(default)]
+    #[schemars(description = "Track kind: 'regular' (default) or 'return'")]
+    pub kind: TrackKind,
     /// Whether to mute the track.
     #[schemars(description = "Whether to mute the track")]
     pub mute: bool,
@@ -85,9 +116,13 @@ pub struct MuteTrackParams {
 /// Parameters for `solo_track` tool.
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct SoloTrackParams {
-    /// Track index (0-based).
-    #[schemars(description = "Track index (0-based)")]
-    pub track: u32,
+    /// Track index (0-based) or name.
+    #[schemars(description = "Track index (0-based) or name")]
+    pub track: TrackRef,
+    /// Kind of track the reference points to.
+    #[serde(default)]
+    #[schemars(description = "Track kind: 'regular' (default) or 'return'")]
+    pub kind: TrackKind,
     /// Whether to solo the track.
     #[schemars(description = "Whether to solo the track")]
     pub solo: bool,
@@ -96,9 +131,9 @@ pub struct SoloTrackParams {
 /// Parameters for `arm_track` tool.
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct ArmTrackParams {
-    /// Track index (0-based).
-    #[schemars(description = "Track index (0-based)")]
-    pub track: u32,
+    /// Track index (0-based) or name.
+    #[schemars(description = "Track index (0-based) or name")]
+    pub track: TrackRef,
     /// Whether to arm the track.
     #[schemars(description = "Whether to arm the track")]
     pub arm: bool,
@@ -115,9 +150,13 @@ pub struct CreateTrackParams {
 /// Parameters for `set_track_name` tool.
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct SetTrackNameParams {
-    /// Track index (0-based).
-    #[schemars(description = "Track index (0-based)")]
-    pub track: u32,
+    /// Track index (0-based) or name.
+    #[schemars(description = "Track index (0-based) or name")]
+    pub track: TrackRef,
+    /// Kind of track the reference points to.
+    #[serde(default)]
+    #[schemars(description = "Track kind: 'regular' (default) or 'return'")]
+    pub kind: TrackKind,
     /// New name for the track.
     #[schemars(description = "New name for the track")]
     pub name: String,
@@ -126,9 +165,9 @@ pub struct SetTrackNameParams {
 /// Parameters for `set_track_send` tool.
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct SetTrackSendParams {
-    /// Track index (0-based).
-    #[schemars(description = "Track index (0-based)")]
-    pub track: u32,
+    /// Track index (0-based) or name.
+    #[schemars(description = "Track index (0-based) or name")]
+    pub track: TrackRef,
     /// Send index (0-based).
     #[schemars(description = "Send index (0-based)")]
     pub send: u32,
@@ -140,9 +179,9 @@ pub struct SetTrackSendParams {
 /// Parameters for `get_track_send` tool.
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct GetTrackSendParams {
-    /// Track index (0-based).
-    #[schemars(description = "Track index (0-based)")]
-    pub track: u32,
+    /// Track index (0-based) or name.
+    #[schemars(description = "Track index (0-based) or name")]
+    pub track: TrackRef,
     /// Send index (0-based).
     #[schemars(description = "Send index (0-based)")]
     pub send: u32,
@@ -151,9 +190,9 @@ pub struct GetTrackSendParams {
 /// Parameters for `set_track_color` tool.
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct SetTrackColorParams {
-    /// Track index (0-based).
-    #[schemars(description = "Track index (0-based)")]
-    pub track: u32,
+    /// Track index (0-based) or name.
+    #[schemars(description = "Track index (0-based) or name")]
+    pub track: TrackRef,
     /// RGB color as integer.
     #[schemars(description = "RGB color as integer")]
     pub color: i32,
@@ -162,9 +201,9 @@ pub struct SetTrackColorParams {
 /// Parameters for `set_track_monitoring` tool.
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct SetTrackMonitoringParams {
-    /// Track index (0-based).
-    #[schemars(description = "Track index (0-based)")]
-    pub track: u32,
+    /// Track index (0-based) or name.
+    #[schemars(description = "Track index (0-based) or name")]
+    pub track: TrackRef,
     /// Monitoring state (0=In, 1=Auto, 2=Off).
     #[schemars(description = "Monitoring state (0=In, 1=Auto, 2=Off)")]
     pub state: i32,
@@ -173,9 +212,9 @@ pub struct SetTrackMonitoringParams {
 /// Parameters for `set_track_fold_state` tool.
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct SetTrackFoldStateParams {
-    /// Track index (0-based).
-    #[schemars(description = "Track index (0-based)")]
-    pub track: u32,
+    /// Track index (0-based) or name.
+    #[schemars(description = "Track index (0-based) or name")]
+    pub track: TrackRef,
     /// Whether to fold the track.
     #[schemars(description = "Whether to fold the track")]
     pub folded: bool,
@@ -184,9 +223,9 @@ pub struct SetTrackFoldStateParams {
 /// Parameters for `set_track_input_routing_type` and `set_track_output_routing_type` tools.
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct SetTrackRoutingTypeParams {
-    /// Track index (0-based).
-    #[schemars(description = "Track index (0-based)")]
-    pub track: u32,
+    /// Track index (0-based) or name.
+    #[schemars(description = "Track index (0-based) or name")]
+    pub track: TrackRef,
     /// Routing type name.
     #[schemars(description = "Routing type name")]
     pub routing_type: String,
@@ -195,9 +234,9 @@ pub struct SetTrackRoutingTypeParams {
 /// Parameters for `set_track_input_routing_channel` and `set_track_output_routing_channel` tools.
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct SetTrackRoutingChannelParams {
-    /// Track index (0-based).
-    #[schemars(description = "Track index (0-based)")]
-    pub track: u32,
+    /// Track index (0-based) or name.
+    #[schemars(description = "Track index (0-based) or name")]
+    pub track: TrackRef,
     /// Routing channel name.
     #[schemars(description = "Routing channel name")]
     pub channel: String,
@@ -210,9 +249,9 @@ pub struct SetTrackRoutingChannelParams {
 /// Parameters for tools that require track and slot indices.
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct ClipSlotParams {
-    /// Track index (0-based).
-    #[schemars(description = "Track index (0-based)")]
-    pub track: u32,
+    /// Track index (0-based) or name.
+    #[schemars(description = "Track index (0-based) or name")]
+    pub track: TrackRef,
     /// Clip slot index (0-based).
     #[schemars(description = "Clip slot index (0-based)")]
     pub slot: u32,
@@ -221,9 +260,9 @@ pub struct ClipSlotParams {
 /// Parameters for `set_clip_name` tool.
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct SetClipNameParams {
-    /// Track index (0-based).
-    #[schemars(description = "Track index (0-based)")]
-    pub track: u32,
+    /// Track index (0-based) or name.
+    #[schemars(description = "Track index (0-based) or name")]
+    pub track: TrackRef,
     /// Clip slot index (0-based).
     #[schemars(description = "Clip slot index (0-based)")]
     pub slot: u32,
@@ -235,9 +274,9 @@ pub struct SetClipNameParams {
 /// Parameters for `create_clip` tool.
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct CreateClipParams {
-    /// Track index (0-based).
-    #[schemars(description = "Track index (0-based)")]
-    pub track: u32,
+    /// Track index (0-based) or name.
+    #[schemars(description = "Track index (0-based) or name")]
+    pub track: TrackRef,
     /// Clip slot index (0-based).
     #[schemars(description = "Clip slot index (0-based)")]
     pub slot: u32,
@@ -249,9 +288,9 @@ pub struct CreateClipParams {
 /// Parameters for setting clip loop start or end.
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct SetClipLoopPointParams {
-    /// Track index (0-based).
-    #[schemars(description = "Track index (0-based)")]
-    pub track: u32,
+    /// Track index (0-based) or name.
+    #[schemars(description = "Track index (0-based) or name")]
+    pub track: TrackRef,
     /// Clip slot index (0-based).
     #[schemars(description = "Clip slot index (0-based)")]
     pub slot: u32,
@@ -263,9 +302,9 @@ pub struct SetClipLoopPointParams {
 /// Parameters for `add_clip_notes` tool.
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct AddClipNotesParams {
-    /// Track index (0-based).
-    #[schemars(description = "Track index (0-based)")]
-    pub track: u32,
+    /// Track index (0-based) or name.
+    #[schemars(description = "Track index (0-based) or name")]
+    pub track: TrackRef,
     /// Clip slot index (0-based).
     #[schemars(description = "Clip slot index (0-based)")]
     pub slot: u32,
@@ -277,9 +316,9 @@ pub struct AddClipNotesParams {
 /// Parameters for `remove_clip_notes` tool.
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct RemoveClipNotesParams {
-    /// Track index (0-based).
-    #[schemars(description = "Track index (0-based)")]
-    pub track: u32,
+    /// Track index (0-based) or name.
+    #[schemars(description = "Track index (0-based) or name")]
+    pub track: TrackRef,
     /// Clip slot index (0-based).
     #[schemars(description = "Clip slot index (0-based)")]
     pub slot: u32,
@@ -300,9 +339,9 @@ pub struct RemoveClipNotesParams {
 /// Parameters for `set_clip_color` tool.
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct SetClipColorParams {
-    /// Track index (0-based).
-    #[schemars(description = "Track index (0-based)")]
-    pub track: u32,
+    /// Track index (0-based) or name.
+    #[schemars(description = "Track index (0-based) or name")]
+    pub track: TrackRef,
     /// Clip slot index (0-based).
     #[schemars(description = "Clip slot index (0-based)")]
     pub slot: u32,
@@ -314,9 +353,9 @@ pub struct SetClipColorParams {
 /// Parameters for `set_clip_gain` tool.
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct SetClipGainParams {
-    /// Track index (0-based).
-    #[schemars(description = "Track index (0-based)")]
-    pub track: u32,
+    /// Track index (0-based) or name.
+    #[schemars(description = "Track index (0-based) or name")]
+    pub track: TrackRef,
     /// Clip slot index (0-based).
     #[schemars(description = "Clip slot index (0-based)")]
     pub slot: u32,
@@ -328,9 +367,9 @@ pub struct SetClipGainParams {
 /// Parameters for `set_clip_pitch` tool.
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct SetClipPitchParams {
-    /// Track index (0-based).
-    #[schemars(description = "Track index (0-based)")]
-    pub track: u32,
+    /// Track index (0-based) or name.
+    #[schemars(description = "Track index (0-based) or name")]
+    pub track: TrackRef,
     /// Clip slot index (0-based).
     #[schemars(description = "Clip slot index (0-based)")]
     pub slot: u32,
@@ -342,9 +381,9 @@ pub struct SetClipPitchParams {
 /// Parameters for `set_clip_warp` tool.
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct SetClipWarpParams {
-    /// Track index (0-based).
-    #[schemars(description = "Track index (0-based)")]
-    pub track: u32,
+    /// Track index (0-based) or name.
+    #[schemars(description = "Track index (0-based) or name")]
+    pub track: TrackRef,
     /// Clip slot index (0-based).
     #[schemars(description = "Clip slot index (0-based)")]
     pub slot: u32,
@@ -356,9 +395,9 @@ pub struct SetClipWarpParams {
 /// Parameters for `set_clip_warp_mode` tool.
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct SetClipWarpModeParams {
-    /// Track index (0-based).
-    #[schemars(description = "Track index (0-based)")]
-    pub track: u32,
+    /// Track index (0-based) or name.
+    #[schemars(description = "Track index (0-based) or name")]
+    pub track: TrackRef,
     /// Clip slot index (0-based).
     #[schemars(description = "Clip slot index (0-based)")]
     pub slot: u32,
@@ -372,9 +411,9 @@ pub struct SetClipWarpModeParams {
 /// Parameters for `set_clip_loop_bounds` tool.
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct SetClipLoopBoundsParams {
-    /// Track index (0-based).
-    #[schemars(description = "Track index (0-based)")]
-    pub track: u32,
+    /// Track index (0-based) or name.
+    #[schemars(description = "Track index (0-based) or name")]
+    pub track: TrackRef,
     /// Clip slot index (0-based).
     #[schemars(description = "Clip slot index (0-based)")]
     pub slot: u32,
@@ -389,9 +428,9 @@ pub struct SetClipLoopBoundsParams {
 /// Parameters for `set_clip_launch_mode` tool.
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct SetClipLaunchModeParams {
-    /// Track index (0-based).
-    #[schemars(description = "Track index (0-based)")]
-    pub track: u32,
+    /// Track index (0-based) or name.
+    #[schemars(description = "Track index (0-based) or name")]
+    pub track: TrackRef,
     /// Clip slot index (0-based).
     #[schemars(description = "Clip slot index (0-based)")]
     pub slot: u32,
@@ -403,9 +442,9 @@ pub struct SetClipLaunchModeParams {
 /// Parameters for `set_clip_launch_quantization` tool.
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct SetClipLaunchQuantizationParams {
-    /// Track index (0-based).
-    #[schemars(description = "Track index (0-based)")]
-    pub track: u32,
+    /// Track index (0-based) or name.
+    #[schemars(description = "Track index (0-based) or name")]
+    pub track: TrackRef,
     /// Clip slot index (0-based).
     #[schemars(description = "Clip slot index (0-based)")]
     pub slot: u32,
@@ -417,15 +456,15 @@ pub struct SetClipLaunchQuantizationParams {
 /// Parameters for `duplicate_clip_to` tool.
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct DuplicateClipToParams {
-    /// Source track index (0-based).
-    #[schemars(description = "Source track index (0-based)")]
-    pub src_track: u32,
+    /// Source track index (0-based) or name.
+    #[schemars(description = "Source track index (0-based) or name")]
+    pub src_track: TrackRef,
     /// Source clip slot index (0-based).
     #[schemars(description = "Source clip slot index (0-based)")]
     pub src_slot: u32,
-    /// Destination track index (0-based).
-    #[schemars(description = "Destination track index (0-based)")]
-    pub dst_track: u32,
+    /// Destination track index (0-based) or name.
+    #[schemars(description = "Destination track index (0-based) or name")]
+    pub dst_track: TrackRef,
     /// Destination clip slot index (0-based).
     #[schemars(description = "Destination clip slot index (0-based)")]
     pub dst_slot: u32,
@@ -434,9 +473,9 @@ pub struct DuplicateClipToParams {
 /// Parameters for `set_clip_slot_has_stop_button` tool.
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct SetClipSlotHasStopButtonParams {
-    /// Track index (0-based).
-    #[schemars(description = "Track index (0-based)")]
-    pub track: u32,
+    /// Track index (0-based) or name.
+    #[schemars(description = "Track index (0-based) or name")]
+    pub track: TrackRef,
     /// Clip slot index (0-based).
     #[schemars(description = "Clip slot index (0-based)")]
     pub slot: u32,
@@ -448,9 +487,9 @@ pub struct SetClipSlotHasStopButtonParams {
 /// Parameters for `set_clip_looping` tool.
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct SetClipLoopingParams {
-    /// Track index (0-based).
-    #[schemars(description = "Track index (0-based)")]
-    pub track: u32,
+    /// Track index (0-based) or name.
+    #[schemars(description = "Track index (0-based) or name")]
+    pub track: TrackRef,
     /// Clip slot index (0-based).
     #[schemars(description = "Clip slot index (0-based)")]
     pub slot: u32,
@@ -462,9 +501,9 @@ pub struct SetClipLoopingParams {
 /// Parameters for `set_clip_muted` tool.
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct SetClipMutedParams {
-    /// Track index (0-based).
-    #[schemars(description = "Track index (0-based)")]
-    pub track: u32,
+    /// Track index (0-based) or name.
+    #[schemars(description = "Track index (0-based) or name")]
+    pub track: TrackRef,
     /// Clip slot index (0-based).
     #[schemars(description = "Clip slot index (0-based)")]
     pub slot: u32,
@@ -476,9 +515,9 @@ pub struct SetClipMutedParams {
 /// Parameters for `set_clip_position` tool.
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct SetClipPositionParams {
-    /// Track index (0-based).
-    #[schemars(description = "Track index (0-based)")]
-    pub track: u32,
+    /// Track index (0-based) or name.
+    #[schemars(description = "Track index (0-based) or name")]
+    pub track: TrackRef,
     /// Clip slot index (0-based).
     #[schemars(description = "Clip slot index (0-based)")]
     pub slot: u32,
@@ -490,9 +529,9 @@ pub struct SetClipPositionParams {
 /// Parameters for `set_clip_start_marker` and `set_clip_end_marker` tools.
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct SetClipMarkerParams {
-    /// Track index (0-based).
-    #[schemars(description = "Track index (0-based)")]
-    pub track: u32,
+    /// Track index (0-based) or name.
+    #[schemars(description = "Track index (0-based) or name")]
+    pub track: TrackRef,
     /// Clip slot index (0-based).
     #[schemars(description = "Clip slot index (0-based)")]
     pub slot: u32,
@@ -504,9 +543,9 @@ pub struct SetClipMarkerParams {
 /// Parameters for `set_clip_legato` tool.
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct SetClipLegatoParams {
-    /// Track index (0-based).
-    #[schemars(description = "Track index (0-based)")]
-    pub track: u32,
+    /// Track index (0-based) or name.
+    #[schemars(description = "Track index (0-based) or name")]
+    pub track: TrackRef,
     /// Clip slot index (0-based).
     #[schemars(description = "Clip slot index (0-based)")]
     pub slot: u32,
@@ -518,9 +557,9 @@ pub struct SetClipLegatoParams {
 /// Parameters for `set_clip_velocity_amount` tool.
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct SetClipVelocityAmountParams {
-    /// Track index (0-based).
-    #[schemars(description = "Track index (0-based)")]
-    pub track: u32,
+    /// Track index (0-based) or name.
+    #[schemars(description = "Track index (0-based) or name")]
+    pub track: TrackRef,
     /// Clip slot index (0-based).
     #[schemars(description = "Clip slot index (0-based)")]
     pub slot: u32,
@@ -532,9 +571,9 @@ pub struct SetClipVelocityAmountParams {
 /// Parameters for `set_clip_color_index` tool.
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct SetClipColorIndexParams {
-    /// Track index (0-based).
-    #[schemars(description = "Track index (0-based)")]
-    pub track: u32,
+    /// Track index (0-based) or name.
+    #[schemars(description = "Track index (0-based) or name")]
+    pub track: TrackRef,
     /// Clip slot index (0-based).
     #[schemars(description = "Clip slot index (0-based)")]
     pub slot: u32,
@@ -546,9 +585,9 @@ pub struct SetClipColorIndexParams {
 /// Parameters for `set_clip_pitch_fine` tool.
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct SetClipPitchFineParams {
-    /// Track index (0-based).
-    #[schemars(description = "Track index (0-based)")]
-    pub track: u32,
+    /// Track index (0-based) or name.
+    #[schemars(description = "Track index (0-based) or name")]
+    pub track: TrackRef,
     /// Clip slot index (0-based).
     #[schemars(description = "Clip slot index (0-based)")]
     pub slot: u32,
@@ -560,9 +599,9 @@ pub struct SetClipPitchFineParams {
 /// Parameters for `set_clip_ram_mode` tool.
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct SetClipRamModeParams {
-    /// Track index (0-based).
-    #[schemars(description = "Track index (0-based)")]
-    pub track: u32,
+    /// Track index (0-based) or name.
+    #[schemars(description = "Track index (0-based) or name")]
+    pub track: TrackRef,
     /// Clip slot index (0-based).
     #[schemars(description = "Clip slot index (0-based)")]
     pub slot: u32,
@@ -575,12 +614,12 @@ pub struct SetClipRamModeParams {
 // Scene Parameters
 // =============================================================================
 
-/// Parameters for tools that only require a scene index.
+/// Parameters for tools that only require a scene.
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct SceneParams {
-    /// Scene index (0-based).
-    #[schemars(description = "Scene index (0-based)")]
-    pub scene: u32,
+    /// Scene index (0-based) or name.
+    #[schemars(description = "Scene index (0-based) or name")]
+    pub scene: SceneRef,
 }
 
 /// Parameters for `create_scene` tool.
@@ -594,9 +633,9 @@ pub struct CreateSceneParams {
 /// Parameters for `set_scene_name` tool.
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct SetSceneNameParams {
-    /// Scene index (0-based).
-    #[schemars(description = "Scene index (0-based)")]
-    pub scene: u32,
+    /// Scene index (0-based) or name.
+    #[schemars(description = "Scene index (0-based) or name")]
+    pub scene: SceneRef,
     /// New name for the scene.
     #[schemars(description = "New name for the scene")]
     pub name: String,
@@ -605,9 +644,9 @@ pub struct SetSceneNameParams {
 /// Parameters for `set_scene_color` tool.
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct SetSceneColorParams {
-    /// Scene index (0-based).
-    #[schemars(description = "Scene index (0-based)")]
-    pub scene: u32,
+    /// Scene index (0-based) or name.
+    #[schemars(description = "Scene index (0-based) or name")]
+    pub scene: SceneRef,
     /// RGB color as integer.
     #[schemars(description = "RGB color as integer")]
     pub color: i32,
@@ -616,9 +655,9 @@ pub struct SetSceneColorParams {
 /// Parameters for `set_scene_tempo` tool.
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct SetSceneTempoParams {
-    /// Scene index (0-based).
-    #[schemars(description = "Scene index (0-based)")]
-    pub scene: u32,
+    /// Scene index (0-based) or name.
+    #[schemars(description = "Scene index (0-based) or name")]
+    pub scene: SceneRef,
     /// Scene tempo in BPM.
     #[schemars(description = "Scene tempo in BPM")]
     pub tempo: f32,
@@ -627,9 +666,9 @@ pub struct SetSceneTempoParams {
 /// Parameters for `set_scene_tempo_enabled` tool.
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct SetSceneTempoEnabledParams {
-    /// Scene index (0-based).
-    #[schemars(description = "Scene index (0-based)")]
-    pub scene: u32,
+    /// Scene index (0-based) or name.
+    #[schemars(description = "Scene index (0-based) or name")]
+    pub scene: SceneRef,
     /// Whether scene tempo is enabled.
     #[schemars(description = "Whether scene tempo is enabled")]
     pub enabled: bool,
@@ -638,9 +677,9 @@ pub struct SetSceneTempoEnabledParams {
 /// Parameters for `set_scene_time_signature` tool.
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct SetSceneTimeSignatureParams {
-    /// Scene index (0-based).
-    #[schemars(description = "Scene index (0-based)")]
-    pub scene: u32,
+    /// Scene index (0-based) or name.
+    #[schemars(description = "Scene index (0-based) or name")]
+    pub scene: SceneRef,
     /// Time signature numerator.
     #[schemars(description = "Time signature numerator")]
     pub numerator: i32,
@@ -652,9 +691,9 @@ pub struct SetSceneTimeSignatureParams {
 /// Parameters for `set_scene_time_sig_enabled` tool.
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct SetSceneTimeSigEnabledParams {
-    /// Scene index (0-based).
-    #[schemars(description = "Scene index (0-based)")]
-    pub scene: u32,
+    /// Scene index (0-based) or name.
+    #[schemars(description = "Scene index (0-based) or name")]
+    pub scene: SceneRef,
     /// Whether scene time signature is enabled.
     #[schemars(description = "Whether scene time signature is enabled")]
     pub enabled: bool,
@@ -664,26 +703,79 @@ pub struct SetSceneTimeSigEnabledParams {
 // Device Parameters
 // =============================================================================
 
-/// Parameters for tools that require track and device indices.
+/// Parameters for tools that require a track and device.
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct DeviceParams {
-    /// Track index (0-based).
-    #[schemars(description = "Track index (0-based)")]
-    pub track: u32,
-    /// Device index (0-based).
-    #[schemars(description = "Device index (0-based)")]
-    pub device: u32,
+    /// Track index (0-based) or name.
+    #[schemars(description = "Track index (0-based) or name")]
+    pub track: TrackRef,
+    /// Device index (0-based) or name.
+    #[schemars(description = "Device index (0-based) or name")]
+    pub device: DeviceRef,
+}
+
+/// Parameters for device tools that also work on return tracks.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct TrackDeviceParams {
+    /// Track index (0-based) or name.
+    #[schemars(description = "Track index (0-based) or name")]
+    pub track: TrackRef,
+    /// Kind of track the reference points to.
+    #[serde(default)]
+    #[schemars(description = "Track kind: 'regular' (default) or 'return'")]
+    pub kind: TrackKind,
+    /// Device index (0-based) or name.
+    #[schemars(description = "Device index (0-based) or name")]
+    pub device: DeviceRef,
+}
+
+/// Parameters for `move_device` tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct MoveDeviceParams {
+    /// Track index (0-based) or name.
+    #[schemars(description = "Track index (0-based) or name")]
+    pub track: TrackRef,
+    /// Device index (0-based) or name.
+    #[schemars(description = "Device index (0-based) or name")]
+    pub device: DeviceRef,
+    /// Destination track (defaults to the same track).
+    #[schemars(description = "Destination track index (0-based) or name (default: same track)")]
+    pub target_track: Option<TrackRef>,
+    /// Destination position.
+    #[schemars(description = "Device index (0-based) to move to (default: end of the chain)")]
+    pub position: Option<u32>,
+}
+
+/// Parameters for `copy_device_to_track` tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct CopyDeviceToTrackParams {
+    /// Track index (0-based) or name.
+    #[schemars(description = "Track index (0-based) or name")]
+    pub track: TrackRef,
+    /// Device index (0-based) or name.
+    #[schemars(description = "Device index (0-based) or name")]
+    pub device: DeviceRef,
+    /// Destination track.
+    #[schemars(description = "Destination track index (0-based) or name")]
+    pub target_track: TrackRef,
+    /// Destination position.
+    #[schemars(description = "Device index (0-based) to insert at (default: end of the chain)")]
+    pub position: Option<u32>,
 }
 
 /// Parameters for `set_device_parameter` tool.
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct SetDeviceParameterParams {
-    /// Track index (0-based).
-    #[schemars(description = "Track index (0-based)")]
-    pub track: u32,
-    /// Device index (0-based).
-    #[schemars(description = "Device index (0-based)")]
-    pub device: u32,
+    /// Track index (0-based) or name.
+    #[schemars(description = "Track index (0-based) or name")]
+    pub track: TrackRef,
+    /// Kind of track the reference points to.
+    #[serde(default)]
+    #[schemars(description = "Track kind: 'regular' (default) or 'return'")]
+    pub kind: TrackKind,
+    /// Device index (0-based) or name.
+    #[schemars(description = "Device index (0-based) or name")]
+    pub device: DeviceRef,
     /// Parameter index (0-based).
     #[schemars(description = "Parameter index (0-based)")]
     pub param: u32,
@@ -692,15 +784,38 @@ pub struct SetDeviceParameterParams {
     pub value: f32,
 }
 
+/// Parameters for `set_device_parameter_by_name` tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct SetDeviceParameterByNameParams {
+    /// Track index (0-based) or name.
+    #[schemars(description = "Track index (0-based) or name")]
+    pub track: TrackRef,
+    /// Device index (0-based) or name.
+    #[schemars(description = "Device index (0-based) or name")]
+    pub device: DeviceRef,
+    /// Parameter name (fuzzy-matched).
+    #[schemars(description = "Parameter name (fuzzy-matched, e.g. 'cutoff', 'Filter Freq')")]
+    pub name: String,
+    /// Value in display units.
+    #[schemars(
+        description = "Value in display units, e.g. '2.5 kHz', '-6 dB', '35%', '120 ms', '1/8', 'Saw'. A bare number is read in the parameter's base unit (Hz, dB, %, ms)"
+    )]
+    pub value: String,
+}
+
 /// Parameters for `set_device_enabled` tool.
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct SetDeviceEnabledParams {
-    /// Track index (0-based).
-    #[schemars(description = "Track index (0-based)")]
-    pub track: u32,
-    /// Device index (0-based).
-    #[schemars(description = "Device index (0-based)")]
-    pub device: u32,
+    /// Track index (0-based) or name.
+    #[schemars(description = "Track index (0-based) or name")]
+    pub track: TrackRef,
+    /// Kind of track the reference points to.
+    #[serde(default)]
+    #[schemars(description = "Track kind: 'regular' (default) or 'return'")]
+    pub kind: TrackKind,
+    /// Device index (0-based) or name.
+    #[schemars(description = "Device index (0-based) or name")]
+    pub device: DeviceRef,
     /// Whether to enable the device.
     #[schemars(description = "Whether to enable the device")]
     pub enabled: bool,
@@ -709,12 +824,16 @@ pub struct SetDeviceEnabledParams {
 /// Parameters for `get_parameter_value_string` tool.
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct GetParameterValueStringParams {
-    /// Track index (0-based).
-    #[schemars(description = "Track index (0-based)")]
-    pub track: u32,
-    /// Device index (0-based).
-    #[schemars(description = "Device index (0-based)")]
-    pub device: u32,
+    /// Track index (0-based) or name.
+    #[schemars(description = "Track index (0-based) or name")]
+    pub track: TrackRef,
+    /// Kind of track the reference points to.
+    #[serde(default)]
+    #[schemars(description = "Track kind: 'regular' (default) or 'return'")]
+    pub kind: TrackKind,
+    /// Device index (0-based) or name.
+    #[schemars(description = "Device index (0-based) or name")]
+    pub device: DeviceRef,
     /// Parameter index (0-based).
     #[schemars(description = "Parameter index (0-based)")]
     pub param: u32,
@@ -723,17 +842,197 @@ pub struct GetParameterValueStringParams {
 /// Parameters for `set_all_device_parameters` tool.
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct SetAllDeviceParametersParams {
-    /// Track index (0-based).
-    #[schemars(description = "Track index (0-based)")]
-    pub track: u32,
-    /// Device index (0-based).
-    #[schemars(description = "Device index (0-based)")]
-    pub device: u32,
+    /// Track index (0-based) or name.
+    #[schemars(description = "Track index (0-based) or name")]
+    pub track: TrackRef,
+    /// Kind of track the reference points to.
+    #[serde(default)]
+    #[schemars(description = "Track kind: 'regular' (default) or 'return'")]
+    pub kind: TrackKind,
+    /// Device index (0-based) or name.
+    #[schemars(description = "Device index (0-based) or name")]
+    pub device: DeviceRef,
     /// Array of parameter values.
     #[schemars(description = "Array of parameter values")]
     pub values: Vec<f32>,
 }
 
+/// Parameters for `save_device_preset` tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct SaveDevicePresetParams {
+    /// Track index (0-based) or name.
+    #[schemars(description = "Track index (0-based) or name")]
+    pub track: TrackRef,
+    /// Device index (0-based) or name.
+    #[schemars(description = "Device index (0-based) or name")]
+    pub device: DeviceRef,
+    /// Preset name.
+    #[schemars(description = "Preset name")]
+    pub name: String,
+    /// Replace an existing preset with the same name.
+    #[schemars(description = "Replace an existing preset with the same name (default false)")]
+    pub overwrite: Option<bool>,
+}
+
+/// Parameters for `load_device_preset` tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct LoadDevicePresetParams {
+    /// Track index (0-based) or name.
+    #[schemars(description = "Track index (0-based) or name")]
+    pub track: TrackRef,
+    /// Device index (0-based) or name.
+    #[schemars(description = "Device index (0-based) or name")]
+    pub device: DeviceRef,
+    /// Preset name.
+    #[schemars(description = "Preset name (fuzzy-matched)")]
+    pub name: String,
+    /// Load even if the preset was saved from a different device class.
+    #[schemars(
+        description = "Load even if the preset was saved from a different device class, mapping parameters by name (default false)"
+    )]
+    pub force: Option<bool>,
+}
+
+/// Parameters for `list_device_presets` tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ListDevicePresetsParams {
+    /// Only list presets for this device class.
+    #[schemars(description = "Only list presets for this device class (e.g. 'Reverb')")]
+    pub class_name: Option<String>,
+}
+
+/// Parameters for `mutate_device` tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct MutateDeviceParams {
+    /// Track index (0-based) or name.
+    #[schemars(description = "Track index (0-based) or name")]
+    pub track: TrackRef,
+    /// Device index (0-based) or name.
+    #[schemars(description = "Device index (0-based) or name")]
+    pub device: DeviceRef,
+    /// Mutation amount (0-1).
+    #[schemars(
+        description = "Mutation amount (0-1): how far continuous parameters move as a fraction of their range, and the chance a quantized parameter changes step (default 0.2)"
+    )]
+    pub amount: Option<f32>,
+    /// Random seed.
+    #[schemars(description = "Seed to reproduce a previous mutation")]
+    pub seed: Option<u64>,
+    /// Number of variations to generate.
+    #[schemars(
+        description = "Number of variations to generate, 1-16 (default 1). One variation is applied to the device; several are only returned"
+    )]
+    pub count: Option<u32>,
+    /// Only mutate these parameters.
+    #[schemars(
+        description = "Only mutate these parameters (exact names, case-insensitive). 'Device On' is only mutated when listed here"
+    )]
+    pub include: Option<Vec<String>>,
+    /// Never mutate these parameters.
+    #[schemars(description = "Never mutate these parameters (e.g. ['Volume'])")]
+    pub exclude: Option<Vec<String>>,
+}
+
+/// A full device state: a saved preset or one value per parameter.
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum DeviceState {
+    /// Raw values for every parameter, in device order.
+    Values(Vec<f32>),
+    /// Name of a preset saved with `save_device_preset`.
+    Preset(String),
+}
+
+/// Parameters for `morph_device` tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct MorphDeviceParams {
+    /// Track index (0-based) or name.
+    #[schemars(description = "Track index (0-based) or name")]
+    pub track: TrackRef,
+    /// Device index (0-based) or name.
+    #[schemars(description = "Device index (0-based) or name")]
+    pub device: DeviceRef,
+    /// Start state.
+    #[schemars(
+        description = "State A: a preset name or an array with one value per parameter. Defaults to the device's current values"
+    )]
+    pub from: Option<DeviceState>,
+    /// End state.
+    #[schemars(description = "State B: a preset name or an array with one value per parameter")]
+    pub to: DeviceState,
+    /// Morph position (0-1).
+    #[schemars(
+        description = "Set the device to this position between A (0) and B (1) immediately. Use either position or duration_beats"
+    )]
+    pub position: Option<f32>,
+    /// Morph duration in beats.
+    #[schemars(
+        description = "Morph from A to B over this many beats (tempo-synced, stoppable with stop_modulation)"
+    )]
+    pub duration_beats: Option<f32>,
+}
+
+// =============================================================================
+// Rack Parameters
+// =============================================================================
+
+/// Parameters for `set_rack_macro` tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct SetRackMacroParams {
+    /// Track index (0-based) or name.
+    #[schemars(description = "Track index (0-based) or name")]
+    pub track: TrackRef,
+    /// Rack device index (0-based) or name.
+    #[schemars(description = "Rack device index (0-based) or name")]
+    pub device: DeviceRef,
+    /// Macro number (1-16) or name.
+    #[serde(rename = "macro")]
+    #[schemars(description = "Macro number (1-16, as shown in Live) or macro name")]
+    pub macro_ref: String,
+    /// Macro value.
+    #[schemars(description = "Macro value (usually 0-127)")]
+    pub value: f32,
+}
+
+/// Parameters for `randomize_rack_macros` tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct RandomizeRackMacrosParams {
+    /// Track index (0-based) or name.
+    #[schemars(description = "Track index (0-based) or name")]
+    pub track: TrackRef,
+    /// Rack device index (0-based) or name.
+    #[schemars(description = "Rack device index (0-based) or name")]
+    pub device: DeviceRef,
+    /// Macros to randomize (numbers or names).
+    #[schemars(
+        description = "Macros to randomize, by number (1-16) or name. Defaults to all renamed (mapped) macros, or all macros if none are renamed"
+    )]
+    pub macros: Option<Vec<String>>,
+    /// Lower bound for random values.
+    #[schemars(description = "Lower bound for random values (defaults to each macro's minimum)")]
+    pub min: Option<f32>,
+    /// Upper bound for random values.
+    #[schemars(description = "Upper bound for random values (defaults to each macro's maximum)")]
+    pub max: Option<f32>,
+    /// Random seed.
+    #[schemars(description = "Random seed, to reproduce a previous result")]
+    pub seed: Option<u64>,
+}
+
+/// Parameters for `store_macro_variation` and `recall_macro_variation` tools.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct MacroVariationParams {
+    /// Track index (0-based) or name.
+    #[schemars(description = "Track index (0-based) or name")]
+    pub track: TrackRef,
+    /// Rack device index (0-based) or name.
+    #[schemars(description = "Rack device index (0-based) or name")]
+    pub device: DeviceRef,
+    /// Variation name.
+    #[schemars(description = "Variation name (e.g. 'verse', 'drop')")]
+    pub name: String,
+}
+
 // =============================================================================
 // Song Parameters
 // =============================================================================
@@ -843,9 +1142,9 @@ pub struct SetCurrentTimeParams {
 /// Parameters for `set_selected_clip` tool.
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct SetSelectedClipParams {
-    /// Track index (0-based).
-    #[schemars(description = "Track index (0-based)")]
-    pub track: u32,
+    /// Track index (0-based) or name.
+    #[schemars(description = "Track index (0-based) or name")]
+    pub track: TrackRef,
     /// Clip slot index (0-based).
     #[schemars(description = "Clip slot index (0-based)")]
     pub slot: u32,
@@ -854,12 +1153,12 @@ pub struct SetSelectedClipParams {
 /// Parameters for `set_selected_device` tool.
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct SetSelectedDeviceParams {
-    /// Track index (0-based).
-    #[schemars(description = "Track index (0-based)")]
-    pub track: u32,
-    /// Device index (0-based).
-    #[schemars(description = "Device index (0-based)")]
-    pub device: u32,
+    /// Track index (0-based) or name.
+    #[schemars(description = "Track index (0-based) or name")]
+    pub track: TrackRef,
+    /// Device index (0-based) or name.
+    #[schemars(description = "Device index (0-based) or name")]
+    pub device: DeviceRef,
 }
 
 // =============================================================================
@@ -897,6 +1196,24 @@ pub struct LoadByNameParams {
     pub name: String,
 }
 
+/// Parameters for browser tools that load a device onto a track.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct LoadDeviceParams {
+    /// Name of the item to load.
+    #[schemars(description = "Name of the item to load")]
+    pub name: String,
+    /// Target track (defaults to the selected track).
+    #[schemars(
+        description = "Track index (0-based) or name to load onto (default: selected track)"
+    )]
+    pub track: Option<TrackRef>,
+    /// Insert position in the device chain.
+    #[schemars(
+        description = "Device index (0-based) to insert at (default: end of the chain). Live loads after the selected device, so 0 only works on an empty track"
+    )]
+    pub position: Option<u32>,
+}
+
 /// Parameters for `load_drum_kit` tool.
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct LoadDrumKitParams {
@@ -942,6 +1259,28 @@ pub struct SearchBrowserParams {
     pub query: String,
 }
 
+/// Parameters for `search_browser_index` tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct SearchBrowserIndexParams {
+    /// Search words.
+    #[schemars(description = "Search words, e.g. 'warm analog pad'")]
+    pub query: String,
+    /// Only items in this category.
+    #[schemars(
+        description = "Only items in this category (sounds, drums, instruments, audio_effects, midi_effects, max_for_live, plugins, clips, samples, packs, user_library)"
+    )]
+    pub category: Option<String>,
+    /// Only loadable items.
+    #[schemars(description = "Only return loadable items (default true)")]
+    pub loadable_only: Option<bool>,
+    /// Only items that create a device.
+    #[schemars(description = "Only return items that create a device (default false)")]
+    pub devices_only: Option<bool>,
+    /// Maximum number of results.
+    #[schemars(description = "Maximum number of results (default 20)")]
+    pub limit: Option<u32>,
+}
+
 /// Parameters for `get_browser_item` tool.
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct GetBrowserItemParams {
@@ -959,17 +1298,159 @@ pub struct LoadUserPresetParams {
     /// Path to the preset in user library.
     #[schemars(description = "Path to the preset in user library")]
     pub path: String,
+    /// Target track (defaults to the selected track).
+    #[schemars(
+        description = "Track index (0-based) or name to load onto (default: selected track)"
+    )]
+    pub track: Option<TrackRef>,
+    /// Insert position in the device chain.
+    #[schemars(
+        description = "Device index (0-based) to insert at (default: end of the chain). Live loads after the selected device, so 0 only works on an empty track"
+    )]
+    pub position: Option<u32>,
+}
+
+/// Parameters for `load_browser_item` tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct LoadBrowserItemParams {
+    /// Full browser path.
+    #[schemars(
+        description = "Full browser path including the category, as returned by search_browser_index (e.g. 'sounds/Pad/Warm Analog Pad.adv')"
+    )]
+    pub path: String,
+    /// Target track (defaults to the selected track).
+    #[schemars(
+        description = "Track index (0-based) or name to load onto (default: selected track)"
+    )]
+    pub track: Option<TrackRef>,
+    /// Clip slot to load a clip or sample into.
+    #[schemars(
+        description = "Clip slot (scene index, 0-based) to load a clip or sample into. Without it, the item is loaded as a device"
+    )]
+    pub slot: Option<u32>,
+    /// Insert position in the device chain.
+    #[schemars(
+        description = "Device index (0-based) to insert at (default: end of the chain). Live loads after the selected device, so 0 only works on an empty track"
+    )]
+    pub position: Option<u32>,
+}
+
+/// Parameters for `audition_samples` tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct AuditionSamplesParams {
+    /// Browser folder whose items are auditioned.
+    #[schemars(
+        description = "Full browser folder path to audition, e.g. 'samples/Drums/Kicks' (use this or query)"
+    )]
+    pub folder: Option<String>,
+    /// Search query for candidates.
+    #[schemars(
+        description = "Search words to pick candidates from the browser index (use this or folder)"
+    )]
+    pub query: Option<String>,
+    /// Category searched by `query`.
+    #[schemars(description = "Category searched by query (default: samples)")]
+    pub category: Option<String>,
+    /// Beats each candidate plays for.
+    #[schemars(
+        description = "Beats to preview each candidate for, at the song tempo (default: 4)"
+    )]
+    pub beats: Option<f32>,
+    /// Maximum number of candidates.
+    #[schemars(description = "Maximum number of candidates (default: 16)")]
+    pub limit: Option<u32>,
+}
+
+/// Parameters for `mark_sample` tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct MarkSampleParams {
+    /// Candidate to mark; the one playing now if omitted.
+    #[schemars(
+        description = "Candidate index (0-based) from audition_samples (default: the one playing now)"
+    )]
+    pub index: Option<u32>,
+    /// Remove the mark instead.
+    #[schemars(description = "Remove the keeper mark instead of setting it (default: false)")]
+    pub unmark: Option<bool>,
+}
+
+/// Parameters for `load_marked_samples` tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct LoadMarkedSamplesParams {
+    /// Target track (defaults to the selected track).
+    #[schemars(description = "Track index (0-based) or name (default: selected track)")]
+    pub track: Option<TrackRef>,
+    /// First clip slot.
+    #[schemars(description = "First clip slot (scene index, 0-based) to fill (default: 0)")]
+    pub start_slot: Option<u32>,
 }
 
 /// Parameters for `hotswap_start` tool.
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct HotswapStartParams {
-    /// Track index (0-based).
-    #[schemars(description = "Track index (0-based)")]
-    pub track: u32,
-    /// Device index (0-based).
-    #[schemars(description = "Device index (0-based)")]
-    pub device: u32,
+    /// Track index (0-based) or name.
+    #[schemars(description = "Track index (0-based) or name")]
+    pub track: TrackRef,
+    /// Device index (0-based) or name.
+    #[schemars(description = "Device index (0-based) or name")]
+    pub device: DeviceRef,
+}
+
+// =============================================================================
+// Modulation Parameters
+// =============================================================================
+
+/// Parameters for `start_modulation` tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct StartModulationParams {
+    /// Kind of parameter to modulate.
+    #[schemars(
+        description = "What to modulate: 'device' (a device parameter), 'volume', 'pan' or 'send'"
+    )]
+    pub target: TargetKind,
+    /// Track index (0-based) or name.
+    #[schemars(description = "Track index (0-based) or name")]
+    pub track: TrackRef,
+    /// Device index (0-based) or name, for device targets.
+    #[schemars(description = "Device index (0-based) or name (required for 'device')")]
+    pub device: Option<DeviceRef>,
+    /// Parameter name or index, for device targets.
+    #[schemars(description = "Parameter name (fuzzy-matched) or index (required for 'device')")]
+    pub parameter: Option<String>,
+    /// Send index (0-based), for send targets.
+    #[schemars(description = "Send index (0=A, 1=B, etc.; required for 'send')")]
+    pub send: Option<u32>,
+    /// Modulation shape.
+    #[schemars(
+        description = "Shape: 'linear' or 'exponential' ramp, or 'sine', 'triangle', 'square', 'sample_and_hold' LFO"
+    )]
+    pub shape: Shape,
+    /// Ramp start value or LFO lower bound.
+    #[schemars(description = "Ramp start value, or LFO lower bound (raw parameter value)")]
+    pub from: f32,
+    /// Ramp end value or LFO upper bound.
+    #[schemars(description = "Ramp end value, or LFO upper bound (raw parameter value)")]
+    pub to: f32,
+    /// LFO period in beats.
+    #[schemars(
+        description = "LFO period in beats (default 1.0, e.g. 0.5 = eighth notes, 4 = one bar in 4/4)"
+    )]
+    pub rate_beats: Option<f32>,
+    /// Duration in beats.
+    #[schemars(
+        description = "Duration in beats (required for ramps; LFOs run until stopped if omitted)"
+    )]
+    pub duration_beats: Option<f32>,
+}
+
+/// Parameters for `stop_modulation` tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct StopModulationParams {
+    /// Modulation id; stops all modulations if omitted.
+    #[schemars(
+        description = "Modulation id from start_modulation (stops all modulations if omitted)"
+    )]
+    pub id: Option<u32>,
 }
 
 // =============================================================================
@@ -991,12 +1472,12 @@ pub struct ShowMessageParams {
 /// Parameters for `map_midi_cc` tool.
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct MapMidiCcParams {
-    /// Track index (0-based).
-    #[schemars(description = "Track index (0-based)")]
-    pub track: u32,
-    /// Device index (0-based).
-    #[schemars(description = "Device index (0-based)")]
-    pub device: u32,
+    /// Track index (0-based) or name.
+    #[schemars(description = "Track index (0-based) or name")]
+    pub track: TrackRef,
+    /// Device index (0-based) or name.
+    #[schemars(description = "Device index (0-based) or name")]
+    pub device: DeviceRef,
     /// Parameter index (0-based).
     #[schemars(description = "Parameter index (0-based)")]
     pub parameter: u32,
@@ -1007,3 +1488,311 @@ pub struct MapMidiCcParams {
     #[schemars(description = "MIDI CC number (0-127)")]
     pub cc: u32,
 }
+
+// =============================================================================
+// Mixer Parameters
+// =============================================================================
+
+/// Parameters for `get_routing_graph` tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct GetRoutingGraphParams {
+    /// Output format.
+    #[schemars(
+        description = "Output format: 'json' (nodes, edges and warnings, default) or 'dot' (Graphviz source)"
+    )]
+    pub format: Option<GraphFormat>,
+}
+
+/// Parameters for `measure_levels` tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct MeasureLevelsParams {
+    /// Tracks to measure.
+    #[schemars(description = "Track indices (0-based) or names to measure (default: all tracks)")]
+    pub tracks: Option<Vec<TrackRef>>,
+    /// Length of the window.
+    #[schemars(description = "Beats to measure for at the song tempo (default: 8)")]
+    pub beats: Option<f32>,
+    /// Sample rate.
+    #[schemars(description = "Meter samples per second, 1-50 (default: 20)")]
+    pub rate: Option<f32>,
+    /// Clip threshold.
+    #[schemars(description = "Meter level (0.0-1.0) counted as near clipping (default: 0.98)")]
+    pub clip_threshold: Option<f32>,
+}
+
+/// Target average level for a track or group, used by `gain_stage`.
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+pub struct GainTarget {
+    /// Track or group track.
+    #[schemars(
+        description = "Track index (0-based) or name. A group track sets the target for every track inside it"
+    )]
+    pub track: TrackRef,
+    /// Target average level.
+    #[schemars(description = "Target average (RMS) level in dB, e.g. -18")]
+    pub target_db: f32,
+}
+
+/// Parameters for `gain_stage` tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct GainStageParams {
+    /// Targets per track or group.
+    #[schemars(
+        description = "Target levels per track or group; a track's own target wins over its group's"
+    )]
+    pub targets: Vec<GainTarget>,
+    /// Allowed distance from the target.
+    #[schemars(description = "Tolerance in dB (default: 1.0)")]
+    pub tolerance_db: Option<f32>,
+    /// Highest fader value gain staging may raise a track to.
+    #[schemars(
+        description = "Highest volume a track may be raised to, as a fader position or in dB (default: \"0 dB\", fader 0.85)"
+    )]
+    pub ceiling: Option<Volume>,
+    /// Beats measured per pass.
+    #[schemars(description = "Beats to measure for on each pass (default: 4)")]
+    pub beats: Option<f32>,
+    /// Maximum number of adjustments.
+    #[schemars(description = "Maximum number of adjust-and-measure passes (default: 4)")]
+    pub max_passes: Option<u32>,
+}
+
+/// A send level to set, used by `set_mixer`.
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+pub struct SendChange {
+    /// Return track the send feeds.
+    #[schemars(description = "Return track index (0-based, 0 = send A) or name")]
+    pub send: TrackRef,
+    /// New send level.
+    #[schemars(description = "Send level (0.0 to 1.0)")]
+    pub level: f32,
+}
+
+/// Changes to one track, used by `set_mixer`.
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+pub struct MixerChange {
+    /// Track index (0-based) or name.
+    #[schemars(description = "Track index (0-based) or name")]
+    pub track: TrackRef,
+    /// Kind of track the reference points to.
+    #[serde(default)]
+    #[schemars(description = "Track kind: 'regular' (default) or 'return'")]
+    pub kind: TrackKind,
+    /// New volume.
+    #[schemars(
+        description = "Volume: fader position (0.0 to 1.0, 0.85 = 0 dB) or a level in dB such as \"-6 dB\""
+    )]
+    pub volume: Option<Volume>,
+    /// New pan.
+    #[schemars(description = "Pan: -1.0 (left) to 1.0 (right), or \"25L\", \"C\", \"50R\"")]
+    pub pan: Option<Pan>,
+    /// New send levels.
+    #[schemars(description = "Send levels by return track")]
+    pub sends: Option<Vec<SendChange>>,
+    /// Mute state.
+    #[schemars(description = "Mute (true) or unmute (false)")]
+    pub mute: Option<bool>,
+    /// Solo state.
+    #[schemars(description = "Solo (true) or unsolo (false)")]
+    pub solo: Option<bool>,
+    /// Arm state.
+    #[schemars(description = "Arm (true) or disarm (false) for recording; regular tracks only")]
+    pub arm: Option<bool>,
+    /// New color.
+    #[schemars(description = "RGB color as integer")]
+    pub color: Option<i32>,
+    /// New name.
+    #[schemars(description = "New track name")]
+    pub name: Option<String>,
+}
+
+/// Parameters for `set_mixer` tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct SetMixerParams {
+    /// Per-track changes.
+    #[schemars(description = "Changes to apply, one entry per track")]
+    pub changes: Vec<MixerChange>,
+}
+
+/// Parameters for `solo_exclusive` tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct SoloExclusiveParams {
+    /// Tracks to solo.
+    #[schemars(
+        description = "Track indices (0-based) or names to solo; all other tracks are unsoloed"
+    )]
+    pub tracks: Vec<TrackRef>,
+}
+
+/// Parameters for `mute_matching` tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct MuteMatchingParams {
+    /// Name pattern.
+    #[schemars(
+        description = "Track name pattern, case-insensitive: '*' matches any text and '?' one character; without wildcards, names containing the text match"
+    )]
+    pub pattern: Option<String>,
+    /// Track color.
+    #[schemars(description = "Track color as RGB integer (as returned by get_track_color)")]
+    pub color: Option<i32>,
+    /// Invert the match.
+    #[schemars(description = "Mute every track that does NOT match instead (default: false)")]
+    pub except: Option<bool>,
+}
+
+/// Parameters for `save_mix_state` tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct SaveMixStateParams {
+    /// Label for the saved state.
+    #[schemars(description = "Label to recognise the state by in list_mix_states")]
+    pub label: Option<String>,
+}
+
+// =============================================================================
+// Track Template Parameters
+// =============================================================================
+
+/// Parameters for `create_track_from_template` tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct CreateTrackFromTemplateParams {
+    /// Saved template name.
+    #[schemars(
+        description = "Name of a saved template (fuzzy-matched, see list_track_templates). Give this or recipe"
+    )]
+    pub template: Option<String>,
+    /// Inline recipe.
+    #[schemars(description = "Inline recipe to use instead of a saved template")]
+    pub recipe: Option<TrackTemplate>,
+    /// Index to insert the track at.
+    #[schemars(description = "Index to insert the new track at (default: after the last track)")]
+    pub index: Option<i32>,
+    /// Track name, overriding the template's.
+    #[schemars(description = "Name for the new track, overriding the template's name")]
+    pub name: Option<String>,
+    /// Save the recipe as a template.
+    #[schemars(description = "Also save the recipe as a template under this name")]
+    pub save_as: Option<String>,
+    /// Replace an existing template when saving.
+    #[schemars(description = "Replace an existing template with the save_as name (default false)")]
+    pub overwrite: Option<bool>,
+}
+
+// =============================================================================
+// Recording Parameters
+// =============================================================================
+
+/// Parameters for `resample_track` tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ResampleTrackParams {
+    /// Track to resample.
+    #[schemars(description = "Track index (0-based) or name to resample")]
+    pub track: TrackRef,
+    /// Source clip slot to play while recording.
+    #[schemars(
+        description = "Clip slot on the source track to play while recording; its length sets the recording length unless bars is given"
+    )]
+    pub slot: Option<u32>,
+    /// Recording length in bars.
+    #[schemars(description = "Recording length in bars (default: the source clip's length)")]
+    pub bars: Option<u32>,
+    /// Clip slot to record into.
+    #[schemars(
+        description = "Clip slot on the new audio track to record into (default: the source slot, or 0)"
+    )]
+    pub target_slot: Option<u32>,
+    /// Input channel on the new track.
+    #[schemars(
+        description = "Where to tap the source: 'Pre FX', 'Post FX' (default) or 'Post Mixer'"
+    )]
+    pub tap: Option<String>,
+    /// Name for the new audio track.
+    #[schemars(description = "Name for the new audio track (default: '<source> (Resampled)')")]
+    pub name: Option<String>,
+    /// Mute the source afterwards.
+    #[schemars(description = "Mute the source track after recording (default: true)")]
+    pub mute_source: Option<bool>,
+}
+
+/// Parameters for `restore_resampled_track` tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct RestoreResampledTrackParams {
+    /// Original source track.
+    #[schemars(description = "Source track index (0-based) or name to unmute")]
+    pub track: TrackRef,
+    /// The resampled audio track.
+    #[schemars(description = "Resampled audio track index (0-based) or name")]
+    pub resampled: TrackRef,
+    /// Delete the resampled track.
+    #[schemars(
+        description = "Delete the resampled audio track instead of muting it (default: false)"
+    )]
+    pub delete: Option<bool>,
+}
+
+/// Parameters for `record_take` tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct RecordTakeParams {
+    /// Track to record on.
+    #[schemars(description = "Track index (0-based) or name to record on")]
+    pub track: TrackRef,
+    /// Length of each take in bars.
+    #[schemars(description = "Length of each take in bars")]
+    pub bars: u32,
+    /// First clip slot.
+    #[schemars(
+        description = "Clip slot for the first take (default: the first empty slot); later takes go into the following empty slots"
+    )]
+    pub slot: Option<u32>,
+    /// Number of takes.
+    #[schemars(description = "Number of takes to record back to back (default: 1)")]
+    pub takes: Option<u32>,
+    /// Count-in in bars.
+    #[schemars(
+        description = "Bars of metronome count-in before the first take (default: 0, start on the next bar)"
+    )]
+    pub count_in: Option<u32>,
+    /// Base name for the takes.
+    #[schemars(
+        description = "Base name for the takes, numbered as '<name> Take N' (default: the track name)"
+    )]
+    pub name: Option<String>,
+}
+
+// =============================================================================
+// Scheduler Parameters
+// =============================================================================
+
+/// Parameters for `schedule_action` tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ScheduleActionParams {
+    /// Tool to call.
+    #[schemars(description = "Name of the tool to call, e.g. 'fire_scene' or 'set_tempo'")]
+    pub tool: String,
+    /// Arguments for the tool.
+    #[schemars(description = "Arguments for the tool, as it would be called directly")]
+    pub arguments: Option<serde_json::Map<String, serde_json::Value>>,
+    /// Bar to fire at.
+    #[schemars(description = "Bar to fire at, counted from 1 as in Live's arrangement")]
+    pub bar: Option<u32>,
+    /// Beat within the bar.
+    #[schemars(
+        description = "Beat within the bar, counted from 1; may be fractional, e.g. 2.5 (default: 1, requires bar)"
+    )]
+    pub beat: Option<f64>,
+    /// Absolute song position in beats.
+    #[schemars(description = "Song position in beats (0-based) to fire at, instead of bar")]
+    pub song_beat: Option<f64>,
+    /// Fire on the next quantization boundary.
+    #[schemars(
+        description = "Fire on the next boundary of this grid: 'global' (the song's clip trigger quantization), 'beat', 'bar', '2 bars', '4 bars', '8 bars', '1/2', '1/4', '1/8', '1/16' or '1/32'"
+    )]
+    pub quantize: Option<String>,
+}
+
+/// Parameters for `cancel_scheduled_action` tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct CancelScheduledActionParams {
+    /// Action id to cancel.
+    #[schemars(description = "Scheduled action id to cancel (omit to cancel all pending actions)")]
+    pub id: Option<u32>,
+}
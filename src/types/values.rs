@@ -0,0 +1,77 @@
+//! Option enums accepted by tool parameters.
+//!
+//! These live with the parameter types rather than in the modules that act on
+//! them, so `tool_params` does not depend on feature modules.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Shape of a modulation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Shape {
+    /// Straight line from `from` to `to` over the duration.
+    Linear,
+    /// Exponential curve from `from` to `to`: slow start, fast finish.
+    Exponential,
+    /// Sine LFO between `from` and `to`.
+    Sine,
+    /// Triangle LFO between `from` and `to`.
+    Triangle,
+    /// Square LFO alternating between `from` and `to`.
+    Square,
+    /// Random value between `from` and `to`, held for each LFO period.
+    SampleAndHold,
+}
+
+impl Shape {
+    /// Whether this shape is a one-shot ramp (as opposed to a repeating LFO).
+    pub const fn is_ramp(self) -> bool {
+        matches!(self, Self::Linear | Self::Exponential)
+    }
+}
+
+/// Kind of parameter a modulation drives.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TargetKind {
+    /// A device parameter.
+    Device,
+    /// Track volume (0.0-1.0).
+    Volume,
+    /// Track panning (-1.0 to 1.0).
+    Pan,
+    /// Track send level (0.0-1.0).
+    Send,
+}
+
+/// Output format of `get_routing_graph`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum GraphFormat {
+    /// JSON nodes, edges and warnings.
+    #[default]
+    Json,
+    /// Graphviz DOT source.
+    Dot,
+}
+
+/// A track volume: a fader position or a level in dB.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum Volume {
+    /// Fader position (0.0 to 1.0, 0.85 = 0 dB).
+    Fader(f32),
+    /// Level in dB, e.g. `"-6 dB"` or `"-inf"`.
+    Db(String),
+}
+
+/// A pan position: a number or a label like `"25L"`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum Pan {
+    /// Pan value (-1.0 left to 1.0 right).
+    Value(f32),
+    /// Pan as Live shows it, e.g. `"25L"`, `"C"` or `"50R"`.
+    Label(String),
+}
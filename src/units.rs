@@ -0,0 +1,167 @@
+//! Parsing of human-readable parameter values.
+//!
+//! Ableton shows parameter values as display strings such as `"2.50 kHz"`,
+//! `"-6.0 dB"`, `"35 %"` or `"1/8"`. The same syntax is accepted from tool
+//! callers, so a requested value and a value read back from Live can be
+//! compared in a common base unit.
+
+/// Physical unit of a display value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Unit {
+    /// No unit (plain numbers, note divisions such as `1/8`).
+    None,
+    /// Frequency, in hertz.
+    Hertz,
+    /// Level, in decibels.
+    Decibels,
+    /// Percentage (0-100).
+    Percent,
+    /// Time, in milliseconds.
+    Milliseconds,
+    /// Pitch, in semitones.
+    Semitones,
+    /// Pitch, in cents.
+    Cents,
+}
+
+/// A numeric value in a base unit.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Quantity {
+    /// Value expressed in the base unit of `unit` (Hz, dB, %, ms, st, ct).
+    pub value: f64,
+    /// Unit of the value.
+    pub unit: Unit,
+}
+
+/// Map a unit suffix to its unit and the multiplier into the base unit.
+fn parse_unit(suffix: &str) -> Option<(Unit, f64)> {
+    let unit = match suffix.trim().to_lowercase().as_str() {
+        "" => (Unit::None, 1.0),
+        "hz" => (Unit::Hertz, 1.0),
+        "khz" => (Unit::Hertz, 1000.0),
+        "db" => (Unit::Decibels, 1.0),
+        "%" => (Unit::Percent, 1.0),
+        "ms" => (Unit::Milliseconds, 1.0),
+        "s" | "sec" => (Unit::Milliseconds, 1000.0),
+        "st" | "semitones" => (Unit::Semitones, 1.0),
+        "ct" | "cents" => (Unit::Cents, 1.0),
+        _ => return None,
+    };
+    Some(unit)
+}
+
+/// Parse a display string such as `"2.5 kHz"`, `"-inf dB"`, `"35%"` or `"1/8"`.
+///
+/// Returns `None` for non-numeric values like `"Sine"` or `"Off"`.
+pub fn parse_quantity(text: &str) -> Option<Quantity> {
+    let text = text.trim();
+
+    if let Some((num, den)) = text.split_once('/') {
+        let num: f64 = num.trim().parse().ok()?;
+        let den: f64 = den.trim().parse().ok()?;
+        if den == 0.0 {
+            return None;
+        }
+        return Some(Quantity {
+            value: num / den,
+            unit: Unit::None,
+        });
+    }
+
+    let split = text
+        .char_indices()
+        .find(|&(i, c)| !(c.is_ascii_digit() || c == '.' || (i == 0 && (c == '-' || c == '+'))))
+        .map_or(text.len(), |(i, _)| i);
+    let (number, suffix) = text.split_at(split);
+
+    // Live shows silence as "-inf dB"
+    if let Some(rest) = suffix
+        .strip_prefix("inf")
+        .filter(|_| matches!(number, "" | "-" | "+"))
+    {
+        let sign = if number == "-" { -1.0 } else { 1.0 };
+        let (unit, _) = parse_unit(rest)?;
+        return Some(Quantity {
+            value: sign * f64::INFINITY,
+            unit,
+        });
+    }
+
+    let value: f64 = number.parse().ok()?;
+    let (unit, scale) = parse_unit(suffix)?;
+    Some(Quantity {
+        value: value * scale,
+        unit,
+    })
+}
+
+/// Check whether two display strings name the same value, ignoring case and spacing.
+pub fn same_display(a: &str, b: &str) -> bool {
+    let squash = |s: &str| {
+        s.chars()
+            .filter(|c| !c.is_whitespace())
+            .flat_map(char::to_lowercase)
+            .collect::<String>()
+    };
+    squash(a) == squash(b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const fn quantity(value: f64, unit: Unit) -> Quantity {
+        Quantity { value, unit }
+    }
+
+    /// Common Live display strings parse into base units.
+    #[test]
+    fn parses_display_strings() {
+        assert_eq!(
+            parse_quantity("2.5 kHz"),
+            Some(quantity(2500.0, Unit::Hertz))
+        );
+        assert_eq!(parse_quantity("440Hz"), Some(quantity(440.0, Unit::Hertz)));
+        assert_eq!(
+            parse_quantity("-6 dB"),
+            Some(quantity(-6.0, Unit::Decibels))
+        );
+        assert_eq!(parse_quantity("35%"), Some(quantity(35.0, Unit::Percent)));
+        assert_eq!(
+            parse_quantity("1.00 s"),
+            Some(quantity(1000.0, Unit::Milliseconds))
+        );
+        assert_eq!(
+            parse_quantity("+12 st"),
+            Some(quantity(12.0, Unit::Semitones))
+        );
+        assert_eq!(parse_quantity("1/8"), Some(quantity(0.125, Unit::None)));
+        assert_eq!(parse_quantity("0.73"), Some(quantity(0.73, Unit::None)));
+    }
+
+    /// Silence is shown as negative infinity.
+    #[test]
+    fn parses_negative_infinity() {
+        assert_eq!(
+            parse_quantity("-inf dB"),
+            Some(quantity(f64::NEG_INFINITY, Unit::Decibels))
+        );
+    }
+
+    /// Text values and unknown units are not quantities.
+    #[test]
+    fn rejects_non_numeric_values() {
+        assert_eq!(parse_quantity("Sine"), None);
+        assert_eq!(parse_quantity("Off"), None);
+        assert_eq!(parse_quantity("50 L"), None);
+        assert_eq!(parse_quantity("1/0"), None);
+    }
+
+    /// Display comparison ignores case and whitespace.
+    #[test]
+    fn compares_display_strings() {
+        assert!(same_display("Saw D", "saw d"));
+        assert!(same_display("1/8 T", "1/8T"));
+        assert!(!same_display("1/8", "1/16"));
+    }
+}
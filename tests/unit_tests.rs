@@ -1,6 +1,7 @@
 //! Unit tests with snapshot testing (insta) and parameterized tests (rstest).
 
 use insta::{assert_json_snapshot, assert_snapshot};
+use remix_mcp::types::TrackAddr;
 use rosc::{OscMessage, OscType};
 use rstest::rstest;
 
@@ -254,3 +255,26 @@ fn test_osc_address_format(#[case] address: &str) {
     assert!(!address.contains(' '));
     assert!(!address.ends_with('/'));
 }
+
+// ============================================================================
+// Track Kind Addressing Tests
+// ============================================================================
+
+/// Both kinds use the track and device endpoints; return tracks lead with
+/// the `return_track` selector.
+#[rstest]
+#[case::regular(TrackAddr::regular(2), vec![OscType::Int(2)])]
+#[case::return_track(
+    TrackAddr::return_track(1),
+    vec![OscType::String("return_track".to_string()), OscType::Int(1)]
+)]
+fn test_track_kind_addresses(#[case] track: TrackAddr, #[case] leading: Vec<OscType>) {
+    let (addr, args) = track.track_message("set/volume");
+    assert_eq!(addr, "/live/track/set/volume");
+    assert_eq!(args, leading);
+
+    let (addr, args) = track.device_message(3, "get/name");
+    assert_eq!(addr, "/live/device/get/name");
+    assert_eq!(args[..leading.len()], leading[..]);
+    assert_eq!(args.last(), Some(&OscType::Int(3)));
+}